@@ -3,12 +3,12 @@ use clap::Parser;
 #[derive(Parser)]
 #[clap()]
 struct Args {
-    #[clap(about = "Serial port to use")]
+    #[clap(help = "Serial port to use")]
     port: String,
-    #[clap(about = "Position to move to")]
+    #[clap(help = "Position to move to")]
     position: f32,
     #[clap(
-        about = "ID of the motor you want to move. Default BROADCAST",
+        help = "ID of the motor you want to move. Default BROADCAST",
         long = "id",
         default_value = "254"
     )]
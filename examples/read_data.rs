@@ -3,7 +3,7 @@ use clap::Parser;
 #[derive(Parser)]
 #[clap()]
 struct Args {
-    #[clap(about = "Serial port to use")]
+    #[clap(help = "Serial port to use")]
     port: String,
 }
 
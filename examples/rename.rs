@@ -5,9 +5,9 @@ use std::time::Duration;
 #[derive(Parser)]
 #[clap()]
 struct Args {
-    #[clap(about = "Serial port to use")]
+    #[clap(help = "Serial port to use")]
     port: String,
-    #[clap(about = "ID you want to give to the connected servo", long = "id")]
+    #[clap(help = "ID you want to give to the connected servo", long = "id")]
     id: u8,
 }
 
@@ -9,7 +9,7 @@ use std::time::Duration;
 #[derive(Parser)]
 #[clap()]
 struct Args {
-    #[clap(about = "Serial port to use")]
+    #[clap(help = "Serial port to use")]
     port: String,
 }
 
@@ -4,10 +4,10 @@ use std::io::{self, BufRead};
 #[derive(Parser)]
 #[clap()]
 struct Args {
-    #[clap(about = "Serial port to use")]
+    #[clap(help = "Serial port to use")]
     port: String,
     #[clap(
-        about = "ID of the motor you want to move. Default BROADCAST",
+        help = "ID of the motor you want to move. Default BROADCAST",
         long = "id",
         default_value = "254"
     )]
@@ -0,0 +1,329 @@
+//! Synchronous version of [LSSDriver](crate::LSSDriver)
+//!
+//! Wraps the async driver and runs each call to completion on an internal single threaded runtime.
+//! Useful for scripts and command line tools that don't otherwise need an async runtime.
+//! Must not be used from within an async runtime.
+//!
+//! Requires the `blocking` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use lss_driver::blocking::LSSDriver;
+//! let mut driver = LSSDriver::new("COM14").unwrap();
+//! driver.move_to_position(5, 90.0).unwrap();
+//! let voltage = driver.query_voltage(5).unwrap();
+//! ```
+
+use crate::driver::message_types::*;
+use futures::Stream;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::broadcast;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+macro_rules! blocking_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Blocking version of [", stringify!($name), "](crate::LSSDriver::", stringify!($name), ")")]
+            pub fn $name(&mut self, $($arg: $ty),*) -> $ret {
+                self.runtime.block_on(self.driver.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Blocking driver for the LSS servo
+///
+/// Has the same methods as [LSSDriver](crate::LSSDriver) except they block until done.
+pub struct LSSDriver {
+    // declared first so that the transport is dropped while the runtime is still alive
+    driver: crate::LSSDriver,
+    runtime: Runtime,
+}
+
+fn new_runtime() -> DriverResult<Runtime> {
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|_| LssDriverError::FailedOpeningSerialPort)
+}
+
+impl LSSDriver {
+    /// Create new driver on a serial port with default settings
+    ///
+    /// Default baud_rate is 115200
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Port to use. e.g. COM1 or /dev/ttyACM0
+    pub fn new(port: &str) -> DriverResult<LSSDriver> {
+        LSSDriver::with_baud_rate(port, 115200)
+    }
+
+    /// Create new driver on a serial port with custom baud rate
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Port to use. e.g. COM1 or /dev/ttyACM0
+    /// * `baud_rate` - Baudrate. e.g. 115200
+    pub fn with_baud_rate(port: &str, baud_rate: u32) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        // serial port registers with the reactor of the current runtime
+        let driver = {
+            let _guard = runtime.enter();
+            crate::LSSDriver::with_baud_rate(port, baud_rate)?
+        };
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [with_rs485](crate::LSSDriver::with_rs485)
+    pub fn with_rs485(port: &str, baud_rate: u32, rs485: Rs485Config) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = {
+            let _guard = runtime.enter();
+            crate::LSSDriver::with_rs485(port, baud_rate, rs485)?
+        };
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [with_options](crate::LSSDriver::with_options)
+    pub fn with_options(port: &str, options: crate::SerialOptions) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = {
+            let _guard = runtime.enter();
+            crate::LSSDriver::with_options(port, options)?
+        };
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [connect_tcp](crate::LSSDriver::connect_tcp)
+    #[cfg(feature = "tcp")]
+    pub fn connect_tcp<A: tokio::net::ToSocketAddrs>(address: A) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = runtime.block_on(crate::LSSDriver::connect_tcp(address))?;
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [connect_udp](crate::LSSDriver::connect_udp)
+    #[cfg(feature = "udp")]
+    pub fn connect_udp<A: tokio::net::ToSocketAddrs>(address: A) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = runtime.block_on(crate::LSSDriver::connect_udp(address))?;
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [connect_rfc2217](crate::LSSDriver::connect_rfc2217)
+    #[cfg(feature = "rfc2217")]
+    pub fn connect_rfc2217<A: tokio::net::ToSocketAddrs>(
+        address: A,
+        baud_rate: u32,
+    ) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = runtime.block_on(crate::LSSDriver::connect_rfc2217(address, baud_rate))?;
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [connect_websocket](crate::LSSDriver::connect_websocket)
+    #[cfg(feature = "websocket")]
+    pub fn connect_websocket(url: &str) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = runtime.block_on(crate::LSSDriver::connect_websocket(url))?;
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Blocking version of [connect_unix](crate::LSSDriver::connect_unix)
+    #[cfg(all(unix, feature = "uds"))]
+    pub fn connect_unix<P: AsRef<std::path::Path>>(path: P) -> DriverResult<LSSDriver> {
+        let runtime = new_runtime()?;
+        let driver = runtime.block_on(crate::LSSDriver::connect_unix(path))?;
+        Ok(LSSDriver { driver, runtime })
+    }
+
+    /// Same as [set_auto_reconnect](crate::LSSDriver::set_auto_reconnect)
+    pub fn set_auto_reconnect(&mut self, policy: Option<RetryPolicy>) {
+        self.driver.set_auto_reconnect(policy)
+    }
+
+    /// Same as [connection_events](crate::LSSDriver::connection_events)
+    ///
+    /// Use `blocking_recv` on the receiver to wait for events.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.driver.connection_events()
+    }
+
+    /// Same as [events](crate::LSSDriver::events)
+    ///
+    /// Use `futures::executor::block_on_stream` to iterate over events.
+    pub fn events(&self) -> impl Stream<Item = DriverEvent> {
+        self.driver.events()
+    }
+
+    /// Same as [bus_metrics](crate::LSSDriver::bus_metrics)
+    pub fn bus_metrics(&self) -> BusMetrics {
+        self.driver.bus_metrics()
+    }
+
+    /// Same as [last_known](crate::LSSDriver::last_known)
+    pub fn last_known(&self, id: u8) -> LastKnown {
+        self.driver.last_known(id)
+    }
+
+    /// Same as [set_verify_writes](crate::LSSDriver::set_verify_writes)
+    pub fn set_verify_writes(&mut self, verify_writes: bool) {
+        self.driver.set_verify_writes(verify_writes)
+    }
+
+    /// Same as [set_retry_policy](crate::LSSDriver::set_retry_policy)
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.driver.set_retry_policy(retry_policy)
+    }
+
+    /// Same as [set_pacing](crate::LSSDriver::set_pacing)
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.driver.set_pacing(pacing)
+    }
+
+    /// Same as [set_receive_timeout](crate::LSSDriver::set_receive_timeout)
+    pub fn set_receive_timeout(&mut self, timeout: Duration) -> Duration {
+        self.driver.set_receive_timeout(timeout)
+    }
+
+    /// Same as [receive_timeout](crate::LSSDriver::receive_timeout)
+    pub fn receive_timeout(&self) -> Duration {
+        self.driver.receive_timeout()
+    }
+
+    /// Same as [set_position_filter](crate::LSSDriver::set_position_filter)
+    pub fn set_position_filter(&mut self, alpha: f32) {
+        self.driver.set_position_filter(alpha)
+    }
+
+    /// Blocking version of [close](crate::LSSDriver::close)
+    pub fn close(self) -> DriverResult<()> {
+        self.runtime.block_on(self.driver.close())
+    }
+
+    blocking_methods! {
+        reconnect() -> DriverResult<()>;
+        flush() -> DriverResult<()>;
+        reset(id: u8) -> DriverResult<()>;
+        reset_and_wait(id: u8, timeout: Duration) -> DriverResult<()>;
+        restore_factory_defaults(id: u8, timeout: Duration) -> DriverResult<()>;
+        query_id(id: u8) -> DriverResult<u8>;
+        detect_id_conflicts() -> DriverResult<IdConflictReport>;
+        detect_id_conflicts_in(ids: &[u8]) -> DriverResult<IdConflictReport>;
+        query_broadcast(cmd: &str) -> DriverResult<Vec<(u8, i32)>>;
+        send_raw(id: u8, cmd: &str, value: Option<i32>) -> DriverResult<()>;
+        query_raw(id: u8, cmd: &str) -> DriverResult<String>;
+        set_id(id: u8, new_id: u8) -> DriverResult<()>;
+        set_baud_rate(id: u8, baud_rate: BaudRate) -> DriverResult<()>;
+        change_baud_rate(id: u8, baud_rate: BaudRate) -> DriverResult<()>;
+        query_baud_rate(id: u8) -> DriverResult<BaudRate>;
+        set_color(id: u8, color: LedColor) -> DriverResult<()>;
+        configure_color(id: u8, color: LedColor) -> DriverResult<()>;
+        query_color(id: u8) -> DriverResult<LedColor>;
+        identify(id: u8, duration: Duration) -> DriverResult<()>;
+        move_to_position(id: u8, position: f32) -> DriverResult<()>;
+        move_to_position_in_mode(id: u8, position: f32, expected_mode: ExpectedMode) -> DriverResult<()>;
+        move_to_position_with_modifier(id: u8, position: f32, modifier: CommandModifier) -> DriverResult<()>;
+        move_to_position_with_speed(id: u8, position: f32, speed: f32) -> DriverResult<()>;
+        move_to_position_in(id: u8, position: f32, duration: Duration) -> DriverResult<()>;
+        move_to_position_with_current_hold(id: u8, position: f32, current: u32) -> DriverResult<()>;
+        move_to_position_with_current_limp(id: u8, position: f32, current: u32) -> DriverResult<()>;
+        move_to_position_with_modifiers(id: u8, position: f32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        execute(id: u8, command: MoveCommand) -> DriverResult<()>;
+        configure_first_position(id: u8, position: Option<f32>) -> DriverResult<()>;
+        query_first_position(id: u8) -> DriverResult<Option<f32>>;
+        move_relative(id: u8, delta: f32) -> DriverResult<()>;
+        move_relative_with_modifiers(id: u8, delta: f32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        set_target_position(id: u8, position: f32) -> DriverResult<()>;
+        query_position(id: u8) -> DriverResult<f32>;
+        query_filtered_position(id: u8) -> DriverResult<f32>;
+        query_target_position(id: u8) -> DriverResult<f32>;
+        query_position_error(id: u8) -> DriverResult<f32>;
+        set_rotation_speed(id: u8, speed: f32) -> DriverResult<()>;
+        query_rotation_speed(id: u8) -> DriverResult<f32>;
+        set_rotation_speed_dps(id: u8, speed: f32) -> DriverResult<()>;
+        set_rotation_speed_rpm(id: u8, speed: f32) -> DriverResult<()>;
+        query_rotation_speed_rpm(id: u8) -> DriverResult<f32>;
+        query_speed_dps(id: u8) -> DriverResult<f32>;
+        query_speed_rpm(id: u8) -> DriverResult<f32>;
+        query_status(id: u8) -> DriverResult<MotorStatus>;
+        query_status_flags(id: u8) -> DriverResult<StatusFlags>;
+        is_moving(id: u8) -> DriverResult<bool>;
+        wait_until_stopped(id: u8, timeout: Duration) -> DriverResult<()>;
+        query_safety_status(id: u8) -> DriverResult<SafeModeStatus>;
+        set_gyre_direction(id: u8, direction: Direction) -> DriverResult<()>;
+        configure_gyre_direction(id: u8, direction: Direction) -> DriverResult<()>;
+        query_gyre_direction(id: u8) -> DriverResult<Direction>;
+        set_motion_profile(id: u8, motion_profile: bool) -> DriverResult<()>;
+        configure_motion_profile(id: u8, motion_profile: bool) -> DriverResult<()>;
+        query_motion_profile(id: u8) -> DriverResult<bool>;
+        set_filter_position_count(id: u8, filter_position_count: u8) -> DriverResult<()>;
+        configure_filter_position_count(id: u8, filter_position_count: u8) -> DriverResult<()>;
+        query_filter_position_count(id: u8) -> DriverResult<u8>;
+        set_angular_stiffness(id: u8, angular_stiffness: i32) -> DriverResult<()>;
+        configure_angular_stiffness(id: u8, angular_stiffness: i32) -> DriverResult<()>;
+        query_angular_stiffness(id: u8) -> DriverResult<i32>;
+        query_angular_stiffness_in_scope(id: u8, scope: QueryScope) -> DriverResult<i32>;
+        set_angular_holding_stiffness(id: u8, angular_holding: i32) -> DriverResult<()>;
+        configure_angular_holding_stiffness(id: u8, angular_holding: i32) -> DriverResult<()>;
+        query_angular_holding_stiffness(id: u8) -> DriverResult<i32>;
+        query_angular_holding_stiffness_in_scope(id: u8, scope: QueryScope) -> DriverResult<i32>;
+        set_angular_acceleration(id: u8, angular_acceleration: i32) -> DriverResult<()>;
+        configure_angular_acceleration(id: u8, angular_acceleration: i32) -> DriverResult<()>;
+        query_angular_acceleration(id: u8) -> DriverResult<i32>;
+        set_angular_deceleration(id: u8, angular_deceleration: i32) -> DriverResult<()>;
+        configure_angular_deceleration(id: u8, angular_deceleration: i32) -> DriverResult<()>;
+        query_angular_deceleration(id: u8) -> DriverResult<i32>;
+        set_maximum_motor_duty(id: u8, maximum_motor_duty: i32) -> DriverResult<()>;
+        configure_maximum_motor_duty(id: u8, maximum_motor_duty: i32) -> DriverResult<()>;
+        query_maximum_motor_duty(id: u8) -> DriverResult<i32>;
+        set_maximum_speed(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed(id: u8) -> DriverResult<f32>;
+        query_maximum_speed_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        set_maximum_speed_rpm(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed_rpm(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed_rpm(id: u8) -> DriverResult<f32>;
+        limp(id: u8) -> DriverResult<()>;
+        limp_all() -> DriverResult<()>;
+        halt_hold(id: u8) -> DriverResult<()>;
+        halt_hold_all() -> DriverResult<()>;
+        try_limp_all(ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>>;
+        try_halt_hold_all(ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>>;
+        query_voltage(id: u8) -> DriverResult<f32>;
+        query_temperature(id: u8) -> DriverResult<f32>;
+        query_current(id: u8) -> DriverResult<f32>;
+        query_positions(ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>>;
+        query_voltages(ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>>;
+        query_temperatures(ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>>;
+        query_currents(ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>>;
+        query_statuses(ids: &[u8]) -> DriverResult<Vec<DriverResult<MotorStatus>>>;
+        query_telemetry(id: u8) -> DriverResult<ServoTelemetry>;
+        query_model(id: u8) -> DriverResult<Model>;
+        query_firmware_version(id: u8) -> DriverResult<FirmwareVersion>;
+        query_serial_number(id: u8) -> DriverResult<String>;
+        set_led_blinking(id: u8, blinking_mode: Vec<LedBlinking>) -> DriverResult<()>;
+        query_led_blinking(id: u8) -> DriverResult<Vec<LedBlinking>>;
+        query_origin_offset(id: u8) -> DriverResult<f32>;
+        query_origin_offset_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        query_angular_range(id: u8) -> DriverResult<f32>;
+        query_angular_range_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        set_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        set_session_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        configure_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        query_pwm_position(id: u8) -> DriverResult<i32>;
+        set_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        set_session_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        configure_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        move_to_pwm_position(id: u8, position: i32) -> DriverResult<()>;
+        move_to_pulse(id: u8, position: i32) -> DriverResult<()>;
+        query_pulse_position(id: u8) -> DriverResult<i32>;
+        move_to_pwm_position_with_modifier(id: u8, position: i32, modifier: CommandModifier) -> DriverResult<()>;
+        move_to_pwm_position_with_modifiers(id: u8, position: i32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        auto_find_limits(id: u8, probe_speed: f32, current_threshold: f32, timeout: Duration) -> DriverResult<(f32, f32)>;
+    }
+}
@@ -0,0 +1,161 @@
+//! Multiple buses addressed through one servo map
+//!
+//! Robots often split servos across several serial ports, for example one per side.
+//! [BusManager] owns a driver per port and maps joint names to a bus and servo ID
+//! so application code doesn't need to know where a joint is connected.
+
+use crate::driver::message_types::*;
+use crate::driver::servo_handle::ServoHandle;
+use crate::LSSDriver;
+use std::collections::HashMap;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Index of a bus added to a [BusManager]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BusId(usize);
+
+/// Owns several drivers and routes commands by joint name
+#[derive(Default)]
+pub struct BusManager {
+    buses: Vec<LSSDriver>,
+    joints: HashMap<String, (BusId, u8)>,
+}
+
+impl BusManager {
+    /// Create manager without any buses
+    pub fn new() -> BusManager {
+        BusManager::default()
+    }
+
+    /// Take ownership of a driver
+    ///
+    /// Returns ID used to map joints to this bus
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - Driver for one serial port
+    pub fn add_bus(&mut self, driver: LSSDriver) -> BusId {
+        self.buses.push(driver);
+        BusId(self.buses.len() - 1)
+    }
+
+    /// Map joint name to a servo on one of the buses
+    ///
+    /// Mapping an existing name again replaces the previous mapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - Name used to address the servo
+    /// * `bus` - Bus the servo is connected to
+    /// * `id` - ID of the servo on that bus
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{BusManager, LSSDriver};
+    /// async fn async_main(){
+    ///     let mut manager = BusManager::new();
+    ///     let left = manager.add_bus(LSSDriver::with_baud_rate("COM1", 115200).unwrap());
+    ///     let right = manager.add_bus(LSSDriver::with_baud_rate("COM2", 115200).unwrap());
+    ///     manager.map_joint("left_shoulder", left, 1);
+    ///     manager.map_joint("right_shoulder", right, 1);
+    ///     manager.servo("right_shoulder").unwrap().move_to_position(90.0).await.unwrap();
+    /// }
+    /// ```
+    pub fn map_joint(&mut self, joint: &str, bus: BusId, id: u8) {
+        self.joints.insert(joint.to_owned(), (bus, id));
+    }
+
+    /// Bus and servo ID a joint is mapped to
+    pub fn location(&self, joint: &str) -> Option<(BusId, u8)> {
+        self.joints.get(joint).copied()
+    }
+
+    /// Names of all mapped joints
+    pub fn joints(&self) -> impl Iterator<Item = &str> {
+        self.joints.keys().map(String::as_str)
+    }
+
+    /// Direct access to the driver of one bus
+    pub fn bus(&mut self, bus: BusId) -> &mut LSSDriver {
+        &mut self.buses[bus.0]
+    }
+
+    /// Handle for issuing commands to a joint
+    ///
+    /// Returns `UnknownJoint` if the joint isn't mapped
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - Name of the joint
+    pub fn servo(&mut self, joint: &str) -> DriverResult<ServoHandle<'_>> {
+        let (bus, id) = self
+            .location(joint)
+            .ok_or_else(|| LssDriverError::UnknownJoint(joint.to_owned()))?;
+        Ok(self.buses[bus.0].servo(id))
+    }
+
+    /// Query positions of multiple joints in degrees
+    ///
+    /// Joints on the same bus are queried with [query_positions](LSSDriver::query_positions).
+    /// Results are in the same order as `joints`. Joints that aren't mapped yield `UnknownJoint`.
+    /// Fails if writing to any of the buses fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `joints` - Names of joints you want to query
+    pub async fn query_positions(
+        &mut self,
+        joints: &[&str],
+    ) -> DriverResult<Vec<DriverResult<f32>>> {
+        let mut results: Vec<DriverResult<f32>> = joints
+            .iter()
+            .map(|joint| Err(LssDriverError::UnknownJoint((*joint).to_owned())))
+            .collect();
+        for (index, bus) in self.buses.iter_mut().enumerate() {
+            let (slots, ids): (Vec<usize>, Vec<u8>) = joints
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, joint)| match self.joints.get(*joint) {
+                    Some((joint_bus, id)) if joint_bus.0 == index => Some((slot, *id)),
+                    _ => None,
+                })
+                .unzip();
+            if ids.is_empty() {
+                continue;
+            }
+            let positions = bus.query_positions(&ids).await?;
+            for (slot, position) in slots.into_iter().zip(positions) {
+                results[slot] = position;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Limp every servo on every bus
+    ///
+    /// All buses are tried even if some fail. Returns the first error.
+    pub async fn limp_all(&mut self) -> DriverResult<()> {
+        let mut result = Ok(());
+        for bus in &mut self.buses {
+            if let Err(error) = bus.limp_all().await {
+                result = result.and(Err(error));
+            }
+        }
+        result
+    }
+
+    /// Halt and hold every servo on every bus
+    ///
+    /// All buses are tried even if some fail. Returns the first error.
+    pub async fn halt_hold_all(&mut self) -> DriverResult<()> {
+        let mut result = Ok(());
+        for bus in &mut self.buses {
+            if let Err(error) = bus.halt_hold_all().await {
+                result = result.and(Err(error));
+            }
+        }
+        result
+    }
+}
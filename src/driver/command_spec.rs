@@ -0,0 +1,94 @@
+//! Traits for commands the driver doesn't wrap
+//!
+//! Implement [LssCommandSpec] or [LssQuery] to add vendor specific commands or
+//! features of newer firmware without forking the crate.
+//! Framing, retries, reply matching and reconnecting of the driver are reused.
+
+use crate::driver::message_types::*;
+use crate::driver::serial_driver::LssResponse;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Command that doesn't expect a reply
+///
+/// Sent with [LSSDriver::send_command](crate::LSSDriver::send_command).
+///
+/// # Example
+///
+/// ```no_run
+/// use lss_driver::{LSSDriver, LssCommandSpec};
+///
+/// /// Set gyre direction
+/// struct SetGyre(i32);
+///
+/// impl LssCommandSpec for SetGyre {
+///     fn command(&self) -> &str {
+///         "G"
+///     }
+///
+///     fn value(&self) -> Option<i32> {
+///         Some(self.0)
+///     }
+/// }
+///
+/// async fn async_main(){
+///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+///     driver.send_command(5, &SetGyre(-1)).await.unwrap();
+/// }
+/// ```
+pub trait LssCommandSpec {
+    /// Command letters. e.g. `D`
+    fn command(&self) -> &str;
+
+    /// Value following the command letters
+    fn value(&self) -> Option<i32> {
+        None
+    }
+
+    /// Modifiers appended after the value
+    fn modifiers(&self) -> Vec<CommandModifier> {
+        vec![]
+    }
+}
+
+/// Query with a typed reply
+///
+/// Sent with [LSSDriver::run_query](crate::LSSDriver::run_query).
+/// Subject to the retry policy of the driver.
+///
+/// # Example
+///
+/// ```no_run
+/// use lss_driver::{LSSDriver, LssDriverError, LssQuery, LssResponse};
+///
+/// /// Query gyre direction
+/// struct QueryGyre;
+///
+/// impl LssQuery for QueryGyre {
+///     type Output = bool;
+///
+///     fn command(&self) -> &str {
+///         "QG"
+///     }
+///
+///     fn parse(&self, response: &LssResponse) -> Result<bool, LssDriverError> {
+///         let (_, value) = response.separate("QG")?;
+///         Ok(value > 0)
+///     }
+/// }
+///
+/// async fn async_main(){
+///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+///     let clockwise = driver.run_query(5, &QueryGyre).await.unwrap();
+/// }
+/// ```
+pub trait LssQuery {
+    /// Parsed reply
+    type Output;
+
+    /// Command letters. e.g. `QD`
+    fn command(&self) -> &str;
+
+    /// Parse reply `*<id><command><value>\r`
+    fn parse(&self, response: &LssResponse) -> DriverResult<Self::Output>;
+}
@@ -0,0 +1,260 @@
+//! Concurrent queries over one bus
+//!
+//! Built on top of [split](crate::LSSDriver::split).
+//! Every query registers the servo ID and command it expects a reply to before it's written.
+//! Received frames are routed to the oldest registered query they answer
+//! so queries from several tasks can be in flight at once without picking up each other's replies.
+
+use crate::driver::command_spec::LssQuery;
+use crate::driver::message_types::{LssDriverError, MotorStatus};
+use crate::driver::serial_driver::{FramedDriver, LssCommand, LssResponse};
+use crate::driver::split_driver::{self, CommandSender, ResponseReceiver};
+use crate::driver::{time, units, with_frames, BROADCAST_ID};
+use crate::protocol;
+use futures::future::{self, Either};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+type Transport = Box<dyn FramedDriver + Send + Sync>;
+
+/// Query waiting for its reply
+struct Pending {
+    id: u8,
+    command: String,
+    reply: oneshot::Sender<LssResponse>,
+}
+
+impl Pending {
+    fn answered_by(&self, response: &LssResponse) -> bool {
+        if self.id == BROADCAST_ID {
+            // replies to broadcast queries such as QID may not contain an ID
+            return response.as_str().contains(self.command.as_str());
+        }
+        matches!(
+            protocol::split_reply(response.as_str(), &self.command),
+            Ok((id, _)) if id == self.id
+        )
+    }
+}
+
+/// Cloneable handle for issuing queries concurrently
+///
+/// Created with [LSSDriver::dispatcher](crate::LSSDriver::dispatcher).
+/// Background task stops once all clones are dropped.
+#[derive(Clone)]
+pub struct Dispatcher {
+    sender: CommandSender,
+    registrations: mpsc::UnboundedSender<Pending>,
+    receive_timeout: Duration,
+}
+
+impl Dispatcher {
+    pub(crate) fn spawn(transport: Transport, receive_timeout: Duration) -> Dispatcher {
+        let (sender, receiver) = split_driver::split(transport);
+        let (registrations, registration_queue) = mpsc::unbounded_channel();
+        tokio::spawn(dispatch(receiver, registration_queue));
+        Dispatcher {
+            sender,
+            registrations,
+            receive_timeout,
+        }
+    }
+
+    /// Write command without waiting for a reply
+    pub async fn send(&self, command: LssCommand) -> DriverResult<()> {
+        self.sender.send(command).await
+    }
+
+    /// Write query and wait for the reply to it
+    ///
+    /// Reply is matched by servo ID and command name.
+    /// If several identical queries are in flight they are answered in the order they were issued.
+    /// Returns `TimeoutError` if no reply arrives within the receive timeout of the driver.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - Query to write
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, LssCommand};
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let dispatcher = driver.dispatcher();
+    ///     let other = dispatcher.clone();
+    ///     let (voltage, position) = futures::join!(
+    ///         dispatcher.query(LssCommand::simple(1, "QV")),
+    ///         other.query(LssCommand::simple(2, "QD")),
+    ///     );
+    ///     println!("{:?}", voltage.unwrap().separate("QV"));
+    ///     println!("{:?}", position.unwrap().separate("QD"));
+    /// }
+    /// ```
+    pub async fn query(&self, command: LssCommand) -> DriverResult<LssResponse> {
+        let (reply, response) = oneshot::channel();
+        self.registrations
+            .send(Pending {
+                id: command.id(),
+                command: command.name().to_owned(),
+                reply,
+            })
+            .map_err(|_| LssDriverError::Disconnected)?;
+        self.sender.send(command).await?;
+        match time::timeout(self.receive_timeout, response).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(LssDriverError::Disconnected),
+            Err(_) => Err(LssDriverError::TimeoutError),
+        }
+    }
+
+    /// Write query and parse the reply to it
+    async fn query_value<T>(
+        &self,
+        command: LssCommand,
+        parse: impl FnOnce(&LssResponse) -> DriverResult<T>,
+    ) -> DriverResult<T> {
+        let response = self.query(command.clone()).await?;
+        parse(&response).map_err(|error| with_frames(error, &command, &response))
+    }
+
+    /// Run query defined outside of this crate
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `query` - Query to run
+    pub async fn run_query<Q: LssQuery>(&self, id: u8, query: &Q) -> DriverResult<Q::Output> {
+        self.query_value(LssCommand::simple(id, query.command()), |response| {
+            query.parse(response)
+        })
+        .await
+    }
+
+    /// Query absolute current position in degrees
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let dispatcher = driver.dispatcher();
+    ///     let (first, second) = futures::join!(
+    ///         dispatcher.query_position(1),
+    ///         dispatcher.query_position(2),
+    ///     );
+    /// }
+    /// ```
+    pub async fn query_position(&self, id: u8) -> DriverResult<f32> {
+        self.query_value(LssCommand::simple(id, "QD"), |response| {
+            let (_, value) = response.separate("QD")?;
+            Ok(units::native_to_degrees(value))
+        })
+        .await
+    }
+
+    /// Query status of a motor
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_status(&self, id: u8) -> DriverResult<MotorStatus> {
+        self.query_value(LssCommand::simple(id, "Q"), |response| {
+            let (_, value) = response.separate("Q")?;
+            MotorStatus::from_i32(value)
+        })
+        .await
+    }
+
+    /// Query voltage of motor in volts
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_voltage(&self, id: u8) -> DriverResult<f32> {
+        self.query_value(LssCommand::simple(id, "QV"), |response| {
+            let (_, value) = response.separate("QV")?;
+            Ok(units::native_to_volts(value))
+        })
+        .await
+    }
+
+    /// Query temperature of motor in celsius
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_temperature(&self, id: u8) -> DriverResult<f32> {
+        self.query_value(LssCommand::simple(id, "QT"), |response| {
+            let (_, value) = response.separate("QT")?;
+            Ok(units::native_to_celsius(value))
+        })
+        .await
+    }
+
+    /// Query current of motor in Amps
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_current(&self, id: u8) -> DriverResult<f32> {
+        self.query_value(LssCommand::simple(id, "QC"), |response| {
+            let (_, value) = response.separate("QC")?;
+            Ok(units::native_to_amps(value))
+        })
+        .await
+    }
+}
+
+async fn dispatch(
+    mut receiver: ResponseReceiver,
+    mut registration_queue: mpsc::UnboundedReceiver<Pending>,
+) {
+    let mut pending: Vec<Pending> = vec![];
+    loop {
+        let next = {
+            let registration = registration_queue.recv();
+            futures::pin_mut!(registration);
+            let response = receiver.recv();
+            futures::pin_mut!(response);
+            match future::select(registration, response).await {
+                Either::Left((registration, _)) => Either::Left(registration),
+                Either::Right((response, _)) => Either::Right(response),
+            }
+        };
+        match next {
+            Either::Left(Some(registration)) => pending.push(registration),
+            // all dispatchers dropped
+            Either::Left(None) => return,
+            Either::Right(Some(Ok(response))) => {
+                // query may have been registered after select picked the reply
+                while let Ok(registration) = registration_queue.try_recv() {
+                    pending.push(registration);
+                }
+                // queries that timed out stopped waiting
+                pending.retain(|query| !query.reply.is_closed());
+                // longest command wins so a QDT reply isn't taken for QD
+                let mut best: Option<usize> = None;
+                for (index, query) in pending.iter().enumerate() {
+                    let longer =
+                        best.is_none_or(|best| query.command.len() > pending[best].command.len());
+                    if longer && query.answered_by(&response) {
+                        best = Some(index);
+                    }
+                }
+                if let Some(index) = best {
+                    let _ = pending.remove(index).reply.send(response);
+                }
+            }
+            Either::Right(Some(Err(_))) => (),
+            // transport disconnected. Dropping pending queries fails them
+            Either::Right(None) => return,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Step by step configuration of [LSSDriver]
+//!
+//! The builder collects transport and driver settings and applies them in one go,
+//! so new options don't need yet another constructor.
+//! Invalid combinations are reported by [build](LSSDriverBuilder::build) before anything is opened.
+
+use crate::driver::message_types::*;
+use crate::driver::serial_driver::FramedDriver;
+#[cfg(feature = "runtime-tokio")]
+use crate::driver::serial_driver::{FramedSerialDriver, SerialOptions};
+use crate::LSSDriver;
+use std::time::Duration;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Builder for [LSSDriver] created with [LSSDriver::builder](crate::LSSDriver::builder)
+///
+/// Settings that aren't set keep the same defaults as the other constructors.
+#[derive(Default)]
+pub struct LSSDriverBuilder {
+    port: Option<String>,
+    #[cfg(feature = "runtime-tokio")]
+    serial_options: SerialOptions,
+    transport: Option<Box<dyn FramedDriver + Send + Sync>>,
+    receive_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    auto_reconnect: Option<RetryPolicy>,
+    verify_writes: bool,
+    pacing: Pacing,
+    position_filter_alpha: Option<f32>,
+}
+
+impl LSSDriverBuilder {
+    /// Serial port to open. e.g. COM1 or /dev/ttyACM0
+    #[cfg(feature = "runtime-tokio")]
+    pub fn port(mut self, port: &str) -> Self {
+        self.port = Some(port.to_owned());
+        self
+    }
+
+    /// Baud rate of the serial port. Default is 115200
+    #[cfg(feature = "runtime-tokio")]
+    pub fn baud_rate(mut self, baud_rate: BaudRate) -> Self {
+        self.serial_options.baud_rate = baud_rate.into();
+        self
+    }
+
+    /// Framing, flow control and line state of the serial port
+    ///
+    /// Replaces baud rate and RS-485 settings set earlier
+    #[cfg(feature = "runtime-tokio")]
+    pub fn serial_options(mut self, options: SerialOptions) -> Self {
+        self.serial_options = options;
+        self
+    }
+
+    /// Direction control for buses behind an RS-485 transceiver
+    #[cfg(feature = "runtime-tokio")]
+    pub fn rs485(mut self, rs485: Rs485Config) -> Self {
+        self.serial_options.rs485 = Some(rs485);
+        self
+    }
+
+    /// Use custom transport instead of opening a serial port
+    ///
+    /// Can't be combined with [port](LSSDriverBuilder::port)
+    pub fn transport(mut self, transport: Box<dyn FramedDriver + Send + Sync>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// How long queries wait for a reply
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.receive_timeout = Some(timeout);
+        self
+    }
+
+    /// Number of times a failed query is repeated
+    ///
+    /// Keeps backoff set by [retry_backoff](LSSDriverBuilder::retry_backoff)
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retry_policy.max_attempts = retries.saturating_add(1);
+        self
+    }
+
+    /// Delay before the first retry. Doubles after every attempt
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_policy.backoff = backoff;
+        self
+    }
+
+    /// Reopen the port automatically when it disconnects
+    pub fn auto_reconnect(mut self, policy: RetryPolicy) -> Self {
+        self.auto_reconnect = Some(policy);
+        self
+    }
+
+    /// Query settings back after setting them
+    pub fn verify_writes(mut self, verify_writes: bool) -> Self {
+        self.verify_writes = verify_writes;
+        self
+    }
+
+    /// Limit how fast frames are written
+    pub fn pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Smoothing factor of the host side position filter
+    pub fn position_filter(mut self, alpha: f32) -> Self {
+        self.position_filter_alpha = Some(alpha);
+        self
+    }
+
+    /// Open the port and create driver
+    ///
+    /// Fails with `FailedOpeningSerialPort` if neither port nor transport were set,
+    /// `InvalidConfiguration` if both were set
+    /// and `UnsupportedBaudRate` if serial options use a baud rate the servos don't support.
+    pub fn build(self) -> DriverResult<LSSDriver> {
+        let transport = match (self.transport, self.port) {
+            (Some(_), Some(_)) => {
+                return Err(LssDriverError::InvalidConfiguration(
+                    "port and transport can't be used together".to_owned(),
+                ))
+            }
+            (Some(transport), None) => transport,
+            #[cfg(feature = "runtime-tokio")]
+            (None, Some(port)) => Box::new(FramedSerialDriver::with_options(
+                &port,
+                self.serial_options,
+            )?),
+            _ => return Err(LssDriverError::FailedOpeningSerialPort),
+        };
+        let mut driver = LSSDriver::with_driver(transport);
+        if let Some(timeout) = self.receive_timeout {
+            driver.set_receive_timeout(timeout);
+        }
+        driver.set_retry_policy(self.retry_policy);
+        driver.set_auto_reconnect(self.auto_reconnect);
+        driver.set_verify_writes(self.verify_writes);
+        driver.set_pacing(self.pacing);
+        if let Some(alpha) = self.position_filter_alpha {
+            driver.set_position_filter(alpha);
+        }
+        Ok(driver)
+    }
+}
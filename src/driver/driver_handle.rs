@@ -0,0 +1,590 @@
+//! Driver handle backed by a background IO task
+//!
+//! The driver is moved into a task that executes requests one after another.
+//! Handles are cheap to clone and can be used from any number of tasks concurrently,
+//! for example a control loop and a telemetry logger sharing one bus.
+//!
+//! Safety commands such as limp and halt go through a separate lane
+//! which the IO task always drains before picking up the next regular request.
+
+use crate::driver::message_types::*;
+use crate::driver::telemetry_poller::TelemetryPoller;
+use crate::driver::{time, LSSDriver};
+use futures::future::{self, BoxFuture, Either};
+use futures::Stream;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+type Job = Box<dyn for<'a> FnOnce(&'a mut LSSDriver) -> BoxFuture<'a, ()> + Send>;
+
+/// Number of requests that can be queued before callers have to wait
+const JOB_QUEUE_CAPACITY: usize = 32;
+/// Number of safety requests that can be queued before callers have to wait
+const SAFETY_QUEUE_CAPACITY: usize = 8;
+
+macro_rules! handle_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> DriverResult<$ret:ty>;)*) => {
+        $(
+            #[doc = concat!("Same as [", stringify!($name), "](crate::LSSDriver::", stringify!($name), ") but executed by the IO task")]
+            pub async fn $name(&self, $($arg: $ty),*) -> DriverResult<$ret> {
+                self.run(move |driver| Box::pin(async move { driver.$name($($arg),*).await }))
+                    .await?
+            }
+        )*
+    };
+}
+
+macro_rules! safety_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> DriverResult<$ret:ty>;)*) => {
+        $(
+            #[doc = concat!("Same as [", stringify!($name), "](crate::LSSDriver::", stringify!($name), ") but executed by the IO task")]
+            ///
+            /// Queued in the safety lane ahead of regular requests.
+            pub async fn $name(&self, $($arg: $ty),*) -> DriverResult<$ret> {
+                self.run_with_priority(Priority::Safety, move |driver| {
+                    Box::pin(async move { driver.$name($($arg),*).await })
+                })
+                .await?
+            }
+        )*
+    };
+}
+
+macro_rules! stream_methods {
+    ($($name:ident => $query:ident -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Same as [", stringify!($name), "](crate::LSSDriver::", stringify!($name), ") but every query is a separate request")]
+            ///
+            /// Requests from other handles are executed between two queries.
+            pub fn $name(&self, id: u8, interval: Duration) -> impl Stream<Item = DriverResult<$ret>> {
+                time::every(interval, self.clone(), move |handle| {
+                    Box::pin(async move { Some(handle.$query(id).await) })
+                })
+            }
+        )*
+    };
+}
+
+/// Lane a request is queued in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Motion, queries and configuration. Executed in order
+    Normal,
+    /// Limp, halt and other emergency commands. Executed before any waiting normal request
+    Safety,
+}
+
+/// Cloneable handle to a driver running in a background task
+///
+/// Created with [LSSDriver::spawn](crate::LSSDriver::spawn).
+/// Requests from all clones are queued and executed in order so commands and replies never interleave.
+/// Safety requests skip ahead of queued regular requests but never interrupt the one currently running.
+/// Background task stops once all handles are dropped.
+#[derive(Clone)]
+pub struct DriverHandle {
+    jobs: mpsc::Sender<Job>,
+    safety_jobs: mpsc::Sender<Job>,
+}
+
+impl DriverHandle {
+    pub(crate) fn spawn(mut driver: LSSDriver) -> DriverHandle {
+        let (jobs, mut queue) = mpsc::channel::<Job>(JOB_QUEUE_CAPACITY);
+        let (safety_jobs, mut safety_queue) = mpsc::channel::<Job>(SAFETY_QUEUE_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                if let Ok(job) = safety_queue.try_recv() {
+                    job(&mut driver).await;
+                    continue;
+                }
+                let next = {
+                    let safety = safety_queue.recv();
+                    futures::pin_mut!(safety);
+                    let normal = queue.recv();
+                    futures::pin_mut!(normal);
+                    match future::select(safety, normal).await {
+                        Either::Left((job, _)) => job,
+                        Either::Right((job, _)) => job,
+                    }
+                };
+                // both lanes close together once the last handle is dropped
+                match next {
+                    Some(job) => job(&mut driver).await,
+                    None => return,
+                }
+            }
+        });
+        DriverHandle { jobs, safety_jobs }
+    }
+
+    /// Run a closure with exclusive access to the driver
+    ///
+    /// Useful for sequences of commands that shouldn't be interleaved with requests from other handles.
+    /// Returns `Disconnected` if the background task stopped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let handle = driver.spawn();
+    ///     let (position, current) = handle
+    ///         .run(|driver| {
+    ///             Box::pin(async move {
+    ///                 let position = driver.query_position(5).await?;
+    ///                 let current = driver.query_current(5).await?;
+    ///                 Ok::<_, lss_driver::LssDriverError>((position, current))
+    ///             })
+    ///         })
+    ///         .await
+    ///         .unwrap()
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn run<T, F>(&self, f: F) -> DriverResult<T>
+    where
+        F: for<'a> FnOnce(&'a mut LSSDriver) -> BoxFuture<'a, T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run_with_priority(Priority::Normal, f).await
+    }
+
+    /// Same as [run](DriverHandle::run) but queued in the selected lane
+    ///
+    /// Use [Priority::Safety] for custom emergency sequences that must not wait behind telemetry polls.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - Lane to queue the request in
+    /// * `f` - Closure executed with exclusive access to the driver
+    pub async fn run_with_priority<T, F>(&self, priority: Priority, f: F) -> DriverResult<T>
+    where
+        F: for<'a> FnOnce(&'a mut LSSDriver) -> BoxFuture<'a, T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let job: Job = Box::new(move |driver| {
+            Box::pin(async move {
+                // caller may have stopped waiting
+                let _ = reply.send(f(driver).await);
+            })
+        });
+        let lane = match priority {
+            Priority::Normal => &self.jobs,
+            Priority::Safety => &self.safety_jobs,
+        };
+        lane.send(job)
+            .await
+            .map_err(|_| LssDriverError::Disconnected)?;
+        response.await.map_err(|_| LssDriverError::Disconnected)
+    }
+
+    /// Poll telemetry of servos in the background
+    ///
+    /// Rounds start every `interval`. Rounds that take longer delay the next one.
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos to poll
+    /// * `interval` - Time between the start of two rounds
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let handle = LSSDriver::with_baud_rate("COM1", 115200).unwrap().spawn();
+    ///     let poller = handle.telemetry_poller(&[1, 2, 3], Duration::from_millis(100));
+    ///     handle.move_to_position(1, 90.0).await.unwrap();
+    ///     if let Some(telemetry) = poller.latest(1) {
+    ///         println!("{:?}", telemetry.position);
+    ///     }
+    /// }
+    /// ```
+    pub fn telemetry_poller(&self, ids: &[u8], interval: Duration) -> TelemetryPoller {
+        TelemetryPoller::spawn(self.clone(), ids, interval)
+    }
+
+    /// Same as [telemetry_stream](crate::LSSDriver::telemetry_stream) but every query is a separate request
+    ///
+    /// Requests from other handles are executed between two queries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let handle = LSSDriver::with_baud_rate("COM1", 115200).unwrap().spawn();
+    ///     let stream = handle.telemetry_stream(vec![1, 2], Duration::from_millis(20));
+    ///     futures::pin_mut!(stream);
+    ///     handle.move_to_position(1, 90.0).await.unwrap();
+    ///     while let Some((id, telemetry)) = stream.next().await {
+    ///         println!("{} {:?}", id, telemetry);
+    ///     }
+    /// }
+    /// ```
+    pub fn telemetry_stream(
+        &self,
+        ids: Vec<u8>,
+        interval: Duration,
+    ) -> impl Stream<Item = (u8, DriverResult<ServoTelemetry>)> {
+        time::every(interval, (self.clone(), ids, 0), |(handle, ids, index)| {
+            Box::pin(async move {
+                let id = *ids.get(*index)?;
+                *index = (*index + 1) % ids.len();
+                Some((id, handle.query_telemetry(id).await))
+            })
+        })
+    }
+
+    stream_methods! {
+        position_stream => query_position -> f32;
+        voltage_stream => query_voltage -> f32;
+        temperature_stream => query_temperature -> f32;
+        current_stream => query_current -> f32;
+        status_stream => query_status -> MotorStatus;
+    }
+
+    /// Same as [set_auto_reconnect](crate::LSSDriver::set_auto_reconnect)
+    pub async fn set_auto_reconnect(&self, policy: Option<RetryPolicy>) -> DriverResult<()> {
+        self.run(move |driver| Box::pin(async move { driver.set_auto_reconnect(policy) }))
+            .await
+    }
+
+    /// Same as [connection_events](crate::LSSDriver::connection_events)
+    pub async fn connection_events(&self) -> DriverResult<broadcast::Receiver<ConnectionEvent>> {
+        self.run(|driver| Box::pin(async move { driver.connection_events() }))
+            .await
+    }
+
+    /// Same as [events](crate::LSSDriver::events)
+    pub async fn events(&self) -> DriverResult<impl Stream<Item = DriverEvent>> {
+        self.run(|driver| Box::pin(async move { driver.events() }))
+            .await
+    }
+
+    /// Same as [bus_metrics](crate::LSSDriver::bus_metrics)
+    pub async fn bus_metrics(&self) -> DriverResult<BusMetrics> {
+        self.run(|driver| Box::pin(async move { driver.bus_metrics() }))
+            .await
+    }
+
+    /// Same as [last_known](crate::LSSDriver::last_known)
+    pub async fn last_known(&self, id: u8) -> DriverResult<LastKnown> {
+        self.run(move |driver| Box::pin(async move { driver.last_known(id) }))
+            .await
+    }
+
+    /// Same as [set_verify_writes](crate::LSSDriver::set_verify_writes)
+    pub async fn set_verify_writes(&self, verify_writes: bool) -> DriverResult<()> {
+        self.run(move |driver| Box::pin(async move { driver.set_verify_writes(verify_writes) }))
+            .await
+    }
+
+    /// Same as [set_retry_policy](crate::LSSDriver::set_retry_policy)
+    pub async fn set_retry_policy(&self, retry_policy: RetryPolicy) -> DriverResult<()> {
+        self.run(move |driver| Box::pin(async move { driver.set_retry_policy(retry_policy) }))
+            .await
+    }
+
+    /// Same as [set_pacing](crate::LSSDriver::set_pacing)
+    pub async fn set_pacing(&self, pacing: Pacing) -> DriverResult<()> {
+        self.run(move |driver| Box::pin(async move { driver.set_pacing(pacing) }))
+            .await
+    }
+
+    /// Same as [set_receive_timeout](crate::LSSDriver::set_receive_timeout)
+    pub async fn set_receive_timeout(&self, timeout: Duration) -> DriverResult<Duration> {
+        self.run(move |driver| Box::pin(async move { driver.set_receive_timeout(timeout) }))
+            .await
+    }
+
+    /// Same as [receive_timeout](crate::LSSDriver::receive_timeout)
+    pub async fn receive_timeout(&self) -> DriverResult<Duration> {
+        self.run(|driver| Box::pin(async move { driver.receive_timeout() }))
+            .await
+    }
+
+    /// Same as [set_position_filter](crate::LSSDriver::set_position_filter)
+    pub async fn set_position_filter(&self, alpha: f32) -> DriverResult<()> {
+        self.run(move |driver| Box::pin(async move { driver.set_position_filter(alpha) }))
+            .await
+    }
+
+    /// Same as [detect_id_conflicts_in](crate::LSSDriver::detect_id_conflicts_in) but executed by the IO task
+    pub async fn detect_id_conflicts_in(&self, ids: &[u8]) -> DriverResult<IdConflictReport> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.detect_id_conflicts_in(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_positions](crate::LSSDriver::query_positions) but executed by the IO task
+    pub async fn query_positions(&self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.query_positions(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_voltages](crate::LSSDriver::query_voltages) but executed by the IO task
+    pub async fn query_voltages(&self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.query_voltages(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_temperatures](crate::LSSDriver::query_temperatures) but executed by the IO task
+    pub async fn query_temperatures(&self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.query_temperatures(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_currents](crate::LSSDriver::query_currents) but executed by the IO task
+    pub async fn query_currents(&self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.query_currents(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_statuses](crate::LSSDriver::query_statuses) but executed by the IO task
+    pub async fn query_statuses(&self, ids: &[u8]) -> DriverResult<Vec<DriverResult<MotorStatus>>> {
+        let ids = ids.to_vec();
+        self.run(move |driver| Box::pin(async move { driver.query_statuses(&ids).await }))
+            .await?
+    }
+
+    /// Same as [query_broadcast](crate::LSSDriver::query_broadcast) but executed by the IO task
+    pub async fn query_broadcast(&self, cmd: &str) -> DriverResult<Vec<(u8, i32)>> {
+        let cmd = cmd.to_owned();
+        self.run(move |driver| Box::pin(async move { driver.query_broadcast(&cmd).await }))
+            .await?
+    }
+
+    /// Same as [send_raw](crate::LSSDriver::send_raw) but executed by the IO task
+    pub async fn send_raw(&self, id: u8, cmd: &str, value: Option<i32>) -> DriverResult<()> {
+        let cmd = cmd.to_owned();
+        self.run(move |driver| Box::pin(async move { driver.send_raw(id, &cmd, value).await }))
+            .await?
+    }
+
+    /// Same as [query_raw](crate::LSSDriver::query_raw) but executed by the IO task
+    pub async fn query_raw(&self, id: u8, cmd: &str) -> DriverResult<String> {
+        let cmd = cmd.to_owned();
+        self.run(move |driver| Box::pin(async move { driver.query_raw(id, &cmd).await }))
+            .await?
+    }
+
+    /// Same as [move_to_position_with_modifiers](crate::LSSDriver::move_to_position_with_modifiers) but executed by the IO task
+    pub async fn move_to_position_with_modifiers(
+        &self,
+        id: u8,
+        position: f32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        let modifiers = modifiers.to_vec();
+        self.run(move |driver| {
+            Box::pin(async move {
+                driver
+                    .move_to_position_with_modifiers(id, position, &modifiers)
+                    .await
+            })
+        })
+        .await?
+    }
+
+    /// Same as [move_relative_with_modifiers](crate::LSSDriver::move_relative_with_modifiers) but executed by the IO task
+    pub async fn move_relative_with_modifiers(
+        &self,
+        id: u8,
+        delta: f32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        let modifiers = modifiers.to_vec();
+        self.run(move |driver| {
+            Box::pin(async move {
+                driver
+                    .move_relative_with_modifiers(id, delta, &modifiers)
+                    .await
+            })
+        })
+        .await?
+    }
+
+    /// Same as [move_to_pwm_position_with_modifiers](crate::LSSDriver::move_to_pwm_position_with_modifiers) but executed by the IO task
+    pub async fn move_to_pwm_position_with_modifiers(
+        &self,
+        id: u8,
+        position: i32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        let modifiers = modifiers.to_vec();
+        self.run(move |driver| {
+            Box::pin(async move {
+                driver
+                    .move_to_pwm_position_with_modifiers(id, position, &modifiers)
+                    .await
+            })
+        })
+        .await?
+    }
+
+    /// Same as [try_limp_all](crate::LSSDriver::try_limp_all) but executed by the IO task
+    ///
+    /// Queued in the safety lane ahead of regular requests.
+    pub async fn try_limp_all(&self, ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>> {
+        self.try_each_with_safety(ids, |driver, ids| Box::pin(driver.try_limp_all(ids)))
+            .await
+    }
+
+    /// Same as [try_halt_hold_all](crate::LSSDriver::try_halt_hold_all) but executed by the IO task
+    ///
+    /// Queued in the safety lane ahead of regular requests.
+    pub async fn try_halt_hold_all(&self, ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>> {
+        self.try_each_with_safety(ids, |driver, ids| Box::pin(driver.try_halt_hold_all(ids)))
+            .await
+    }
+
+    /// Run a `try_*_all` operation in the safety lane, reporting every servo as disconnected if the IO task is gone
+    async fn try_each_with_safety<F>(
+        &self,
+        ids: &[u8],
+        operation: F,
+    ) -> Result<(), Vec<(u8, LssDriverError)>>
+    where
+        F: for<'a> FnOnce(
+                &'a mut LSSDriver,
+                &'a [u8],
+            ) -> BoxFuture<'a, Result<(), Vec<(u8, LssDriverError)>>>
+            + Send
+            + 'static,
+    {
+        let owned_ids = ids.to_vec();
+        self.run_with_priority(Priority::Safety, move |driver| {
+            Box::pin(async move { operation(driver, &owned_ids).await })
+        })
+        .await
+        .unwrap_or_else(|_| Err(all_disconnected(ids)))
+    }
+
+    safety_methods! {
+        limp(id: u8) -> DriverResult<()>;
+        limp_all() -> DriverResult<()>;
+        halt_hold(id: u8) -> DriverResult<()>;
+        halt_hold_all() -> DriverResult<()>;
+    }
+
+    handle_methods! {
+        reconnect() -> DriverResult<()>;
+        flush() -> DriverResult<()>;
+        reset(id: u8) -> DriverResult<()>;
+        reset_and_wait(id: u8, timeout: Duration) -> DriverResult<()>;
+        restore_factory_defaults(id: u8, timeout: Duration) -> DriverResult<()>;
+        query_id(id: u8) -> DriverResult<u8>;
+        detect_id_conflicts() -> DriverResult<IdConflictReport>;
+        set_id(id: u8, new_id: u8) -> DriverResult<()>;
+        set_baud_rate(id: u8, baud_rate: BaudRate) -> DriverResult<()>;
+        change_baud_rate(id: u8, baud_rate: BaudRate) -> DriverResult<()>;
+        query_baud_rate(id: u8) -> DriverResult<BaudRate>;
+        set_color(id: u8, color: LedColor) -> DriverResult<()>;
+        configure_color(id: u8, color: LedColor) -> DriverResult<()>;
+        query_color(id: u8) -> DriverResult<LedColor>;
+        identify(id: u8, duration: Duration) -> DriverResult<()>;
+        move_to_position(id: u8, position: f32) -> DriverResult<()>;
+        move_to_position_in_mode(id: u8, position: f32, expected_mode: ExpectedMode) -> DriverResult<()>;
+        move_to_position_with_modifier(id: u8, position: f32, modifier: CommandModifier) -> DriverResult<()>;
+        move_to_position_with_speed(id: u8, position: f32, speed: f32) -> DriverResult<()>;
+        move_to_position_in(id: u8, position: f32, duration: Duration) -> DriverResult<()>;
+        move_to_position_with_current_hold(id: u8, position: f32, current: u32) -> DriverResult<()>;
+        move_to_position_with_current_limp(id: u8, position: f32, current: u32) -> DriverResult<()>;
+        execute(id: u8, command: MoveCommand) -> DriverResult<()>;
+        configure_first_position(id: u8, position: Option<f32>) -> DriverResult<()>;
+        query_first_position(id: u8) -> DriverResult<Option<f32>>;
+        move_relative(id: u8, delta: f32) -> DriverResult<()>;
+        set_target_position(id: u8, position: f32) -> DriverResult<()>;
+        query_position(id: u8) -> DriverResult<f32>;
+        query_filtered_position(id: u8) -> DriverResult<f32>;
+        query_target_position(id: u8) -> DriverResult<f32>;
+        query_position_error(id: u8) -> DriverResult<f32>;
+        set_rotation_speed(id: u8, speed: f32) -> DriverResult<()>;
+        query_rotation_speed(id: u8) -> DriverResult<f32>;
+        set_rotation_speed_dps(id: u8, speed: f32) -> DriverResult<()>;
+        set_rotation_speed_rpm(id: u8, speed: f32) -> DriverResult<()>;
+        query_rotation_speed_rpm(id: u8) -> DriverResult<f32>;
+        query_speed_dps(id: u8) -> DriverResult<f32>;
+        query_speed_rpm(id: u8) -> DriverResult<f32>;
+        query_status(id: u8) -> DriverResult<MotorStatus>;
+        query_status_flags(id: u8) -> DriverResult<StatusFlags>;
+        is_moving(id: u8) -> DriverResult<bool>;
+        wait_until_stopped(id: u8, timeout: Duration) -> DriverResult<()>;
+        query_safety_status(id: u8) -> DriverResult<SafeModeStatus>;
+        set_gyre_direction(id: u8, direction: Direction) -> DriverResult<()>;
+        configure_gyre_direction(id: u8, direction: Direction) -> DriverResult<()>;
+        query_gyre_direction(id: u8) -> DriverResult<Direction>;
+        set_motion_profile(id: u8, motion_profile: bool) -> DriverResult<()>;
+        configure_motion_profile(id: u8, motion_profile: bool) -> DriverResult<()>;
+        query_motion_profile(id: u8) -> DriverResult<bool>;
+        set_filter_position_count(id: u8, filter_position_count: u8) -> DriverResult<()>;
+        configure_filter_position_count(id: u8, filter_position_count: u8) -> DriverResult<()>;
+        query_filter_position_count(id: u8) -> DriverResult<u8>;
+        set_angular_stiffness(id: u8, angular_stiffness: i32) -> DriverResult<()>;
+        configure_angular_stiffness(id: u8, angular_stiffness: i32) -> DriverResult<()>;
+        query_angular_stiffness(id: u8) -> DriverResult<i32>;
+        query_angular_stiffness_in_scope(id: u8, scope: QueryScope) -> DriverResult<i32>;
+        set_angular_holding_stiffness(id: u8, angular_holding: i32) -> DriverResult<()>;
+        configure_angular_holding_stiffness(id: u8, angular_holding: i32) -> DriverResult<()>;
+        query_angular_holding_stiffness(id: u8) -> DriverResult<i32>;
+        query_angular_holding_stiffness_in_scope(id: u8, scope: QueryScope) -> DriverResult<i32>;
+        set_angular_acceleration(id: u8, angular_acceleration: i32) -> DriverResult<()>;
+        configure_angular_acceleration(id: u8, angular_acceleration: i32) -> DriverResult<()>;
+        query_angular_acceleration(id: u8) -> DriverResult<i32>;
+        set_angular_deceleration(id: u8, angular_deceleration: i32) -> DriverResult<()>;
+        configure_angular_deceleration(id: u8, angular_deceleration: i32) -> DriverResult<()>;
+        query_angular_deceleration(id: u8) -> DriverResult<i32>;
+        set_maximum_motor_duty(id: u8, maximum_motor_duty: i32) -> DriverResult<()>;
+        configure_maximum_motor_duty(id: u8, maximum_motor_duty: i32) -> DriverResult<()>;
+        query_maximum_motor_duty(id: u8) -> DriverResult<i32>;
+        set_maximum_speed(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed(id: u8) -> DriverResult<f32>;
+        query_maximum_speed_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        set_maximum_speed_rpm(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed_rpm(id: u8, maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed_rpm(id: u8) -> DriverResult<f32>;
+        query_voltage(id: u8) -> DriverResult<f32>;
+        query_temperature(id: u8) -> DriverResult<f32>;
+        query_current(id: u8) -> DriverResult<f32>;
+        query_telemetry(id: u8) -> DriverResult<ServoTelemetry>;
+        query_model(id: u8) -> DriverResult<Model>;
+        query_firmware_version(id: u8) -> DriverResult<FirmwareVersion>;
+        query_serial_number(id: u8) -> DriverResult<String>;
+        set_led_blinking(id: u8, blinking_mode: Vec<LedBlinking>) -> DriverResult<()>;
+        query_led_blinking(id: u8) -> DriverResult<Vec<LedBlinking>>;
+        query_origin_offset(id: u8) -> DriverResult<f32>;
+        query_origin_offset_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        query_angular_range(id: u8) -> DriverResult<f32>;
+        query_angular_range_in_scope(id: u8, scope: QueryScope) -> DriverResult<f32>;
+        set_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        set_session_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        configure_angular_range(id: u8, range: f32) -> DriverResult<()>;
+        query_pwm_position(id: u8) -> DriverResult<i32>;
+        set_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        set_session_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        configure_origin_offset(id: u8, origin_offset: f32) -> DriverResult<()>;
+        move_to_pwm_position(id: u8, position: i32) -> DriverResult<()>;
+        move_to_pulse(id: u8, position: i32) -> DriverResult<()>;
+        query_pulse_position(id: u8) -> DriverResult<i32>;
+        move_to_pwm_position_with_modifier(id: u8, position: i32, modifier: CommandModifier) -> DriverResult<()>;
+        auto_find_limits(id: u8, probe_speed: f32, current_threshold: f32, timeout: Duration) -> DriverResult<(f32, f32)>;
+    }
+}
+
+fn all_disconnected(ids: &[u8]) -> Vec<(u8, LssDriverError)> {
+    ids.iter()
+        .map(|id| (*id, LssDriverError::Disconnected))
+        .collect()
+}
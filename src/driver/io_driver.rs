@@ -0,0 +1,104 @@
+use crate::driver::message_types::LssDriverError;
+use crate::driver::serial_driver::{
+    FramedDriver, LssCodec, LssCommand, LssResponse, DEFAULT_RECEIVE_TIMEOUT,
+};
+use crate::driver::time;
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use std::time::Duration;
+use tokio_util::codec::Decoder;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Transport over any byte stream implementing the `futures` IO traits
+///
+/// Doesn't depend on a tokio runtime so it can be used with async-std, smol and other executors.
+pub struct IoDriver<T> {
+    io: T,
+    receive_timeout: Duration,
+    read_buffer: BytesMut,
+    codec: LssCodec,
+}
+
+impl<T> IoDriver<T> {
+    pub fn new(io: T) -> IoDriver<T> {
+        IoDriver {
+            io,
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            read_buffer: BytesMut::new(),
+            codec: LssCodec::new(),
+        }
+    }
+}
+
+impl<T: AsyncRead + Unpin> IoDriver<T> {
+    async fn read_frame(&mut self) -> DriverResult<LssResponse> {
+        let mut read_buffer = [0_u8; 256];
+        loop {
+            if let Some(response) = self
+                .codec
+                .decode(&mut self.read_buffer)
+                .map_err(|_| LssDriverError::Disconnected)?
+            {
+                return Ok(response);
+            }
+            let read = self
+                .io
+                .read(&mut read_buffer)
+                .await
+                .map_err(|_| LssDriverError::Disconnected)?;
+            if read == 0 {
+                return Err(LssDriverError::Disconnected);
+            }
+            self.read_buffer.extend_from_slice(&read_buffer[..read]);
+        }
+    }
+}
+
+#[async_trait]
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + Sync> FramedDriver for IoDriver<T> {
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        self.read_buffer.clear();
+        Ok(())
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        self.codec.take_dropped_bytes()
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        self.io
+            .write_all(command.as_bytes())
+            .await
+            .map_err(|_| LssDriverError::SendingError)?;
+        self.io
+            .flush()
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        time::timeout(self.receive_timeout, self.read_frame())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+    }
+
+    async fn flush(&mut self) -> DriverResult<()> {
+        self.io
+            .flush()
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn close(&mut self) -> DriverResult<()> {
+        self.io
+            .close()
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+}
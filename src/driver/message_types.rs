@@ -0,0 +1,1226 @@
+use crate::driver::serial_driver::{LssCommand, LssResponse};
+use crate::driver::units;
+use bitflags::bitflags;
+use std::{
+    fmt, str,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// Driver errors
+#[derive(Error, Debug)]
+pub enum LssDriverError {
+    #[error("Failed to parse data: {0}")]
+    /// Error triggered if we fail parsing a value into a data structure
+    ///
+    /// Queries report [ParseError](LssDriverError::ParseError) instead which also carries the frames
+    PacketParsingError(String),
+    #[error("Operation timed out")]
+    /// Error triggered for reading timeout
+    TimeoutError,
+    #[error("Failed to open serial port")]
+    /// Error triggered if serial port can't be opened or reopened
+    FailedOpeningSerialPort,
+    #[error("Failed to send command")]
+    /// Error triggered if command can't be written to the serial port
+    SendingError,
+    #[error("Serial port disconnected")]
+    /// Error triggered if serial port can't be read from anymore. e.g. USB cable was unplugged
+    Disconnected,
+    #[error("Unexpected response {received:?} to {:?}", .sent.as_deref().unwrap_or_default())]
+    /// Error triggered when reply doesn't echo the command that was sent
+    /// e.g. stale reply to an earlier query
+    UnexpectedResponse {
+        /// Query the reply was read for. `None` when the reply was parsed outside of a query
+        sent: Option<String>,
+        /// Raw frame as received
+        received: String,
+    },
+    #[error("Failed to parse response {received:?} to {:?}: {reason}", .sent.as_deref().unwrap_or_default())]
+    /// Error triggered when a received frame can't be parsed into a data structure
+    ParseError {
+        reason: String,
+        /// Query the reply was read for. `None` when the reply was parsed outside of a query
+        sent: Option<String>,
+        /// Raw frame as received
+        received: String,
+    },
+    #[error("Value out of range: {0}")]
+    /// Error triggered when a parameter is outside of the range accepted by the servo
+    /// Nothing is sent in that case
+    ValueOutOfRange(String),
+    #[error("Verification failed: {0}")]
+    /// Error triggered when setting queried back doesn't match the value that was set
+    VerificationFailed(String),
+    #[error("Unsupported baud rate {0}")]
+    /// Error triggered when baud rate is not supported by the servo firmware
+    UnsupportedBaudRate(u32),
+    #[error("Servo motion profile is not {0:?}")]
+    /// Error triggered when servo motion profile doesn't match the expected mode
+    MotionProfileMismatch(ExpectedMode),
+    #[error("Unknown joint {0:?}")]
+    /// Error triggered when a joint name isn't mapped to any servo
+    UnknownJoint(String),
+    #[error("Invalid configuration: {0}")]
+    /// Error triggered when driver settings contradict each other. e.g. both port and transport given to the builder
+    InvalidConfiguration(String),
+}
+
+impl LssDriverError {
+    /// Errors caused by a missing or garbled reply that may succeed if the query is repeated
+    pub(crate) fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            LssDriverError::TimeoutError
+                | LssDriverError::PacketParsingError(_)
+                | LssDriverError::ParseError { .. }
+                | LssDriverError::UnexpectedResponse { .. }
+        )
+    }
+}
+
+/// Changes of connection state reported when auto reconnect is enabled
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Serial port stopped responding
+    Disconnected,
+    /// Serial port was reopened
+    Reconnected,
+}
+
+/// Bus activity reported by [LSSDriver::events](crate::LSSDriver::events)
+#[derive(Clone, Debug, PartialEq)]
+pub enum DriverEvent {
+    /// Command was written to the transport
+    CommandSent(LssCommand),
+    /// Frame was received
+    ResponseReceived(LssResponse),
+    /// Nothing was received within the receive timeout
+    Timeout,
+    /// Transport was reopened by auto reconnect
+    Reconnected,
+    /// Reply to a query couldn't be parsed
+    ParseError {
+        response: LssResponse,
+        error: String,
+    },
+}
+
+#[derive(Default)]
+struct BusCounters {
+    commands_sent: AtomicU64,
+    responses_received: AtomicU64,
+    timeouts: AtomicU64,
+    reconnects: AtomicU64,
+    parse_errors: AtomicU64,
+}
+
+/// Bus statistics counted by the driver
+///
+/// Returned by [LSSDriver::bus_metrics](crate::LSSDriver::bus_metrics).
+/// Counted where events are emitted so nothing is missed when event subscribers fall behind.
+/// Clones share the same counters and keep counting while the driver is used.
+#[derive(Clone, Default)]
+pub struct BusMetrics {
+    counters: Arc<BusCounters>,
+}
+
+impl BusMetrics {
+    pub(crate) fn record(&self, event: &DriverEvent) {
+        let counter = match event {
+            DriverEvent::CommandSent(_) => &self.counters.commands_sent,
+            DriverEvent::ResponseReceived(_) => &self.counters.responses_received,
+            DriverEvent::Timeout => &self.counters.timeouts,
+            DriverEvent::Reconnected => &self.counters.reconnects,
+            DriverEvent::ParseError { .. } => &self.counters.parse_errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Frames written to the bus
+    pub fn commands_sent(&self) -> u64 {
+        self.counters.commands_sent.load(Ordering::Relaxed)
+    }
+
+    /// Frames received from the bus
+    pub fn responses_received(&self) -> u64 {
+        self.counters.responses_received.load(Ordering::Relaxed)
+    }
+
+    /// Receives that ran into the receive timeout
+    pub fn timeouts(&self) -> u64 {
+        self.counters.timeouts.load(Ordering::Relaxed)
+    }
+
+    /// Times the transport was reopened
+    pub fn reconnects(&self) -> u64 {
+        self.counters.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Replies that couldn't be parsed
+    pub fn parse_errors(&self) -> u64 {
+        self.counters.parse_errors.load(Ordering::Relaxed)
+    }
+}
+
+/// Value together with the time it was received
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timestamped<T> {
+    pub value: T,
+    pub received_at: Instant,
+}
+
+impl<T> Timestamped<T> {
+    pub(crate) fn now(value: T) -> Timestamped<T> {
+        Timestamped {
+            value,
+            received_at: Instant::now(),
+        }
+    }
+
+    /// Time since the value was received
+    pub fn age(&self) -> Duration {
+        self.received_at.elapsed()
+    }
+}
+
+/// Most recent values queried from a servo
+///
+/// Returned by [LSSDriver::last_known](crate::LSSDriver::last_known).
+/// Values that were never queried are `None`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LastKnown {
+    /// Position in degrees
+    pub position: Option<Timestamped<f32>>,
+    /// Rotation speed in degrees per second
+    pub speed: Option<Timestamped<f32>>,
+    /// Voltage in volts
+    pub voltage: Option<Timestamped<f32>>,
+    /// Temperature in celsius
+    pub temperature: Option<Timestamped<f32>>,
+    /// Current in amps
+    pub current: Option<Timestamped<f32>>,
+    pub status: Option<Timestamped<MotorStatus>>,
+    pub color: Option<Timestamped<LedColor>>,
+}
+
+/// Limits that raise an [Alarm] when exceeded
+///
+/// Limits that are `None` aren't checked.
+/// Raised alarm clears once the value is back within its limit by more than the hysteresis band,
+/// so values hovering around a limit don't raise it over and over.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AlarmThresholds {
+    /// Highest allowed temperature in celsius
+    pub max_temperature: Option<f32>,
+    /// Highest allowed current in amps
+    pub max_current: Option<f32>,
+    /// Lowest allowed voltage in volts
+    pub min_voltage: Option<f32>,
+    /// Hysteresis band of the temperature limit in celsius
+    pub temperature_hysteresis: f32,
+    /// Hysteresis band of the current limit in amps
+    pub current_hysteresis: f32,
+    /// Hysteresis band of the voltage limit in volts
+    pub voltage_hysteresis: f32,
+}
+
+impl AlarmThresholds {
+    /// Limits exceeded by `telemetry` together with the offending value
+    #[cfg(feature = "runtime-tokio")]
+    pub(crate) fn exceeded(&self, telemetry: &LastKnown) -> Vec<(AlarmKind, f32)> {
+        let mut exceeded = vec![];
+        if let (Some(max), Some(temperature)) = (self.max_temperature, telemetry.temperature) {
+            if temperature.value > max {
+                exceeded.push((AlarmKind::OverTemperature, temperature.value));
+            }
+        }
+        if let (Some(max), Some(current)) = (self.max_current, telemetry.current) {
+            if current.value > max {
+                exceeded.push((AlarmKind::OverCurrent, current.value));
+            }
+        }
+        if let (Some(min), Some(voltage)) = (self.min_voltage, telemetry.voltage) {
+            if voltage.value < min {
+                exceeded.push((AlarmKind::UnderVoltage, voltage.value));
+            }
+        }
+        exceeded
+    }
+
+    /// Whether a raised alarm of `kind` can clear
+    ///
+    /// Value has to be back within the limit by more than the hysteresis band.
+    /// Alarms of limits that were removed or values that are missing clear right away.
+    #[cfg(feature = "runtime-tokio")]
+    pub(crate) fn cleared(&self, kind: AlarmKind, telemetry: &LastKnown) -> bool {
+        match kind {
+            AlarmKind::OverTemperature => match (self.max_temperature, telemetry.temperature) {
+                (Some(max), Some(temperature)) => {
+                    temperature.value < max - self.temperature_hysteresis
+                }
+                _ => true,
+            },
+            AlarmKind::OverCurrent => match (self.max_current, telemetry.current) {
+                (Some(max), Some(current)) => current.value < max - self.current_hysteresis,
+                _ => true,
+            },
+            AlarmKind::UnderVoltage => match (self.min_voltage, telemetry.voltage) {
+                (Some(min), Some(voltage)) => voltage.value > min + self.voltage_hysteresis,
+                _ => true,
+            },
+        }
+    }
+}
+
+/// Limit that was exceeded
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AlarmKind {
+    OverTemperature,
+    OverCurrent,
+    UnderVoltage,
+}
+
+/// Servo exceeded one of its [AlarmThresholds]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Alarm {
+    /// ID of the servo
+    pub id: u8,
+    pub kind: AlarmKind,
+    /// Value that exceeded the limit. In celsius, amps or volts
+    pub value: f32,
+}
+
+/// Colors for the LED on the servo
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LedColor {
+    /// No color
+    Off = 0,
+    Red = 1,
+    Green = 2,
+    Blue = 3,
+    Yellow = 4,
+    Cyan = 5,
+    Magenta = 6,
+    White = 7,
+}
+
+impl LedColor {
+    pub(crate) fn from_i32(number: i32) -> Result<LedColor, LssDriverError> {
+        match number {
+            0 => Ok(LedColor::Off),
+            1 => Ok(LedColor::Red),
+            2 => Ok(LedColor::Green),
+            3 => Ok(LedColor::Blue),
+            4 => Ok(LedColor::Yellow),
+            5 => Ok(LedColor::Cyan),
+            6 => Ok(LedColor::Magenta),
+            7 => Ok(LedColor::White),
+            value => Err(LssDriverError::PacketParsingError(format!(
+                "Failed parsing LedColor from {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Status of the motor as responded to status query
+/// If status is safe mode you can use `query_safety_status` to see more details
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MotorStatus {
+    Unknown = 0,
+    Limp = 1,
+    FreeMoving = 2,
+    Accelerating = 3,
+    Traveling = 4,
+    Decelerating = 5,
+    Holding = 6,
+    OutsideLimits = 7,
+    Stuck = 8,
+    Blocked = 9,
+    /// You can use `query_safety_status` to see more details
+    SafeMode = 10,
+}
+
+impl MotorStatus {
+    pub(crate) fn from_i32(number: i32) -> Result<MotorStatus, LssDriverError> {
+        match number {
+            0 => Ok(MotorStatus::Unknown),
+            1 => Ok(MotorStatus::Limp),
+            2 => Ok(MotorStatus::FreeMoving),
+            3 => Ok(MotorStatus::Accelerating),
+            4 => Ok(MotorStatus::Traveling),
+            5 => Ok(MotorStatus::Decelerating),
+            6 => Ok(MotorStatus::Holding),
+            7 => Ok(MotorStatus::OutsideLimits),
+            8 => Ok(MotorStatus::Stuck),
+            9 => Ok(MotorStatus::Blocked),
+            10 => Ok(MotorStatus::SafeMode),
+            value => Err(LssDriverError::PacketParsingError(format!(
+                "Failed parsing MotorStatus from {}",
+                value
+            ))),
+        }
+    }
+}
+
+bitflags! {
+    /// Status of the motor as a set of flags
+    ///
+    /// Built from the single status code the servo replies with,
+    /// so at most one flag is set. `Unknown` results in no flags.
+    /// Helpers like [is_moving](StatusFlags::is_moving) group related states.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct StatusFlags: u32 {
+        const LIMP = 1 << 0;
+        const FREE_MOVING = 1 << 1;
+        const ACCELERATING = 1 << 2;
+        const TRAVELING = 1 << 3;
+        const DECELERATING = 1 << 4;
+        const HOLDING = 1 << 5;
+        const OUTSIDE_LIMITS = 1 << 6;
+        const STUCK = 1 << 7;
+        const BLOCKED = 1 << 8;
+        const SAFE_MODE = 1 << 9;
+    }
+}
+
+impl StatusFlags {
+    pub(crate) fn from_i32(number: i32) -> Result<StatusFlags, LssDriverError> {
+        let flags = match MotorStatus::from_i32(number)? {
+            MotorStatus::Unknown => StatusFlags::empty(),
+            MotorStatus::Limp => StatusFlags::LIMP,
+            MotorStatus::FreeMoving => StatusFlags::FREE_MOVING,
+            MotorStatus::Accelerating => StatusFlags::ACCELERATING,
+            MotorStatus::Traveling => StatusFlags::TRAVELING,
+            MotorStatus::Decelerating => StatusFlags::DECELERATING,
+            MotorStatus::Holding => StatusFlags::HOLDING,
+            MotorStatus::OutsideLimits => StatusFlags::OUTSIDE_LIMITS,
+            MotorStatus::Stuck => StatusFlags::STUCK,
+            MotorStatus::Blocked => StatusFlags::BLOCKED,
+            MotorStatus::SafeMode => StatusFlags::SAFE_MODE,
+        };
+        Ok(flags)
+    }
+
+    /// Motor is actively holding position
+    pub fn is_holding(&self) -> bool {
+        self.contains(StatusFlags::HOLDING)
+    }
+
+    /// Motor is accelerating, traveling or decelerating
+    pub fn is_moving(&self) -> bool {
+        self.intersects(
+            StatusFlags::ACCELERATING | StatusFlags::TRAVELING | StatusFlags::DECELERATING,
+        )
+    }
+
+    /// Motor is limp
+    pub fn is_limp(&self) -> bool {
+        self.contains(StatusFlags::LIMP)
+    }
+
+    /// Motor is outside of its angular limits
+    pub fn is_outside_limits(&self) -> bool {
+        self.contains(StatusFlags::OUTSIDE_LIMITS)
+    }
+
+    /// Motor is either stuck or blocked
+    pub fn stall_detected(&self) -> bool {
+        self.intersects(StatusFlags::STUCK | StatusFlags::BLOCKED)
+    }
+}
+
+/// Reason why status mode is engaged
+/// if `query_status` doesn't return `SafeMode` this should be `NoLimits`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SafeModeStatus {
+    /// Motor is not in safety mode
+    NoLimits = 0,
+    /// This probably means that motor was overloaded
+    CurrentLimit = 1,
+    /// Voltage is either too high or too low.  
+    /// Query voltage to get more info
+    InputVoltageOutOfRange = 2,
+    /// You can query temperature to see if it's high
+    TemperatureLimit = 3,
+}
+
+impl SafeModeStatus {
+    pub(crate) fn from_i32(number: i32) -> Result<SafeModeStatus, LssDriverError> {
+        match number {
+            0 => Ok(SafeModeStatus::NoLimits),
+            1 => Ok(SafeModeStatus::CurrentLimit),
+            2 => Ok(SafeModeStatus::InputVoltageOutOfRange),
+            3 => Ok(SafeModeStatus::TemperatureLimit),
+            value => Err(LssDriverError::PacketParsingError(format!(
+                "Failed parsing SafeModeStatus from {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Rotation direction of the servo (gyre)
+///
+/// Useful for mirrored joints
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Default direction
+    Clockwise = 1,
+    CounterClockwise = -1,
+}
+
+impl Direction {
+    pub(crate) fn from_i32(number: i32) -> Result<Direction, LssDriverError> {
+        match number {
+            1 => Ok(Direction::Clockwise),
+            -1 => Ok(Direction::CounterClockwise),
+            value => Err(LssDriverError::PacketParsingError(format!(
+                "Failed parsing Direction from {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Version of the motor
+#[derive(Clone, Debug, PartialEq)]
+pub enum Model {
+    /// Standard model
+    ST1,
+    /// High speed model
+    HS1,
+    /// High torque model
+    HT1,
+    /// Other model.  
+    /// Shouldn't happen unless new motors were added later
+    Other(String),
+}
+
+impl Model {
+    pub(crate) fn from_str(model: &str) -> Model {
+        match model {
+            "LSS-ST1" => Model::ST1,
+            "LSS-HS1" => Model::HS1,
+            "LSS-HT1" => Model::HT1,
+            other => Model::Other(other.to_owned()),
+        }
+    }
+}
+
+/// Firmware version of the motor
+///
+/// Versions with fewer components have the missing ones set to 0
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FirmwareVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl FirmwareVersion {
+    pub(crate) fn from_str(version: &str) -> Result<FirmwareVersion, LssDriverError> {
+        let parse_error = || {
+            LssDriverError::PacketParsingError(format!(
+                "Failed parsing FirmwareVersion from {}",
+                version
+            ))
+        };
+        let mut components = [0_u32; 3];
+        let mut parts = version.split('.');
+        for component in components.iter_mut() {
+            if let Some(part) = parts.next() {
+                *component = part.parse().map_err(|_| parse_error())?;
+            }
+        }
+        if parts.next().is_some() {
+            return Err(parse_error());
+        }
+        let [major, minor, patch] = components;
+        Ok(FirmwareVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Which status should trigger LED blinking
+/// Can be combined in a list
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LedBlinking {
+    NoBlinking = 0,
+    Limp = 1,
+    Holding = 2,
+    Accelerating = 4,
+    Decelerating = 8,
+    Free = 16,
+    Travelling = 32,
+    AlwaysBlink = 63,
+}
+
+impl LedBlinking {
+    pub(crate) fn from_i32_mask(number: i32) -> Result<Vec<LedBlinking>, LssDriverError> {
+        match number {
+            0 => Ok(vec![LedBlinking::NoBlinking]),
+            63 => Ok(vec![LedBlinking::AlwaysBlink]),
+            1..=62 => Ok([
+                LedBlinking::Limp,
+                LedBlinking::Holding,
+                LedBlinking::Accelerating,
+                LedBlinking::Decelerating,
+                LedBlinking::Free,
+                LedBlinking::Travelling,
+            ]
+            .into_iter()
+            .filter(|mode| number & *mode as i32 != 0)
+            .collect()),
+            value => Err(LssDriverError::PacketParsingError(format!(
+                "Failed parsing LedBlinking from {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Motion profile mode servo is expected to be in
+///
+/// Used to guard against moves behaving differently than intended
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExpectedMode {
+    /// Motion profile enabled (EM1)
+    /// Servo follows a motion curve using angular acceleration and deceleration
+    Profiled,
+    /// Motion profile disabled (EM0)
+    /// Servo moves towards target at full speed filtered by filter position count
+    Unprofiled,
+}
+
+impl ExpectedMode {
+    pub(crate) fn from_motion_profile(motion_profile: bool) -> ExpectedMode {
+        if motion_profile {
+            ExpectedMode::Profiled
+        } else {
+            ExpectedMode::Unprofiled
+        }
+    }
+}
+
+/// Which copy of a setting a query should return
+///
+/// Settings changed without the configure variant of a command only last for the current session.
+/// Comparing both scopes shows whether there are unsaved changes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QueryScope {
+    /// Value currently used by the servo
+    Session,
+    /// Value saved to EEPROM and loaded after reset
+    Configured,
+}
+
+/// Retry policy applied to queries
+///
+/// Queries that time out or receive a reply that can't be parsed are repeated.
+/// Delay between attempts starts at `backoff` and doubles after every attempt.
+/// Commands that don't expect a reply are never repeated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, backoff: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff,
+        }
+    }
+
+    /// Don't retry
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new(1, Duration::ZERO)
+    }
+
+    /// Delay before attempt following `attempt`
+    pub(crate) fn delay_after(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::none()
+    }
+}
+
+/// Limits how fast frames are written to the bus
+///
+/// Some USB-serial adapters and long daisy chains drop characters when commands are sent back to back.
+/// Both limits are combined and the stricter one is applied.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pacing {
+    /// Maximum number of frames written per second
+    pub max_frames_per_second: Option<u32>,
+    /// Minimum time between the start of two consecutive frames
+    pub min_gap: Duration,
+}
+
+impl Pacing {
+    pub fn new(max_frames_per_second: Option<u32>, min_gap: Duration) -> Pacing {
+        Pacing {
+            max_frames_per_second,
+            min_gap,
+        }
+    }
+
+    /// Limit only number of frames per second
+    pub fn max_frames_per_second(max_frames_per_second: u32) -> Pacing {
+        Pacing::new(Some(max_frames_per_second), Duration::ZERO)
+    }
+
+    /// Limit only gap between frames
+    pub fn min_gap(min_gap: Duration) -> Pacing {
+        Pacing::new(None, min_gap)
+    }
+
+    /// Shortest allowed time between two frames
+    pub(crate) fn interval(&self) -> Duration {
+        let rate_gap = match self.max_frames_per_second {
+            Some(0) | None => Duration::ZERO,
+            Some(frames) => Duration::from_secs(1) / frames,
+        };
+        rate_gap.max(self.min_gap)
+    }
+}
+
+/// Direction control for buses behind an RS-485 transceiver
+///
+/// RTS drives the DE/RE pins of the transceiver.
+/// It's switched to transmit before each command and back to receive once the command left the port.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rs485Config {
+    /// RTS level used while transmitting
+    pub rts_active_high: bool,
+    /// Delay between enabling the transmitter and sending the command
+    pub delay_before_send: Duration,
+    /// Delay between the end of the command and enabling the receiver
+    pub delay_after_send: Duration,
+}
+
+impl Rs485Config {
+    pub fn new(delay_before_send: Duration, delay_after_send: Duration) -> Rs485Config {
+        Rs485Config {
+            rts_active_high: true,
+            delay_before_send,
+            delay_after_send,
+        }
+    }
+}
+
+impl Default for Rs485Config {
+    fn default() -> Self {
+        Rs485Config::new(Duration::ZERO, Duration::ZERO)
+    }
+}
+
+/// Baud rates supported by the servo firmware
+///
+/// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HBaudRate)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BaudRate {
+    Baud9600 = 9600,
+    Baud19200 = 19200,
+    Baud38400 = 38400,
+    Baud57600 = 57600,
+    /// Default baud rate
+    Baud115200 = 115200,
+    Baud230400 = 230400,
+    Baud250000 = 250000,
+    Baud460800 = 460800,
+    Baud500000 = 500000,
+}
+
+impl TryFrom<u32> for BaudRate {
+    type Error = LssDriverError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            9600 => Ok(BaudRate::Baud9600),
+            19200 => Ok(BaudRate::Baud19200),
+            38400 => Ok(BaudRate::Baud38400),
+            57600 => Ok(BaudRate::Baud57600),
+            115200 => Ok(BaudRate::Baud115200),
+            230400 => Ok(BaudRate::Baud230400),
+            250000 => Ok(BaudRate::Baud250000),
+            460800 => Ok(BaudRate::Baud460800),
+            500000 => Ok(BaudRate::Baud500000),
+            value => Err(LssDriverError::UnsupportedBaudRate(value)),
+        }
+    }
+}
+
+impl From<BaudRate> for u32 {
+    fn from(baud_rate: BaudRate) -> u32 {
+        baud_rate as u32
+    }
+}
+
+/// Result of probing the bus for servos sharing an ID
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdConflictReport {
+    /// IDs that got exactly one valid reply
+    pub responding: Vec<u8>,
+    /// IDs that got several, garbled or only unframeable replies. Likely more than one servo uses them
+    pub conflicting: Vec<u8>,
+}
+
+impl IdConflictReport {
+    /// No conflicts were detected
+    pub fn is_ok(&self) -> bool {
+        self.conflicting.is_empty()
+    }
+}
+
+/// Telemetry snapshot of a single servo
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ServoTelemetry {
+    /// Absolute position in degrees
+    pub position: f32,
+    /// Speed in degrees per second
+    pub speed: f32,
+    /// Voltage in volts
+    pub voltage: f32,
+    /// Temperature in celsius
+    pub temperature: f32,
+    /// Current in Amps
+    pub current: f32,
+    /// Status of the motor
+    pub status: MotorStatus,
+}
+
+/// Modifiers used for some commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandModifier {
+    /// only for P commands
+    /// microseconds per second
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HSpeed28S2CSD29modifier)
+    Speed(u32),
+    /// Only for D or MD commands
+    /// tenths of degrees per second
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HSpeed28S2CSD29modifier)
+    SpeedDegrees(u32),
+    /// Useful for (P, D, MD) actions
+    /// in milliseconds
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HTimedmove28T29modifier)
+    Timed(u32),
+    /// Useful for (P, D, MD) actions
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HTimedmove28T29modifier)
+    TimedDuration(Duration),
+    /// Useful with (D; MD; WD; WR)
+    /// in mA
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HCurrentHalt26Hold28CH29modifier)
+    CurrentHold(u32),
+    /// Useful with (D; MD; WD; WR.)
+    /// in mA
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HCurrentLimp28CL29modifier)
+    CurrentLimp(u32),
+    /// Special modifier that allows you to use modifiable commands with no modifiers
+    None,
+    /// Useful in case there is a new modifier that is not supported by this library
+    Custom(&'static str, i32),
+}
+
+impl CommandModifier {
+    pub fn to_msg(&self) -> String {
+        use CommandModifier::*;
+        // this is pretty inefficient because it's all using strings. But that's okay for now
+        match self {
+            Speed(speed) => format!("S{}", speed),
+            SpeedDegrees(speed) => format!("SD{}", speed),
+            Timed(time) => format!("T{}", time),
+            TimedDuration(time) => format!("T{}", time.as_millis()),
+            CurrentHold(current) => format!("CH{}", current),
+            CurrentLimp(current) => format!("CL{}", current),
+            CommandModifier::None => String::from(""),
+            Custom(text, value) => format!("{}{}", text, value),
+        }
+    }
+
+    /// Name and value of the modifier as used by [protocol](crate::protocol)
+    pub(crate) fn as_pair(&self) -> Option<(&'static str, i64)> {
+        use CommandModifier::*;
+        match self {
+            Speed(speed) => Some(("S", *speed as i64)),
+            SpeedDegrees(speed) => Some(("SD", *speed as i64)),
+            Timed(time) => Some(("T", *time as i64)),
+            TimedDuration(time) => Some(("T", time.as_millis() as i64)),
+            CurrentHold(current) => Some(("CH", *current as i64)),
+            CurrentLimp(current) => Some(("CL", *current as i64)),
+            CommandModifier::None => Option::None,
+            Custom(text, value) => Some((text, *value as i64)),
+        }
+    }
+
+    pub fn vec_to_msg(modifiers: &[CommandModifier]) -> String {
+        let mut buffer = String::new();
+        for modifier in modifiers {
+            buffer.push_str(&modifier.to_msg());
+        }
+        buffer
+    }
+}
+
+/// Move command with stacked modifiers
+///
+/// Built using chained calls and sent with [execute](crate::LSSDriver::execute).
+/// Modifiers are sent in the order they were added.
+///
+/// # Example
+///
+/// ```no_run
+/// use lss_driver::{LSSDriver, MoveCommand};
+/// use std::time::Duration;
+/// async fn async_main(){
+///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+///     let command = MoveCommand::to(90.0)
+///         .speed_dps(45.0)
+///         .timed(Duration::from_millis(800))
+///         .current_limit_ma(600);
+///     driver.execute(5, command).await;
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveCommand {
+    position: f32,
+    relative: bool,
+    modifiers: Vec<CommandModifier>,
+    speeds: Vec<f32>,
+}
+
+impl MoveCommand {
+    /// Move to absolute position in degrees (D)
+    pub fn to(position: f32) -> MoveCommand {
+        MoveCommand {
+            position,
+            relative: false,
+            modifiers: vec![],
+            speeds: vec![],
+        }
+    }
+
+    /// Move relative to current position in degrees (MD)
+    pub fn by(delta: f32) -> MoveCommand {
+        MoveCommand {
+            position: delta,
+            relative: true,
+            modifiers: vec![],
+            speeds: vec![],
+        }
+    }
+
+    /// Limit speed in degrees per second (SD)
+    ///
+    /// Speeds outside of 0 to 180 make [execute](crate::LSSDriver::execute) fail with `ValueOutOfRange`
+    pub fn speed_dps(mut self, speed: f32) -> MoveCommand {
+        self.speeds.push(speed);
+        let native = units::deg_per_s_to_native(speed) as u32;
+        self.modifier(CommandModifier::SpeedDegrees(native))
+    }
+
+    /// Finish move in given duration (T)
+    pub fn timed(self, duration: Duration) -> MoveCommand {
+        self.modifier(CommandModifier::TimedDuration(duration))
+    }
+
+    /// Halt and hold if current exceeds limit in mA (CH)
+    pub fn current_limit_ma(self, current: u32) -> MoveCommand {
+        self.modifier(CommandModifier::CurrentHold(current))
+    }
+
+    /// Go limp if current exceeds limit in mA (CL)
+    pub fn current_limp_ma(self, current: u32) -> MoveCommand {
+        self.modifier(CommandModifier::CurrentLimp(current))
+    }
+
+    /// Add any other modifier
+    pub fn modifier(mut self, modifier: CommandModifier) -> MoveCommand {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    pub(crate) fn command(&self) -> &'static str {
+        if self.relative {
+            "MD"
+        } else {
+            "D"
+        }
+    }
+
+    pub(crate) fn is_relative(&self) -> bool {
+        self.relative
+    }
+
+    pub(crate) fn position(&self) -> f32 {
+        self.position
+    }
+
+    pub(crate) fn modifiers(&self) -> &[CommandModifier] {
+        &self.modifiers
+    }
+
+    /// Speeds requested with `speed_dps`, validated before the command is sent
+    pub(crate) fn speeds(&self) -> &[f32] {
+        &self.speeds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pacing_uses_stricter_limit() {
+        assert_eq!(Pacing::default().interval(), Duration::ZERO);
+        assert_eq!(
+            Pacing::max_frames_per_second(100).interval(),
+            Duration::from_millis(10)
+        );
+        assert_eq!(
+            Pacing::new(Some(100), Duration::from_millis(20)).interval(),
+            Duration::from_millis(20)
+        );
+        assert_eq!(
+            Pacing::new(Some(50), Duration::from_millis(5)).interval(),
+            Duration::from_millis(20)
+        );
+    }
+
+    #[test]
+    fn move_command_stacks_modifiers() {
+        let command = MoveCommand::to(90.0)
+            .speed_dps(45.0)
+            .timed(Duration::from_millis(800))
+            .current_limit_ma(600);
+        assert_eq!(command.command(), "D");
+        assert_eq!(
+            CommandModifier::vec_to_msg(command.modifiers()),
+            "SD450T800CH600"
+        );
+    }
+
+    #[test]
+    fn relative_move_command() {
+        let command = MoveCommand::by(-10.0).current_limp_ma(300);
+        assert_eq!(command.command(), "MD");
+        assert_eq!(CommandModifier::vec_to_msg(command.modifiers()), "CL300");
+    }
+
+    #[test]
+    fn retry_backoff_doubles() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(5));
+        assert_eq!(policy.delay_after(1), Duration::from_millis(5));
+        assert_eq!(policy.delay_after(2), Duration::from_millis(10));
+        assert_eq!(policy.delay_after(3), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn errors_are_distinguishable() {
+        let error = LssDriverError::PacketParsingError("Failed parsing value".to_owned());
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse data: Failed parsing value"
+        );
+        assert_eq!(
+            LssDriverError::SendingError.to_string(),
+            "Failed to send command"
+        );
+    }
+
+    #[test]
+    fn model_parses_other() {
+        let model = Model::from_str("something");
+        assert_eq!(model, Model::Other("something".to_owned()));
+    }
+
+    #[test]
+    fn firmware_version_parses() {
+        let version = FirmwareVersion::from_str("368").unwrap();
+        assert_eq!(
+            version,
+            FirmwareVersion {
+                major: 368,
+                minor: 0,
+                patch: 0
+            }
+        );
+        let version = FirmwareVersion::from_str("368.29.14").unwrap();
+        assert_eq!(version.to_string(), "368.29.14");
+        assert!(version > FirmwareVersion::from_str("368.3").unwrap());
+    }
+
+    #[test]
+    fn firmware_version_parse_fails() {
+        assert!(FirmwareVersion::from_str("").is_err());
+        assert!(FirmwareVersion::from_str("3.a").is_err());
+        assert!(FirmwareVersion::from_str("1.2.3.4").is_err());
+    }
+
+    #[test]
+    fn color_parse_fails() {
+        let color = LedColor::from_i32(42);
+        assert!(color.is_err());
+    }
+
+    #[test]
+    fn colors_parse() {
+        let params = vec![
+            (LedColor::Off, 0),
+            (LedColor::Red, 1),
+            (LedColor::Green, 2),
+            (LedColor::Blue, 3),
+            (LedColor::Yellow, 4),
+            (LedColor::Cyan, 5),
+            (LedColor::Magenta, 6),
+            (LedColor::White, 7),
+        ];
+        for (expected_color, int) in params {
+            let color = LedColor::from_i32(int).unwrap();
+            assert_eq!(expected_color, color);
+        }
+    }
+
+    #[test]
+    fn status_flags_parse_single_status() {
+        let flags = StatusFlags::from_i32(6).unwrap();
+        assert_eq!(flags, StatusFlags::HOLDING);
+        assert!(flags.is_holding());
+        assert!(!flags.is_moving());
+        assert!(!flags.stall_detected());
+    }
+
+    #[test]
+    fn status_flags_parse_grouped_states() {
+        let flags = StatusFlags::from_i32(4).unwrap();
+        assert_eq!(flags, StatusFlags::TRAVELING);
+        assert!(flags.is_moving());
+        assert!(!flags.stall_detected());
+
+        let flags = StatusFlags::from_i32(9).unwrap();
+        assert_eq!(flags, StatusFlags::BLOCKED);
+        assert!(flags.stall_detected());
+        assert!(!flags.is_moving());
+
+        let flags = StatusFlags::from_i32(7).unwrap();
+        assert!(flags.is_outside_limits());
+        assert!(!flags.is_limp());
+
+        assert!(StatusFlags::from_i32(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn status_flags_parse_fails() {
+        assert!(StatusFlags::from_i32(11).is_err());
+        assert!(StatusFlags::from_i32(4 | 1 << 4).is_err());
+        assert!(StatusFlags::from_i32(63).is_err());
+        assert!(StatusFlags::from_i32(-1).is_err());
+    }
+
+    #[test]
+    fn baud_rates_parse() {
+        let params = vec![
+            (BaudRate::Baud9600, 9600),
+            (BaudRate::Baud19200, 19200),
+            (BaudRate::Baud38400, 38400),
+            (BaudRate::Baud57600, 57600),
+            (BaudRate::Baud115200, 115200),
+            (BaudRate::Baud230400, 230400),
+            (BaudRate::Baud250000, 250000),
+            (BaudRate::Baud460800, 460800),
+            (BaudRate::Baud500000, 500000),
+        ];
+        for (expected_baud_rate, int) in params {
+            let baud_rate = BaudRate::try_from(int).unwrap();
+            assert_eq!(expected_baud_rate, baud_rate);
+            assert_eq!(u32::from(baud_rate), int);
+        }
+    }
+
+    #[test]
+    fn baud_rate_parse_fails() {
+        let baud_rate = BaudRate::try_from(115201);
+        assert!(matches!(
+            baud_rate,
+            Err(LssDriverError::UnsupportedBaudRate(115201))
+        ));
+    }
+
+    #[test]
+    fn direction_parse() {
+        assert_eq!(Direction::from_i32(1).unwrap(), Direction::Clockwise);
+        assert_eq!(
+            Direction::from_i32(-1).unwrap(),
+            Direction::CounterClockwise
+        );
+        assert!(Direction::from_i32(0).is_err());
+    }
+
+    #[test]
+    fn led_blinking_parse() {
+        assert_eq!(
+            LedBlinking::from_i32_mask(0).unwrap(),
+            vec![LedBlinking::NoBlinking]
+        );
+        assert_eq!(
+            LedBlinking::from_i32_mask(63).unwrap(),
+            vec![LedBlinking::AlwaysBlink]
+        );
+        assert_eq!(
+            LedBlinking::from_i32_mask(5).unwrap(),
+            vec![LedBlinking::Limp, LedBlinking::Accelerating]
+        );
+        assert!(LedBlinking::from_i32_mask(64).is_err());
+        assert!(LedBlinking::from_i32_mask(-1).is_err());
+    }
+
+    #[test]
+    fn motor_status_parse_fails() {
+        let status = MotorStatus::from_i32(42);
+        assert!(status.is_err());
+    }
+
+    #[test]
+    fn motor_status_parse() {
+        let params = vec![
+            (MotorStatus::Unknown, 0),
+            (MotorStatus::Limp, 1),
+            (MotorStatus::FreeMoving, 2),
+            (MotorStatus::Accelerating, 3),
+            (MotorStatus::Traveling, 4),
+            (MotorStatus::Decelerating, 5),
+            (MotorStatus::Holding, 6),
+            (MotorStatus::OutsideLimits, 7),
+            (MotorStatus::Stuck, 8),
+            (MotorStatus::Blocked, 9),
+            (MotorStatus::SafeMode, 10),
+        ];
+        for (expected_status, int) in params {
+            let status = MotorStatus::from_i32(int).unwrap();
+            assert_eq!(expected_status, status);
+        }
+    }
+}
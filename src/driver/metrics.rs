@@ -0,0 +1,293 @@
+//! Prometheus metrics
+//!
+//! Servo telemetry and bus statistics rendered in the Prometheus text exposition format
+//! and served over plain HTTP for scraping.
+//! [Exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/)
+
+use crate::driver::message_types::{BusMetrics, LastKnown, Timestamped};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+
+const MAX_REQUEST_LENGTH: usize = 4096;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Pause after a failed accept so running out of file descriptors doesn't spin
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Metric name, help text and value of a servo gauge
+type Gauge = (
+    &'static str,
+    &'static str,
+    fn(&LastKnown) -> Option<Timestamped<f32>>,
+);
+
+/// Render telemetry and bus statistics in the Prometheus text format
+///
+/// Servos are labeled with `id`. Values that were never received are left out.
+///
+/// # Arguments
+///
+/// * `telemetry` - Values by servo ID. e.g. from [TelemetryPoller::latest_all](crate::TelemetryPoller::latest_all)
+/// * `bus` - Bus statistics to include. e.g. from [LSSDriver::bus_metrics](crate::LSSDriver::bus_metrics)
+pub fn encode_metrics(telemetry: &HashMap<u8, LastKnown>, bus: Option<&BusMetrics>) -> String {
+    let mut ids: Vec<&u8> = telemetry.keys().collect();
+    ids.sort();
+    let gauges: [Gauge; 4] = [
+        (
+            "lss_servo_position_degrees",
+            "Position of the servo",
+            |telemetry| telemetry.position,
+        ),
+        (
+            "lss_servo_voltage_volts",
+            "Input voltage of the servo",
+            |telemetry| telemetry.voltage,
+        ),
+        (
+            "lss_servo_temperature_celsius",
+            "Temperature of the servo",
+            |telemetry| telemetry.temperature,
+        ),
+        (
+            "lss_servo_current_amps",
+            "Current drawn by the servo",
+            |telemetry| telemetry.current,
+        ),
+    ];
+    let mut output = String::new();
+    for (name, help, value) in gauges {
+        header(&mut output, name, help, "gauge");
+        for id in &ids {
+            // exposition format only knows +Inf, -Inf and NaN spelled that way
+            if let Some(value) = value(&telemetry[id]).filter(|value| value.value.is_finite()) {
+                let _ = writeln!(output, "{}{{id=\"{}\"}} {}", name, id, value.value);
+            }
+        }
+    }
+    header(
+        &mut output,
+        "lss_servo_telemetry_age_seconds",
+        "Time since the newest value of the servo was received",
+        "gauge",
+    );
+    for id in &ids {
+        let telemetry = &telemetry[id];
+        let newest = [
+            telemetry.position,
+            telemetry.voltage,
+            telemetry.temperature,
+            telemetry.current,
+        ]
+        .iter()
+        .flatten()
+        .map(|value| value.age())
+        .min();
+        if let Some(age) = newest {
+            let _ = writeln!(
+                output,
+                "lss_servo_telemetry_age_seconds{{id=\"{}\"}} {}",
+                id,
+                age.as_secs_f64()
+            );
+        }
+    }
+    if let Some(bus) = bus {
+        let counters = [
+            (
+                "lss_bus_commands_sent_total",
+                "Frames written to the bus",
+                bus.commands_sent(),
+            ),
+            (
+                "lss_bus_responses_received_total",
+                "Frames received from the bus",
+                bus.responses_received(),
+            ),
+            (
+                "lss_bus_timeouts_total",
+                "Receives that ran into the receive timeout",
+                bus.timeouts(),
+            ),
+            (
+                "lss_bus_reconnects_total",
+                "Times the transport was reopened",
+                bus.reconnects(),
+            ),
+            (
+                "lss_bus_parse_errors_total",
+                "Replies that couldn't be parsed",
+                bus.parse_errors(),
+            ),
+        ];
+        for (name, help, value) in counters {
+            header(&mut output, name, help, "counter");
+            let _ = writeln!(output, "{} {}", name, value);
+        }
+    }
+    output
+}
+
+fn header(output: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, kind);
+}
+
+/// HTTP endpoint serving metrics on `GET /metrics`
+///
+/// Stops serving when dropped. Requires the `metrics` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use lss_driver::{encode_metrics, LSSDriver, MetricsServer};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// async fn async_main(){
+///     let handle = LSSDriver::with_baud_rate("COM1", 115200).unwrap().spawn();
+///     let bus = handle.bus_metrics().await.unwrap();
+///     let poller = Arc::new(handle.telemetry_poller(&[1, 2, 3], Duration::from_secs(1)));
+///     let server = MetricsServer::bind("0.0.0.0:9100", move || {
+///         encode_metrics(&poller.latest_all(), Some(&bus))
+///     })
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsServer {
+    /// Start serving
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to listen on. e.g. `0.0.0.0:9100`
+    /// * `render` - Called for every scrape. e.g. wrapping [encode_metrics]
+    pub async fn bind(
+        addr: impl ToSocketAddrs,
+        render: impl Fn() -> String + Send + Sync + 'static,
+    ) -> io::Result<MetricsServer> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let render = Arc::new(render);
+        let task = tokio::spawn(async move {
+            // runs until the server is dropped. Accept errors like EMFILE are transient
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(_) => {
+                        tokio::time::sleep(ACCEPT_BACKOFF).await;
+                        continue;
+                    }
+                };
+                let render = render.clone();
+                tokio::spawn(async move {
+                    // scrapers retry on their own, failed requests are dropped
+                    let _ = timeout(REQUEST_TIMEOUT, respond(stream, &*render)).await;
+                });
+            }
+        });
+        Ok(MetricsServer { local_addr, task })
+    }
+
+    /// Address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    render: &(dyn Fn() -> String + Send + Sync),
+) -> io::Result<()> {
+    let mut request = vec![];
+    let mut buffer = [0_u8; 512];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_LENGTH {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let (status, body) = if request.starts_with(b"GET /metrics ") {
+        ("200 OK", render())
+    } else {
+        ("404 Not Found", String::new())
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::message_types::DriverEvent;
+    use crate::driver::serial_driver::LssCommand;
+
+    #[test]
+    fn encodes_telemetry() {
+        let mut telemetry = HashMap::new();
+        telemetry.insert(
+            2,
+            LastKnown {
+                voltage: Some(Timestamped::now(11.2)),
+                ..LastKnown::default()
+            },
+        );
+        telemetry.insert(
+            1,
+            LastKnown {
+                voltage: Some(Timestamped::now(12.0)),
+                current: Some(Timestamped::now(f32::NAN)),
+                ..LastKnown::default()
+            },
+        );
+        let output = encode_metrics(&telemetry, None);
+        assert!(output.contains(
+            "# TYPE lss_servo_voltage_volts gauge\nlss_servo_voltage_volts{id=\"1\"} 12\nlss_servo_voltage_volts{id=\"2\"} 11.2\n"
+        ));
+        assert!(!output.contains("lss_servo_current_amps{"));
+        assert!(output.contains("lss_servo_telemetry_age_seconds{id=\"2\"}"));
+        assert!(!output.contains("lss_bus"));
+    }
+
+    #[test]
+    fn encodes_bus_counters() {
+        let bus = BusMetrics::default();
+        bus.record(&DriverEvent::CommandSent(LssCommand::simple(1, "QD")));
+        bus.record(&DriverEvent::CommandSent(LssCommand::simple(1, "QD")));
+        bus.record(&DriverEvent::Timeout);
+        let output = encode_metrics(&HashMap::new(), Some(&bus));
+        assert!(output.contains(
+            "# TYPE lss_bus_commands_sent_total counter\nlss_bus_commands_sent_total 2\n"
+        ));
+        assert!(output.contains("lss_bus_timeouts_total 1\n"));
+        assert!(output.contains("lss_bus_responses_received_total 0\n"));
+    }
+}
@@ -0,0 +1,6743 @@
+//! Async driver and its transports
+//!
+//! Everything except the `no_std` [protocol](crate::protocol) module lives here and needs the `std` feature.
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod bus_manager;
+mod command_spec;
+#[cfg(feature = "runtime-tokio")]
+mod dispatcher;
+mod driver_builder;
+#[cfg(feature = "runtime-tokio")]
+mod driver_handle;
+mod io_driver;
+mod message_types;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "rfc2217")]
+mod rfc2217_driver;
+mod safety_guard;
+mod serial_driver;
+mod servo_handle;
+mod shared_driver;
+#[cfg(feature = "runtime-tokio")]
+mod split_driver;
+#[cfg(any(feature = "tcp", all(unix, feature = "uds")))]
+mod stream_driver;
+#[cfg(feature = "tcp")]
+mod tcp_driver;
+mod telemetry_history;
+#[cfg(feature = "telemetry-log")]
+mod telemetry_logger;
+#[cfg(feature = "runtime-tokio")]
+mod telemetry_poller;
+#[cfg(all(unix, feature = "testutil"))]
+pub mod testutil;
+mod time;
+#[cfg(feature = "udp")]
+mod udp_driver;
+#[cfg(all(unix, feature = "uds"))]
+mod uds_driver;
+pub mod units;
+#[cfg(feature = "websocket")]
+mod websocket_driver;
+
+use crate::BROADCAST_ID;
+pub use async_trait::async_trait;
+pub use bus_manager::{BusId, BusManager};
+pub use command_spec::{LssCommandSpec, LssQuery};
+#[cfg(feature = "runtime-tokio")]
+pub use dispatcher::Dispatcher;
+pub use driver_builder::LSSDriverBuilder;
+#[cfg(feature = "runtime-tokio")]
+pub use driver_handle::{DriverHandle, Priority};
+use futures::future::BoxFuture;
+use futures::Stream;
+pub use message_types::*;
+#[cfg(feature = "metrics")]
+pub use metrics::{encode_metrics, MetricsServer};
+pub use safety_guard::{SafeAction, SafetyGuard};
+#[cfg(feature = "runtime-tokio")]
+use serial_driver::FramedSerialDriver;
+#[cfg(feature = "runtime-tokio")]
+pub use serial_driver::SerialOptions;
+use serial_driver::DEFAULT_RECEIVE_TIMEOUT;
+pub use serial_driver::{FramedDriver, LssCommand, LssResponse};
+pub use servo_handle::ServoHandle;
+pub use shared_driver::SharedLSSDriver;
+#[cfg(feature = "runtime-tokio")]
+pub use split_driver::{CommandSender, ResponseReceiver};
+use std::{
+    collections::HashMap,
+    iter, str,
+    time::{Duration, Instant},
+};
+pub use telemetry_history::{Stats, TelemetryHistory, TelemetryStats};
+#[cfg(feature = "telemetry-log")]
+pub use telemetry_logger::{LogFormat, TelemetryLogger};
+#[cfg(feature = "runtime-tokio")]
+pub use telemetry_poller::TelemetryPoller;
+use tokio::sync::broadcast;
+#[cfg(feature = "runtime-tokio")]
+pub use tokio_serial::{DataBits, FlowControl, Parity, StopBits};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+#[cfg(any(
+    feature = "tcp",
+    feature = "udp",
+    feature = "rfc2217",
+    feature = "websocket"
+))]
+const NETWORK_RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+const CONNECTION_EVENT_CAPACITY: usize = 16;
+/// Number of bus events buffered for each subscriber
+const DRIVER_EVENT_CAPACITY: usize = 64;
+/// ID servos come back with after restoring factory defaults
+const FACTORY_DEFAULT_ID: u8 = 0;
+const RESET_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const MOTION_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const IDENTIFY_BLINK_INTERVAL: Duration = Duration::from_millis(250);
+const HARD_STOP_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+/// Inrush current right after the servo starts moving would look like a hard stop
+const HARD_STOP_SETTLE_TIME: Duration = Duration::from_millis(200);
+/// Consecutive samples over the threshold needed to detect a hard stop
+const HARD_STOP_SAMPLES: u32 = 3;
+
+/// Driver for the LSS servo
+pub struct LSSDriver {
+    driver: Box<dyn FramedDriver + Send + Sync>,
+    position_filter_alpha: f32,
+    filtered_positions: HashMap<u8, f32>,
+    last_known: HashMap<u8, LastKnown>,
+    /// Session angular range in degrees last set or queried per servo. Used to validate moves
+    angular_ranges: HashMap<u8, f32>,
+    receive_timeout: Duration,
+    retry_policy: RetryPolicy,
+    verify_writes: bool,
+    reply_pending: bool,
+    /// Servo a cancelled motion routine left moving. Halted before the next command is written
+    halt_pending: Option<u8>,
+    auto_reconnect: Option<RetryPolicy>,
+    connection_events: broadcast::Sender<ConnectionEvent>,
+    events: broadcast::Sender<DriverEvent>,
+    bus_metrics: BusMetrics,
+    pacing: Pacing,
+    last_frame: Option<Instant>,
+}
+
+impl LSSDriver {
+    /// Create new driver on a serial port with default settings
+    ///
+    /// Default baud_rate is 115200
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - Port to use. e.g. COM1 or /dev/ttyACM0
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// let mut driver = LSSDriver::new("COM1").unwrap();
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn new(port: &str) -> DriverResult<LSSDriver> {
+        let driver = FramedSerialDriver::new(port)?;
+        Ok(LSSDriver::with_driver(Box::new(driver)))
+    }
+
+    /// Create new driver on a serial port with custom baud rate
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - Port to use. e.g. COM1 or /dev/ttyACM0
+    /// * `baud_rate` - Baudrate. e.g. 115200 or `BaudRate::Baud115200.into()`
+    ///
+    /// Fails with `UnsupportedBaudRate` if the servo firmware doesn't support `baud_rate`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{BaudRate, LSSDriver};
+    /// let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    /// let mut driver = LSSDriver::with_baud_rate("COM1", BaudRate::Baud115200.into()).unwrap();
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn with_baud_rate(port: &str, baud_rate: u32) -> DriverResult<LSSDriver> {
+        let driver = FramedSerialDriver::with_baud_rate(port, baud_rate)?;
+        Ok(LSSDriver::with_driver(Box::new(driver)))
+    }
+
+    /// Create new driver on a serial port connected to an RS-485 transceiver
+    ///
+    /// RTS is used to switch the transceiver between transmitting and receiving.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Port to use. e.g. COM1 or /dev/ttyUSB0
+    /// * `baud_rate` - Baudrate. e.g. 115200
+    /// * `rs485` - RTS polarity and delays around each command
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, Rs485Config};
+    /// use std::time::Duration;
+    /// let rs485 = Rs485Config::new(Duration::from_micros(100), Duration::from_micros(100));
+    /// let mut driver = LSSDriver::with_rs485("/dev/ttyUSB0", 115200, rs485).unwrap();
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn with_rs485(port: &str, baud_rate: u32, rs485: Rs485Config) -> DriverResult<LSSDriver> {
+        let options = SerialOptions {
+            rs485: Some(rs485),
+            ..SerialOptions::new(baud_rate)
+        };
+        LSSDriver::with_options(port, options)
+    }
+
+    /// Create new driver on a serial port with custom port settings
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - Port to use. e.g. COM1 or /dev/ttyACM0
+    /// * `options` - Baud rate, framing, flow control and DTR/RTS state on open
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, SerialOptions};
+    /// // don't reset boards that restart when DTR toggles
+    /// let options = SerialOptions {
+    ///     dtr_on_open: Some(false),
+    ///     rts_on_open: Some(false),
+    ///     ..SerialOptions::new(115200)
+    /// };
+    /// let mut driver = LSSDriver::with_options("/dev/ttyACM0", options).unwrap();
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn with_options(port: &str, options: SerialOptions) -> DriverResult<LSSDriver> {
+        let driver = FramedSerialDriver::with_options(port, options)?;
+        Ok(LSSDriver::with_driver(Box::new(driver)))
+    }
+
+    /// Create new driver talking to servos behind a serial over TCP bridge
+    ///
+    /// Works with bridges that forward raw bytes such as ser2net or ESP-Link.
+    /// Reply timeout defaults to 100ms to account for network latency.
+    /// Baud rate is configured on the bridge.
+    ///
+    /// Requires the `tcp` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the bridge. e.g. `192.168.1.50:2000`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::connect_tcp("192.168.1.50:2000").await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "tcp")]
+    pub async fn connect_tcp<A: tokio::net::ToSocketAddrs>(address: A) -> DriverResult<LSSDriver> {
+        let driver = tcp_driver::TcpFramedDriver::connect(address).await?;
+        let mut driver = LSSDriver::with_driver(Box::new(driver));
+        driver.set_receive_timeout(NETWORK_RECEIVE_TIMEOUT);
+        Ok(driver)
+    }
+
+    /// Create new driver talking to servos behind a serial over UDP bridge
+    ///
+    /// Each command is sent as one datagram. Useful on wireless links where
+    /// TCP retransmissions would stall a control loop.
+    /// Lost replies return `TimeoutError`. Use [set_retry_policy](LSSDriver::set_retry_policy) to resend queries.
+    /// Reply timeout defaults to 100ms to account for network latency.
+    ///
+    /// Requires the `udp` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the bridge. e.g. `192.168.1.50:2000`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::connect_udp("192.168.1.50:2000").await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "udp")]
+    pub async fn connect_udp<A: tokio::net::ToSocketAddrs>(address: A) -> DriverResult<LSSDriver> {
+        let driver = udp_driver::UdpFramedDriver::connect(address).await?;
+        let mut driver = LSSDriver::with_driver(Box::new(driver));
+        driver.set_receive_timeout(NETWORK_RECEIVE_TIMEOUT);
+        Ok(driver)
+    }
+
+    /// Create new driver talking to servos behind a serial over UDP bridge that acknowledges commands
+    ///
+    /// Commands are resent until the bridge acknowledges them.
+    /// Every datagram starts with a big endian `u16` sequence number which the bridge echoes to acknowledge a command.
+    ///
+    /// Requires the `udp` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the bridge. e.g. `192.168.1.50:2000` or `[fd00::50]:2000`
+    /// * `resend` - Number of attempts and how long the first one waits for the acknowledgement
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, RetryPolicy};
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let resend = RetryPolicy::new(3, Duration::from_millis(20));
+    ///     let mut driver = LSSDriver::connect_udp_sequenced("192.168.1.50:2000", resend).await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "udp")]
+    pub async fn connect_udp_sequenced<A: tokio::net::ToSocketAddrs>(
+        address: A,
+        resend: RetryPolicy,
+    ) -> DriverResult<LSSDriver> {
+        let driver = udp_driver::UdpFramedDriver::connect(address)
+            .await?
+            .with_sequence_numbers(resend);
+        let mut driver = LSSDriver::with_driver(Box::new(driver));
+        driver.set_receive_timeout(NETWORK_RECEIVE_TIMEOUT);
+        Ok(driver)
+    }
+
+    /// Create new driver talking to servos behind an RFC2217 serial device server
+    ///
+    /// Baud rate of the remote port is configured by the driver, including
+    /// when it's changed with [change_baud_rate](LSSDriver::change_baud_rate).
+    /// Fails with `UnsupportedBaudRate` if the servo firmware doesn't support `baud_rate`
+    /// or when the server acknowledges a different baud rate.
+    /// Reply timeout defaults to 100ms to account for network latency.
+    ///
+    /// Requires the `rfc2217` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the device server. e.g. `192.168.1.50:4000`
+    /// * `baud_rate` - Baudrate. e.g. 115200
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::connect_rfc2217("192.168.1.50:4000", 115200).await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "rfc2217")]
+    pub async fn connect_rfc2217<A: tokio::net::ToSocketAddrs>(
+        address: A,
+        baud_rate: u32,
+    ) -> DriverResult<LSSDriver> {
+        let driver = rfc2217_driver::Rfc2217Driver::connect(address, baud_rate).await?;
+        let mut driver = LSSDriver::with_driver(Box::new(driver));
+        driver.set_receive_timeout(NETWORK_RECEIVE_TIMEOUT);
+        Ok(driver)
+    }
+
+    /// Create new driver talking to a WebSocket server that forwards to the servo bus
+    ///
+    /// Commands are sent as text messages. Replies can arrive as text or binary messages.
+    /// Only plain `ws://` URLs are supported.
+    /// Reply timeout defaults to 100ms to account for network latency.
+    ///
+    /// Requires the `websocket` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the server. e.g. `ws://192.168.1.50:9000/lss`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::connect_websocket("ws://192.168.1.50:9000/lss").await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "websocket")]
+    pub async fn connect_websocket(url: &str) -> DriverResult<LSSDriver> {
+        let driver = websocket_driver::WebSocketDriver::connect(url).await?;
+        let mut driver = LSSDriver::with_driver(Box::new(driver));
+        driver.set_receive_timeout(NETWORK_RECEIVE_TIMEOUT);
+        Ok(driver)
+    }
+
+    /// Create new driver talking to a local daemon over a unix domain socket
+    ///
+    /// Lets a single daemon own the serial port while multiple local processes share the bus.
+    /// The daemon is expected to forward raw bytes in both directions.
+    ///
+    /// Requires the `uds` feature. Unix only.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the socket. e.g. `/run/lss.sock`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::connect_unix("/run/lss.sock").await.unwrap();
+    /// }
+    /// ```
+    #[cfg(all(unix, feature = "uds"))]
+    pub async fn connect_unix<P: AsRef<std::path::Path>>(path: P) -> DriverResult<LSSDriver> {
+        let driver = uds_driver::UdsFramedDriver::connect(path).await?;
+        Ok(LSSDriver::with_driver(Box::new(driver)))
+    }
+
+    /// Create new driver on any byte stream implementing the `futures` IO traits
+    ///
+    /// Doesn't need a tokio runtime so it can be used with async-std, smol or other executors
+    /// together with their own serial port or socket implementations.
+    ///
+    /// # Arguments
+    ///
+    /// * `io` - Stream connected to the servo bus
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let stream = async_std::net::TcpStream::connect("192.168.1.20:2000").await.unwrap();
+    ///     let mut driver = LSSDriver::with_io(stream);
+    /// }
+    /// ```
+    pub fn with_io<T>(io: T) -> LSSDriver
+    where
+        T: futures::io::AsyncRead + futures::io::AsyncWrite + Unpin + Send + Sync + 'static,
+    {
+        LSSDriver::with_driver(Box::new(io_driver::IoDriver::new(io)))
+    }
+
+    /// Configure driver step by step
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{BaudRate, LSSDriver};
+    /// use std::time::Duration;
+    /// let mut driver = LSSDriver::builder()
+    ///     .port("COM3")
+    ///     .baud_rate(BaudRate::Baud115200)
+    ///     .timeout(Duration::from_millis(50))
+    ///     .retries(2)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> LSSDriverBuilder {
+        LSSDriverBuilder::default()
+    }
+
+    /// Creates new LSS driver with a custom implementation of the transport
+    ///
+    /// This is used for tests and can be used if you want to reimplement the driver over network.
+    /// See [FramedDriver] for how to implement a transport
+    pub fn with_driver(driver: Box<dyn FramedDriver + Send + Sync>) -> LSSDriver {
+        LSSDriver {
+            driver,
+            position_filter_alpha: 1.0,
+            filtered_positions: HashMap::new(),
+            last_known: HashMap::new(),
+            angular_ranges: HashMap::new(),
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            retry_policy: RetryPolicy::none(),
+            verify_writes: false,
+            reply_pending: false,
+            halt_pending: None,
+            auto_reconnect: None,
+            connection_events: broadcast::channel(CONNECTION_EVENT_CAPACITY).0,
+            events: broadcast::channel(DRIVER_EVENT_CAPACITY).0,
+            bus_metrics: BusMetrics::default(),
+            pacing: Pacing::default(),
+            last_frame: None,
+        }
+    }
+
+    /// Reopen serial port automatically if it disconnects
+    ///
+    /// When sending fails or the port can't be read from anymore the driver reports
+    /// [ConnectionEvent::Disconnected], tries to reopen the port according to `policy`
+    /// and reports [ConnectionEvent::Reconnected] once it succeeds.
+    /// The interrupted command is then sent again.
+    /// Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Number of reconnect attempts and backoff between them. `None` disables auto reconnect
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, RetryPolicy};
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_auto_reconnect(Some(RetryPolicy::new(10, Duration::from_millis(100))));
+    ///     let mut events = driver.connection_events();
+    ///     tokio::spawn(async move {
+    ///         while let Ok(event) = events.recv().await {
+    ///             println!("{:?}", event);
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn set_auto_reconnect(&mut self, policy: Option<RetryPolicy>) {
+        self.auto_reconnect = policy;
+    }
+
+    /// Most recent values queried from a servo
+    ///
+    /// Updated by every successful query of position, speed, voltage, temperature, current, status and color
+    /// including pipelined and telemetry queries. Doesn't touch the bus.
+    /// Useful for rendering UI without waiting for a round trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.query_position(5).await.unwrap();
+    ///     if let Some(position) = driver.last_known(5).position {
+    ///         println!("{} degrees {:?} ago", position.value, position.age());
+    ///     }
+    /// }
+    /// ```
+    pub fn last_known(&self, id: u8) -> LastKnown {
+        self.last_known.get(&id).copied().unwrap_or_default()
+    }
+
+    fn remember(&mut self, id: u8, update: impl FnOnce(&mut LastKnown)) {
+        // broadcast replies can't be attributed to a single servo
+        if id != BROADCAST_ID {
+            update(self.last_known.entry(id).or_default());
+        }
+    }
+
+    /// Check absolute position against the known session angular range of the servo
+    ///
+    /// The range is centered on the origin. Falls back to `MAX_ANGULAR_RANGE` in either direction
+    /// when the range of the servo wasn't set or queried yet.
+    fn check_position(&self, id: u8, position: f32) -> DriverResult<()> {
+        let limit = self.position_limit(id);
+        check_range("position", position, -limit..=limit)
+    }
+
+    /// Check relative motion against the span of the known session angular range of the servo
+    fn check_delta(&self, id: u8, delta: f32) -> DriverResult<()> {
+        let span = 2.0 * self.position_limit(id);
+        check_range("relative motion", delta, -span..=span)
+    }
+
+    fn position_limit(&self, id: u8) -> f32 {
+        self.angular_ranges
+            .get(&id)
+            .map_or(MAX_ANGULAR_RANGE, |range| range / 2.0)
+    }
+
+    fn remember_angular_range(&mut self, id: u8, range: f32) {
+        if id == BROADCAST_ID {
+            // servos that were set before may have been changed too
+            self.angular_ranges.clear();
+        } else {
+            self.angular_ranges.insert(id, range);
+        }
+    }
+
+    fn remember_all<T: Copy>(
+        &mut self,
+        ids: &[u8],
+        results: &[DriverResult<T>],
+        update: impl Fn(&mut LastKnown, Timestamped<T>),
+    ) {
+        for (id, result) in ids.iter().zip(results) {
+            if let Ok(value) = result {
+                self.remember(*id, |known| update(known, Timestamped::now(*value)));
+            }
+        }
+    }
+
+    /// Subscribe to connection events emitted by auto reconnect
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.connection_events.subscribe()
+    }
+
+    /// Stream of bus activity
+    ///
+    /// Reports every command written, frame received, receive timeout, reconnect and unparsable reply.
+    /// Useful for monitoring tools and bus analyzers.
+    /// Events emitted before subscribing aren't delivered.
+    /// Subscriber that falls behind by more than 64 events skips the oldest ones.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use futures::StreamExt;
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let mut events = Box::pin(driver.events());
+    ///     tokio::spawn(async move {
+    ///         while let Some(event) = events.next().await {
+    ///             println!("{:?}", event);
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn events(&self) -> impl Stream<Item = DriverEvent> {
+        futures::stream::unfold(self.events.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    // subscriber fell behind and missed the oldest events
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Frame, timeout, reconnect and parse error counters of this driver
+    ///
+    /// Unlike [events](LSSDriver::events) the counters never miss anything.
+    /// Returned value keeps counting, clone it into metrics exporters.
+    pub fn bus_metrics(&self) -> BusMetrics {
+        self.bus_metrics.clone()
+    }
+
+    fn emit(&self, event: DriverEvent) {
+        self.bus_metrics.record(&event);
+        // nobody listening to events isn't an error
+        let _ = self.events.send(event);
+    }
+
+    /// Read next frame from the transport
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        let result = self.driver.receive().await;
+        match &result {
+            Ok(response) => self.emit(DriverEvent::ResponseReceived(response.clone())),
+            Err(LssDriverError::TimeoutError) => self.emit(DriverEvent::Timeout),
+            Err(_) => (),
+        }
+        result
+    }
+
+    /// Send command reconnecting and resending once if the port disconnected
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        match self.write(command.clone()).await {
+            Err(error) if self.should_reconnect(&error) => {
+                self.recover_connection().await?;
+                self.write(command).await
+            }
+            result => result,
+        }
+    }
+
+    /// Discard stale input and write command to the transport
+    async fn write(&mut self, command: LssCommand) -> DriverResult<()> {
+        self.driver.discard_input().await?;
+        self.write_paced(command).await
+    }
+
+    /// Write pending halt and then command
+    async fn write_paced(&mut self, command: LssCommand) -> DriverResult<()> {
+        if let Some(id) = self.halt_pending {
+            self.write_frame(LssCommand::simple(id, "H")).await?;
+            self.halt_pending = None;
+        }
+        self.write_frame(command).await
+    }
+
+    /// Write command once the configured gap since the previous frame passed
+    async fn write_frame(&mut self, command: LssCommand) -> DriverResult<()> {
+        if let Some(last_frame) = self.last_frame {
+            let wait = self.pacing.interval().saturating_sub(last_frame.elapsed());
+            if !wait.is_zero() {
+                time::sleep(wait).await;
+            }
+        }
+        self.last_frame = Some(Instant::now());
+        self.driver.send(command.clone()).await?;
+        self.emit(DriverEvent::CommandSent(command));
+        Ok(())
+    }
+
+    fn should_reconnect(&self, error: &LssDriverError) -> bool {
+        self.auto_reconnect.is_some()
+            && matches!(
+                error,
+                LssDriverError::SendingError | LssDriverError::Disconnected
+            )
+    }
+
+    /// Reopen transport according to auto reconnect policy
+    async fn recover_connection(&mut self) -> DriverResult<()> {
+        let policy = self.auto_reconnect.unwrap_or_default();
+        // nobody listening to events isn't an error
+        let _ = self.connection_events.send(ConnectionEvent::Disconnected);
+        let mut attempt = 1;
+        loop {
+            match self.driver.reconnect().await {
+                Ok(()) => {
+                    self.reply_pending = false;
+                    let _ = self.connection_events.send(ConnectionEvent::Reconnected);
+                    self.emit(DriverEvent::Reconnected);
+                    return Ok(());
+                }
+                Err(error) if attempt >= policy.max_attempts => return Err(error),
+                Err(_) => {
+                    time::sleep(policy.delay_after(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Query settings back after setting them
+    ///
+    /// When enabled every setting command is followed by the matching query
+    /// and returns `VerificationFailed` if the servo reports a different value.
+    /// Session settings (e.g. `set_color`, `set_motion_profile`) are queried back as is,
+    /// EEPROM settings (`configure_*`, `set_led_blinking` and `set_*` methods saved to EEPROM) are queried with the configured scope.
+    ///
+    /// Not verified are motion commands (moves, `set_rotation_speed*`),
+    /// `set_id` and `set_baud_rate` which only take effect after a reset,
+    /// and commands sent to `BROADCAST_ID`. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `verify_writes` - Enable verification
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LedColor, LSSDriver};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_verify_writes(true);
+    ///     driver.set_color(5, LedColor::Red).await.unwrap();
+    /// }
+    /// ```
+    pub fn set_verify_writes(&mut self, verify_writes: bool) {
+        self.verify_writes = verify_writes;
+    }
+
+    fn should_verify(&self, id: u8) -> bool {
+        self.verify_writes && id != BROADCAST_ID
+    }
+
+    /// Query raw value of a setting back from `scope` if verification is enabled
+    async fn verify_setting(
+        &mut self,
+        id: u8,
+        query: &str,
+        scope: QueryScope,
+        expected: i32,
+    ) -> DriverResult<()> {
+        if self.should_verify(id) {
+            let actual = self.query_in_scope(id, query, scope).await?;
+            verify_write(query, expected, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Set retry policy applied to queries
+    ///
+    /// Useful on long daisy chains where replies occasionally get garbled.
+    /// Queries don't retry by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - Number of attempts and backoff between them
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, RetryPolicy};
+    /// use std::time::Duration;
+    /// let mut driver = LSSDriver::new("COM1").unwrap();
+    /// driver.set_retry_policy(RetryPolicy::new(3, Duration::from_millis(5)));
+    /// ```
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Set limits on how fast frames are written
+    ///
+    /// Commands wait until enough time passed since the previous frame.
+    /// Frames aren't paced by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `pacing` - Maximum frame rate and minimum gap between frames
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, Pacing};
+    /// use std::time::Duration;
+    /// let mut driver = LSSDriver::new("COM1").unwrap();
+    /// driver.set_pacing(Pacing::new(Some(500), Duration::from_millis(1)));
+    /// ```
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacing = pacing;
+    }
+
+    /// Send query and parse the reply
+    ///
+    /// Cancellation safe. If a query future is dropped while waiting,
+    /// its reply is discarded before the next query is sent.
+    /// Replies from other servos are discarded.
+    /// Replies that don't echo the query return `UnexpectedResponse`.
+    /// Repeated according to the retry policy if reply is missing or can't be parsed
+    async fn query<T, F>(&mut self, command: LssCommand, parse: F) -> DriverResult<T>
+    where
+        F: Fn(&LssResponse) -> DriverResult<T>,
+    {
+        let mut attempt = 1;
+        let mut reconnected = false;
+        loop {
+            let result = self.query_once(command.clone(), &parse).await;
+            match result {
+                Err(error) if !reconnected && self.should_reconnect(&error) => {
+                    self.recover_connection().await?;
+                    reconnected = true;
+                }
+                Err(error) if error.is_retryable() && attempt < self.retry_policy.max_attempts => {
+                    time::sleep(self.retry_policy.delay_after(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn query_once<T, F>(&mut self, command: LssCommand, parse: &F) -> DriverResult<T>
+    where
+        F: Fn(&LssResponse) -> DriverResult<T>,
+    {
+        if self.reply_pending {
+            // Previous query was dropped before its reply was read.
+            // Consume the orphaned reply so that it isn't mistaken for the reply to this one
+            let _ = self.receive().await;
+            self.reply_pending = false;
+        }
+        let id = command.id();
+        let sent = command.clone();
+        self.reply_pending = true;
+        if let Err(error) = self.write(command).await {
+            self.reply_pending = false;
+            return Err(error);
+        }
+        let result = loop {
+            let response = match self.receive().await {
+                Ok(response) => response,
+                Err(error) => break Err(error),
+            };
+            // Replies from other servos are stale. Skip them and wait for the right one
+            match response.id() {
+                Some(response_id) if id != BROADCAST_ID && response_id != id => continue,
+                _ => {
+                    break parse(&response)
+                        .map_err(|error| self.parse_failed(error, &sent, &response))
+                }
+            }
+        };
+        self.reply_pending = false;
+        result
+    }
+
+    /// Add frames to parsing errors and report them as events
+    fn parse_failed(
+        &self,
+        error: LssDriverError,
+        sent: &LssCommand,
+        received: &LssResponse,
+    ) -> LssDriverError {
+        let error = with_frames(error, sent, received);
+        if matches!(
+            error,
+            LssDriverError::ParseError { .. } | LssDriverError::UnexpectedResponse { .. }
+        ) {
+            self.emit(DriverEvent::ParseError {
+                response: received.clone(),
+                error: error.to_string(),
+            });
+        }
+        error
+    }
+
+    /// Write query to every servo first and then collect replies
+    ///
+    /// Replies are matched by ID so servos may answer in any order.
+    /// Servos that don't answer get `TimeoutError`.
+    async fn query_pipelined<T, F>(
+        &mut self,
+        ids: &[u8],
+        cmd: &str,
+        parse: F,
+    ) -> DriverResult<Vec<DriverResult<T>>>
+    where
+        F: Fn(i32) -> DriverResult<T>,
+    {
+        if self.reply_pending {
+            let _ = self.receive().await;
+            self.reply_pending = false;
+        }
+        self.driver.discard_input().await?;
+        self.reply_pending = true;
+        for &id in ids {
+            if let Err(error) = self.write_paced(LssCommand::simple(id, cmd)).await {
+                self.reply_pending = false;
+                return Err(error);
+            }
+        }
+        let mut results: Vec<Option<DriverResult<T>>> = ids.iter().map(|_| None).collect();
+        let mut outstanding = ids.len();
+        while outstanding > 0 {
+            let response = match self.receive().await {
+                Ok(response) => response,
+                Err(LssDriverError::TimeoutError) => break,
+                Err(error) => {
+                    self.reply_pending = false;
+                    return Err(error);
+                }
+            };
+            // Unknown IDs and repeated replies are stale frames
+            let slot = response.id().and_then(|response_id| {
+                ids.iter()
+                    .zip(results.iter_mut())
+                    .find(|(id, result)| **id == response_id && result.is_none())
+                    .map(|(id, result)| (*id, result))
+            });
+            if let Some((id, slot)) = slot {
+                *slot = Some(
+                    response
+                        .separate(cmd)
+                        .and_then(|(_, value)| parse(value))
+                        .map_err(|error| {
+                            self.parse_failed(error, &LssCommand::simple(id, cmd), &response)
+                        }),
+                );
+                outstanding -= 1;
+            }
+        }
+        self.reply_pending = false;
+        Ok(results
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(LssDriverError::TimeoutError)))
+            .collect())
+    }
+
+    /// Set how long queries wait for a reply
+    ///
+    /// Queries to servos that don't answer return `TimeoutError` after this.
+    /// Default is 10ms which is enough for a reply at 115200 baud.
+    /// Returns the previous timeout so it can be restored after a slow call.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum time to wait for a reply
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 9600).unwrap();
+    ///     let previous = driver.set_receive_timeout(Duration::from_millis(100));
+    ///     let voltage = driver.query_voltage(5).await;
+    ///     driver.set_receive_timeout(previous);
+    /// }
+    /// ```
+    pub fn set_receive_timeout(&mut self, timeout: Duration) -> Duration {
+        self.driver.set_receive_timeout(timeout);
+        std::mem::replace(&mut self.receive_timeout, timeout)
+    }
+
+    /// Current reply timeout
+    pub fn receive_timeout(&self) -> Duration {
+        self.receive_timeout
+    }
+
+    /// Set host side exponential filter used by [query_filtered_position](LSSDriver::query_filtered_position)
+    ///
+    /// This is independent from the servo's own filter position count.
+    /// Only affects `query_filtered_position`. Other queries always return raw values.
+    /// Resets filter state of all servos.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Weight of the newest sample between 0.0 and 1.0. 1.0 disables filtering
+    pub fn set_position_filter(&mut self, alpha: f32) {
+        self.position_filter_alpha = alpha.clamp(f32::EPSILON, 1.0);
+        self.filtered_positions.clear();
+    }
+
+    /// Reopen the underlying transport
+    ///
+    /// Useful to recover after the serial port disappeared. For example when USB cable was unplugged.
+    pub async fn reconnect(&mut self) -> DriverResult<()> {
+        self.driver.reconnect().await
+    }
+
+    /// Make sure all commands were written to the serial port
+    pub async fn flush(&mut self) -> DriverResult<()> {
+        self.driver.flush().await
+    }
+
+    /// Flush pending commands and release the serial port
+    ///
+    /// Port is released once this returns instead of whenever the driver gets dropped.
+    /// This matters on Windows where an open COM port can't be used by other tools.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.limp_all().await.unwrap();
+    ///     driver.close().await.unwrap();
+    /// }
+    /// ```
+    pub async fn close(mut self) -> DriverResult<()> {
+        self.driver.close().await
+    }
+
+    /// Split driver into independent sending and receiving halves
+    ///
+    /// For implementing custom scheduling such as several queries in flight at once.
+    /// Commands are written as soon as they are sent and every received frame is yielded by the receiver.
+    /// Retries, verification and reconnecting are not applied.
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, LssCommand};
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let (sender, mut receiver) = driver.split();
+    ///     sender.send(LssCommand::simple(1, "QV")).await.unwrap();
+    ///     sender.send(LssCommand::simple(2, "QV")).await.unwrap();
+    ///     while let Some(Ok(response)) = receiver.recv().await {
+    ///         println!("{:?}", response.separate("QV"));
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn split(self) -> (CommandSender, ResponseReceiver) {
+        split_driver::split(self.driver)
+    }
+
+    /// Move driver into a background task routing replies to the queries they answer
+    ///
+    /// Unlike [spawn](LSSDriver::spawn) queries from different tasks don't wait for each other.
+    /// Replies are matched to queries by servo ID and command.
+    /// Retries, verification and reconnecting are not applied.
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, LssCommand};
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let dispatcher = driver.dispatcher();
+    ///     let response = dispatcher.query(LssCommand::simple(5, "QV")).await.unwrap();
+    ///     println!("{:?}", response.separate("QV"));
+    /// }
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn dispatcher(self) -> Dispatcher {
+        Dispatcher::spawn(self.driver, self.receive_timeout)
+    }
+
+    /// Handle to a single servo
+    ///
+    /// Methods of the handle don't take an `id` which avoids mixing up IDs of servos.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let mut elbow = driver.servo(5);
+    ///     elbow.move_to_position(90.0).await.unwrap();
+    ///     let position = elbow.query_position().await.unwrap();
+    /// }
+    /// ```
+    pub fn servo(&mut self, id: u8) -> ServoHandle<'_> {
+        ServoHandle::new(self, id)
+    }
+
+    /// Move driver into a background task and return a cloneable handle to it
+    ///
+    /// Handles can be shared between tasks.
+    /// Requests from all handles are executed one at a time in the order they were issued.
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let handle = driver.spawn();
+    ///     let telemetry_handle = handle.clone();
+    ///     tokio::spawn(async move {
+    ///         let voltage = telemetry_handle.query_voltage(5).await;
+    ///     });
+    ///     handle.move_to_position(5, 90.0).await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn spawn(self) -> DriverHandle {
+        DriverHandle::spawn(self)
+    }
+
+    /// Soft reset
+    /// This command does a "soft reset" and reverts all commands to those stored in EEPROM
+    ///
+    /// Servo doesn't respond for a moment after reset.
+    /// Use [reset_and_wait](LSSDriver::reset_and_wait) to wait until it's responsive again.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HReset)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to reset
+    pub async fn reset(&mut self, id: u8) -> DriverResult<()> {
+        self.send(LssCommand::simple(id, "RESET")).await?;
+        // session angular range is reloaded from EEPROM
+        if id == BROADCAST_ID {
+            self.angular_ranges.clear();
+        } else {
+            self.angular_ranges.remove(&id);
+        }
+        Ok(())
+    }
+
+    /// Soft reset and wait until servo responds again
+    ///
+    /// Polls status of the servo until it answers or `timeout` elapses
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HReset)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to reset. Can't be `BROADCAST_ID`
+    /// * `timeout` - Maximum time to wait for the servo to come back
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.reset_and_wait(5, Duration::from_secs(2)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn reset_and_wait(&mut self, id: u8, timeout: Duration) -> DriverResult<()> {
+        self.reset(id).await?;
+        self.wait_until_responsive(id, timeout).await
+    }
+
+    /// Restore factory defaults and wait until servo responds again
+    ///
+    /// Performs the DEFAULT and CONFIRM handshake.
+    /// This reverts all settings stored in EEPROM including ID and baud rate.
+    /// Servo resets afterwards and comes back with factory ID 0, which is what is polled.
+    /// Talk to it with ID 0 afterwards. Port has to run at the factory baud rate of 115200 for it to respond.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HDefault26confirm)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to restore
+    /// * `timeout` - Maximum time to wait for the servo to come back
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.restore_factory_defaults(0, Duration::from_secs(2)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn restore_factory_defaults(
+        &mut self,
+        id: u8,
+        timeout: Duration,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::simple(id, "DEFAULT")).await?;
+        self.send(LssCommand::simple(id, "CONFIRM")).await?;
+        self.wait_until_responsive(FACTORY_DEFAULT_ID, timeout)
+            .await
+    }
+
+    /// Query raw value of a setting from either the session or EEPROM
+    ///
+    /// Session queries are sent bare, configured ones with parameter `1`
+    async fn query_in_scope(&mut self, id: u8, cmd: &str, scope: QueryScope) -> DriverResult<i32> {
+        let command = match scope {
+            QueryScope::Session => LssCommand::simple(id, cmd),
+            QueryScope::Configured => LssCommand::with_param(id, cmd, 1),
+        };
+        self.query(command, |response| {
+            let (_, value) = response.separate(cmd)?;
+            Ok(value)
+        })
+        .await
+    }
+
+    async fn wait_until_responsive(&mut self, id: u8, timeout: Duration) -> DriverResult<()> {
+        time::timeout(timeout, async {
+            while self.query_status(id).await.is_err() {
+                time::sleep(RESET_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| LssDriverError::TimeoutError)
+    }
+
+    /// Query value of ID
+    /// Especially useful with BROADCAST_ID
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HIdentificationNumber28ID29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let id = driver.query_id(lss_driver::BROADCAST_ID).await.unwrap();
+    /// }
+    /// ```
+    pub async fn query_id(&mut self, id: u8) -> DriverResult<u8> {
+        self.query(LssCommand::simple(id, "QID"), |response| {
+            response.get_val_as("QID")
+        })
+        .await
+    }
+
+    /// Probe every ID on the bus for servos sharing an ID
+    ///
+    /// Takes a while since every ID has to wait for the reply timeout.
+    /// See [detect_id_conflicts_in](LSSDriver::detect_id_conflicts_in).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let report = driver.detect_id_conflicts().await.unwrap();
+    ///     for id in report.conflicting {
+    ///         println!("Multiple servos use ID {}", id);
+    ///     }
+    /// }
+    /// ```
+    pub async fn detect_id_conflicts(&mut self) -> DriverResult<IdConflictReport> {
+        let ids: Vec<u8> = (0..BROADCAST_ID).collect();
+        self.detect_id_conflicts_in(&ids).await
+    }
+
+    /// Probe given IDs for servos sharing an ID
+    ///
+    /// Sends a status query to each ID and collects every reply until timeout.
+    /// Servos sharing an ID answer at the same time which results in several or garbled replies.
+    /// IDs that only sent noise the transport couldn't frame are reported as conflicting too.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs to probe
+    pub async fn detect_id_conflicts_in(&mut self, ids: &[u8]) -> DriverResult<IdConflictReport> {
+        let mut report = IdConflictReport::default();
+        for &id in ids {
+            // forget noise left over from earlier commands
+            self.driver.take_dropped_bytes().await;
+            self.send(LssCommand::simple(id, "Q")).await?;
+            let mut replies = 0;
+            let mut garbled = false;
+            loop {
+                match self.receive().await {
+                    Ok(response) => {
+                        replies += 1;
+                        if !matches!(response.separate("Q"), Ok((reply_id, _)) if reply_id == id) {
+                            garbled = true;
+                        }
+                    }
+                    Err(LssDriverError::TimeoutError) => break,
+                    Err(error) => return Err(error),
+                }
+            }
+            // colliding replies often end up as noise the codec drops instead of a frame
+            if self.driver.take_dropped_bytes().await > 0 {
+                garbled = true;
+            }
+            if garbled || replies > 1 {
+                report.conflicting.push(id);
+            } else if replies == 1 {
+                report.responding.push(id);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Send command defined outside of this crate
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `command` - Command to send
+    pub async fn send_command<C: LssCommandSpec>(
+        &mut self,
+        id: u8,
+        command: &C,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::encode(
+            id,
+            command.command(),
+            command.value(),
+            &command.modifiers(),
+        ))
+        .await
+    }
+
+    /// Run query defined outside of this crate
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `query` - Query to run
+    pub async fn run_query<Q: LssQuery>(&mut self, id: u8, query: &Q) -> DriverResult<Q::Output> {
+        self.query(LssCommand::simple(id, query.command()), |response| {
+            query.parse(response)
+        })
+        .await
+    }
+
+    /// Send command the driver doesn't wrap yet
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `cmd` - Command letters. e.g. `G`
+    /// * `value` - Value following the command letters
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.send_raw(5, "G", Some(-1)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn send_raw(&mut self, id: u8, cmd: &str, value: Option<i32>) -> DriverResult<()> {
+        self.send(LssCommand::encode(id, cmd, value, &[])).await
+    }
+
+    /// Run query the driver doesn't wrap yet
+    ///
+    /// Returns everything following the command letters in the reply as text.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `cmd` - Query command letters. e.g. `QG`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let gyre = driver.query_raw(5, "QG").await.unwrap();
+    /// }
+    /// ```
+    pub async fn query_raw(&mut self, id: u8, cmd: &str) -> DriverResult<String> {
+        self.query(LssCommand::simple(id, cmd), |response| {
+            let (_, value) = response.separate_string(cmd)?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Send query to every servo and collect all replies
+    ///
+    /// Query is sent to `BROADCAST_ID` and replies are collected until no frame arrives within the receive timeout.
+    /// Replies are sorted by ID. Garbled replies caused by servos answering at the same time are skipped,
+    /// so missing servos don't necessarily mean they aren't connected.
+    /// Skipped replies are reported as [DriverEvent::ParseError] together with the raw frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmd` - Query command. e.g. `QD`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     for (id, position) in driver.query_broadcast("QD").await.unwrap() {
+    ///         println!("Servo {} at {}", id, position);
+    ///     }
+    /// }
+    /// ```
+    pub async fn query_broadcast(&mut self, cmd: &str) -> DriverResult<Vec<(u8, i32)>> {
+        if self.reply_pending {
+            let _ = self.receive().await;
+            self.reply_pending = false;
+        }
+        let command = LssCommand::simple(BROADCAST_ID, cmd);
+        self.send(command.clone()).await?;
+        let mut replies = vec![];
+        loop {
+            match self.receive().await {
+                Ok(response) => match response.separate(cmd) {
+                    Ok(reply) => replies.push(reply),
+                    // reported as event since other replies are still useful
+                    Err(error) => {
+                        self.parse_failed(error, &command, &response);
+                    }
+                },
+                Err(LssDriverError::TimeoutError) => break,
+                Err(error) => return Err(error),
+            }
+        }
+        replies.sort_by_key(|(id, _)| *id);
+        Ok(replies)
+    }
+
+    /// Set value of ID
+    /// Saved to EEPROM
+    /// Only takes effect after restart
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HIdentificationNumber28ID29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `new_id` - ID You want that servo to have
+    pub async fn set_id(&mut self, id: u8, new_id: u8) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "CID", new_id as i32))
+            .await?;
+        Ok(())
+    }
+
+    /// Set baud rate of the servo
+    /// Saved to EEPROM
+    /// Only takes effect after restart
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HBaudRate)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `baud_rate` - Baud rate supported by the servo
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{BaudRate, LSSDriver};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_baud_rate(5, BaudRate::Baud500000).await.unwrap();
+    /// }
+    /// ```
+    pub async fn set_baud_rate(&mut self, id: u8, baud_rate: BaudRate) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "CB", baud_rate as i32))
+            .await?;
+        Ok(())
+    }
+
+    /// Change baud rate of the servo and reconfigure serial port to match
+    ///
+    /// Configures new baud rate, resets the servo so that it takes effect
+    /// and switches the serial port to the new baud rate.
+    /// All servos on the bus have to use the same baud rate so this is usually used with `BROADCAST_ID`.
+    /// Servos take a moment to restart so wait before sending further commands.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HBaudRate)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `baud_rate` - Baud rate supported by the servo
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{BaudRate, LSSDriver, BROADCAST_ID};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.change_baud_rate(BROADCAST_ID, BaudRate::Baud500000).await.unwrap();
+    /// }
+    /// ```
+    pub async fn change_baud_rate(&mut self, id: u8, baud_rate: BaudRate) -> DriverResult<()> {
+        self.set_baud_rate(id, baud_rate).await?;
+        self.reset(id).await?;
+        self.driver.set_baud_rate(baud_rate.into()).await
+    }
+
+    /// Query baud rate of the servo
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HBaudRate)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_baud_rate(&mut self, id: u8) -> DriverResult<BaudRate> {
+        self.query(LssCommand::simple(id, "QB"), |response| {
+            let (_, value) = response.separate_as::<u32>("QB")?;
+            BaudRate::try_from(value)
+        })
+        .await
+    }
+
+    /// set color for driver with id
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `color` - Color to set
+    pub async fn set_color(&mut self, id: u8, color: LedColor) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "LED", color as i32))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_color(id).await?;
+            verify_write("QLED", color, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure color of servo LED
+    /// Saved to EEPROM
+    ///
+    /// Color the LED has after power up
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `color` - Color to set
+    pub async fn configure_color(&mut self, id: u8, color: LedColor) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "CLED", color as i32))
+            .await?;
+        self.verify_setting(id, "QLED", QueryScope::Configured, color as i32)
+            .await
+    }
+
+    /// Query color of servo LED
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_color(&mut self, id: u8) -> DriverResult<LedColor> {
+        let color = self
+            .query(LssCommand::simple(id, "QLED"), |response| {
+                let (_, value) = response.separate("QLED")?;
+                LedColor::from_i32(value)
+            })
+            .await?;
+        self.remember(id, |known| known.color = Some(Timestamped::now(color)));
+        Ok(color)
+    }
+
+    /// Blink LED of servo so that it can be located on a robot
+    ///
+    /// Alternates between white and off for the given duration
+    /// and then restores the color servo had before, also when blinking fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to identify
+    /// * `duration` - How long to blink for
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.identify(5, Duration::from_secs(3)).await;
+    /// }
+    /// ```
+    pub async fn identify(&mut self, id: u8, duration: Duration) -> DriverResult<()> {
+        let previous_color = self.query_color(id).await?;
+        let blinks = duration
+            .as_millis()
+            .div_ceil(IDENTIFY_BLINK_INTERVAL.as_millis())
+            .max(1);
+        let blinked = async {
+            for blink in 0..blinks {
+                let color = if blink % 2 == 0 {
+                    LedColor::White
+                } else {
+                    LedColor::Off
+                };
+                self.set_color(id, color).await?;
+                time::sleep(IDENTIFY_BLINK_INTERVAL).await;
+            }
+            Ok(())
+        }
+        .await;
+        // restore even if blinking failed so the LED isn't left white or off
+        let restored = self.set_color(id, previous_color).await;
+        blinked.and(restored)
+    }
+
+    /// Move to absolute position in degrees
+    ///
+    /// Supports virtual positions up to a full turn in either direction.
+    /// Fails with `ValueOutOfRange` if the position is outside of the session angular range
+    /// last set with [set_session_angular_range](LSSDriver::set_session_angular_range)
+    /// or queried with [query_angular_range](LSSDriver::query_angular_range).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position(5, 180.0).await;
+    ///     driver.move_to_position(5, -270.0).await;
+    /// }
+    /// ```
+    pub async fn move_to_position(&mut self, id: u8, position: f32) -> DriverResult<()> {
+        self.check_position(id, position)?;
+        let angle = units::degrees_to_native(position);
+        self.send(LssCommand::with_param(id, "D", angle)).await?;
+        Ok(())
+    }
+
+    /// Move to absolute position in degrees if servo is in expected motion profile mode
+    ///
+    /// Queries motion profile of the servo first and doesn't move if it doesn't match.
+    /// With motion profile disabled servos move towards target location at full speed
+    /// which can be surprising if a smooth motion was expected.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `expected_mode` - Motion profile mode servo has to be in
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{ExpectedMode, LSSDriver};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_in_mode(5, 180.0, ExpectedMode::Profiled).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_in_mode(
+        &mut self,
+        id: u8,
+        position: f32,
+        expected_mode: ExpectedMode,
+    ) -> DriverResult<()> {
+        let actual_mode = ExpectedMode::from_motion_profile(self.query_motion_profile(id).await?);
+        if actual_mode != expected_mode {
+            return Err(LssDriverError::MotionProfileMismatch(expected_mode));
+        }
+        self.move_to_position(id, position).await
+    }
+
+    /// Move to absolute position in degrees with modifier
+    ///
+    /// Positions are validated like in [move_to_position](LSSDriver::move_to_position)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `modifier` - Modifier applied to this motion. Look at the type for more info.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use lss_driver::CommandModifier;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_with_modifier(5, 180.0, CommandModifier::Timed(2500)).await;
+    ///     driver.move_to_position_with_modifier(5, -270.0, CommandModifier::Timed(2500)).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_with_modifier(
+        &mut self,
+        id: u8,
+        position: f32,
+        modifier: CommandModifier,
+    ) -> DriverResult<()> {
+        self.check_position(id, position)?;
+        let angle = units::degrees_to_native(position);
+        self.send(LssCommand::with_param_modifier(id, "D", angle, modifier))
+            .await?;
+        Ok(())
+    }
+
+    /// Move to absolute position in degrees at given speed
+    ///
+    /// Uses the SD modifier. Only has effect when motion profile is enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `speed` - Speed in °/s (0 to 180)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_with_speed(5, 180.0, 45.0).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_with_speed(
+        &mut self,
+        id: u8,
+        position: f32,
+        speed: f32,
+    ) -> DriverResult<()> {
+        check_range("speed", speed, 0.0..=MAX_SPEED)?;
+        let speed = units::deg_per_s_to_native(speed) as u32;
+        self.move_to_position_with_modifier(id, position, CommandModifier::SpeedDegrees(speed))
+            .await
+    }
+
+    /// Move to absolute position in degrees within given duration
+    ///
+    /// Uses the T modifier. Only has effect when motion profile is enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `duration` - Time the motion should take
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_in(5, 180.0, Duration::from_millis(800)).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_in(
+        &mut self,
+        id: u8,
+        position: f32,
+        duration: Duration,
+    ) -> DriverResult<()> {
+        self.move_to_position_with_modifier(id, position, CommandModifier::TimedDuration(duration))
+            .await
+    }
+
+    /// Move to absolute position in degrees holding with limited current
+    ///
+    /// Uses the CH modifier. If the servo meets resistance it holds position with current capped at `current`.
+    /// Useful for grippers.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HCurrentHalt26Hold28CH29modifier)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `current` - Current limit in mA
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_with_current_hold(5, 45.0, 400).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_with_current_hold(
+        &mut self,
+        id: u8,
+        position: f32,
+        current: u32,
+    ) -> DriverResult<()> {
+        self.move_to_position_with_modifier(id, position, CommandModifier::CurrentHold(current))
+            .await
+    }
+
+    /// Move to absolute position in degrees going limp when current is exceeded
+    ///
+    /// Uses the CL modifier. If current exceeds `current` during the motion the servo goes limp.
+    /// Useful for safe contact detection.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HCurrentLimp28CL29modifier)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `current` - Current limit in mA
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_with_current_limp(5, 45.0, 400).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_with_current_limp(
+        &mut self,
+        id: u8,
+        position: f32,
+        current: u32,
+    ) -> DriverResult<()> {
+        self.move_to_position_with_modifier(id, position, CommandModifier::CurrentLimp(current))
+            .await
+    }
+
+    /// Move to absolute position in degrees with multiple modifiers
+    ///
+    /// Positions are validated like in [move_to_position](LSSDriver::move_to_position)
+    /// Be careful about which modifiers are supported together
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    /// * `modifiers` - Array of modifiers applied to this motion. Look at the type for more info.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use lss_driver::CommandModifier;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position_with_modifiers(5, 180.0, &[CommandModifier::Timed(2500), CommandModifier::CurrentHold(400)]).await;
+    /// }
+    /// ```
+    pub async fn move_to_position_with_modifiers(
+        &mut self,
+        id: u8,
+        position: f32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        self.check_position(id, position)?;
+        let angle = units::degrees_to_native(position);
+        self.send(LssCommand::with_param_modifiers(id, "D", angle, modifiers))
+            .await?;
+        Ok(())
+    }
+
+    /// Execute move command built with [MoveCommand]
+    ///
+    /// Allows combining any modifiers supported by the protocol in one command.
+    /// Positions and speeds are validated like in [move_to_position](LSSDriver::move_to_position)
+    /// and [move_relative](LSSDriver::move_relative) before anything is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `command` - Move command to send
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, MoveCommand};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.execute(5, MoveCommand::to(90.0).speed_dps(45.0)).await;
+    /// }
+    /// ```
+    pub async fn execute(&mut self, id: u8, command: MoveCommand) -> DriverResult<()> {
+        if command.is_relative() {
+            self.check_delta(id, command.position())?;
+        } else {
+            self.check_position(id, command.position())?;
+        }
+        for speed in command.speeds() {
+            check_range("speed", *speed, 0.0..=MAX_SPEED)?;
+        }
+        self.send(LssCommand::with_param_modifiers(
+            id,
+            command.command(),
+            units::degrees_to_native(command.position()),
+            command.modifiers(),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Configure first position in degrees
+    /// Saved to EEPROM
+    ///
+    /// Position the servo moves to on power up.
+    /// `None` disables first position and servo stays limp after power up.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HConfigureFirstPosition28CFD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.configure_first_position(5, Some(0.0)).await;
+    ///     driver.configure_first_position(6, None).await;
+    /// }
+    /// ```
+    pub async fn configure_first_position(
+        &mut self,
+        id: u8,
+        position: Option<f32>,
+    ) -> DriverResult<()> {
+        let command = match position {
+            Some(position) => LssCommand::with_param(id, "CFD", units::degrees_to_native(position)),
+            None => LssCommand::simple(id, "CFD"),
+        };
+        self.send(command).await?;
+        if self.should_verify(id) {
+            let actual = self.query_first_position(id).await?;
+            verify_write(
+                "QFD",
+                position.map(units::degrees_to_native),
+                actual.map(units::degrees_to_native),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Query first position in degrees
+    ///
+    /// Returns `None` if first position is disabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HConfigureFirstPosition28CFD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_first_position(&mut self, id: u8) -> DriverResult<Option<f32>> {
+        self.query(LssCommand::simple(id, "QFD"), |response| {
+            let (_, value) = response.separate_string("QFD")?;
+            if value.is_empty() {
+                return Ok(None);
+            }
+            let value: i32 = value.parse().map_err(|_| {
+                LssDriverError::PacketParsingError(String::from("Failed parsing value"))
+            })?;
+            Ok(Some(units::native_to_degrees(value)))
+        })
+        .await
+    }
+
+    /// Move relative to current position in degrees
+    ///
+    /// Fails with `ValueOutOfRange` if `delta` is wider than the known session angular range,
+    /// or a full turn in either direction if it isn't known.
+    ///
+    /// [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMoveinDegrees28MD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `delta` - Relative motion in degrees
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_relative(5, -12.5).await;
+    /// }
+    /// ```
+    pub async fn move_relative(&mut self, id: u8, delta: f32) -> DriverResult<()> {
+        self.check_delta(id, delta)?;
+        self.send(LssCommand::with_param(
+            id,
+            "MD",
+            units::degrees_to_native(delta),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Move relative to current position in degrees with modifiers
+    ///
+    /// `delta` is validated like in [move_relative](LSSDriver::move_relative)
+    /// Be careful about which modifiers are supported together
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `delta` - Relative motion in degrees
+    /// * `modifiers` - Array of modifiers applied to this motion. Look at the type for more info.
+    pub async fn move_relative_with_modifiers(
+        &mut self,
+        id: u8,
+        delta: f32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        self.check_delta(id, delta)?;
+        self.send(LssCommand::with_param_modifiers(
+            id,
+            "MD",
+            units::degrees_to_native(delta),
+            modifiers,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Move to absolute position in degrees
+    ///
+    /// Same as `move_to_position`
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `position` - Absolute position in degrees
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_target_position(5, 180.0).await;
+    ///     driver.set_target_position(5, -270.0).await;
+    /// }
+    /// ```
+    pub async fn set_target_position(&mut self, id: u8, position: f32) -> DriverResult<()> {
+        self.move_to_position(id, position).await
+    }
+
+    /// Query absolute current position in degrees
+    ///
+    /// Supports virtual positions that are more than 360 degrees
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_position(&mut self, id: u8) -> DriverResult<f32> {
+        let position = self
+            .query(LssCommand::simple(id, "QD"), |response| {
+                let (_, value) = response.separate("QD")?;
+                Ok(units::native_to_degrees(value))
+            })
+            .await?;
+        self.remember(id, |known| {
+            known.position = Some(Timestamped::now(position))
+        });
+        Ok(position)
+    }
+
+    /// Query absolute current position in degrees smoothed by host side filter
+    ///
+    /// Filter state is kept per servo. Configure it using [set_position_filter](LSSDriver::set_position_filter).
+    /// First query of each servo returns the raw position.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_filtered_position(&mut self, id: u8) -> DriverResult<f32> {
+        let position = self.query_position(id).await?;
+        let alpha = self.position_filter_alpha;
+        let filtered = self
+            .filtered_positions
+            .entry(id)
+            .and_modify(|filtered| *filtered = alpha * position + (1.0 - alpha) * *filtered)
+            .or_insert(position);
+        Ok(*filtered)
+    }
+
+    /// Query absolute target position in degrees
+    ///
+    /// Supports virtual positions that are more than 360 degrees
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_target_position(&mut self, id: u8) -> DriverResult<f32> {
+        self.query(LssCommand::simple(id, "QDT"), |response| {
+            let (_, value) = response.separate("QDT")?;
+            Ok(units::native_to_degrees(value))
+        })
+        .await
+    }
+
+    /// Query position error in degrees
+    ///
+    /// Difference between target position (QDT) and current position (QD).
+    /// Useful for monitoring how well a servo tracks its target.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_position_error(&mut self, id: u8) -> DriverResult<f32> {
+        let target = self.query_target_position(id).await?;
+        let position = self.query_position(id).await?;
+        Ok(target - position)
+    }
+
+    /// Set continuous rotation speed in °/s
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `speed` - Speed in °/s (-180 to 180)
+    pub async fn set_rotation_speed(&mut self, id: u8, speed: f32) -> DriverResult<()> {
+        check_range("rotation speed", speed, -MAX_SPEED..=MAX_SPEED)?;
+        self.send(LssCommand::with_param(
+            id,
+            "WD",
+            units::wheel_deg_per_s_to_native(speed),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Query absolute rotation speed in °/s
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_rotation_speed(&mut self, id: u8) -> DriverResult<f32> {
+        let speed = self
+            .query(LssCommand::simple(id, "QWD"), |response| {
+                let (_, value) = response.separate("QWD")?;
+                Ok(units::native_to_wheel_deg_per_s(value))
+            })
+            .await?;
+        self.remember(id, |known| known.speed = Some(Timestamped::now(speed)));
+        Ok(speed)
+    }
+
+    /// Set continuous rotation speed in °/s
+    ///
+    /// Same as `set_rotation_speed`
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `speed` - Speed in °/s (-180 to 180)
+    pub async fn set_rotation_speed_dps(&mut self, id: u8, speed: f32) -> DriverResult<()> {
+        self.set_rotation_speed(id, speed).await
+    }
+
+    /// Set continuous rotation speed in RPM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `speed` - Speed in RPM (-30 to 30)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_rotation_speed_rpm(5, 10.0).await;
+    ///     driver.set_rotation_speed_rpm(6, -10.0).await;
+    /// }
+    /// ```
+    pub async fn set_rotation_speed_rpm(&mut self, id: u8, speed: f32) -> DriverResult<()> {
+        check_range("rotation speed", speed, -MAX_SPEED_RPM..=MAX_SPEED_RPM)?;
+        self.send(LssCommand::with_param(
+            id,
+            "WR",
+            units::wheel_rpm_to_native(speed),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Query absolute rotation speed in RPM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_rotation_speed_rpm(&mut self, id: u8) -> DriverResult<f32> {
+        self.query(LssCommand::simple(id, "QWR"), |response| {
+            let (_, value) = response.separate("QWR")?;
+            Ok(units::native_to_wheel_rpm(value))
+        })
+        .await
+    }
+
+    /// Query current speed in °/s
+    ///
+    /// Same as `query_rotation_speed`
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_speed_dps(&mut self, id: u8) -> DriverResult<f32> {
+        self.query_rotation_speed(id).await
+    }
+
+    /// Query current speed in RPM
+    ///
+    /// Same as `query_rotation_speed_rpm`
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_speed_rpm(&mut self, id: u8) -> DriverResult<f32> {
+        self.query_rotation_speed_rpm(id).await
+    }
+
+    /// Query status of a motor
+    ///
+    /// View more on [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HQueryStatus28Q29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_status(&mut self, id: u8) -> DriverResult<MotorStatus> {
+        let status = self
+            .query(LssCommand::simple(id, "Q"), |response| {
+                let (_, value) = response.separate("Q")?;
+                MotorStatus::from_i32(value)
+            })
+            .await?;
+        self.remember(id, |known| known.status = Some(Timestamped::now(status)));
+        Ok(status)
+    }
+
+    /// Query status of a motor as a set of flags
+    ///
+    /// Same reply as [query_status](LSSDriver::query_status) with helpers grouping related states.
+    /// View more on [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HQueryStatus28Q29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_status_flags(&mut self, id: u8) -> DriverResult<StatusFlags> {
+        self.query(LssCommand::simple(id, "Q"), |response| {
+            let (_, value) = response.separate("Q")?;
+            StatusFlags::from_i32(value)
+        })
+        .await
+    }
+
+    /// Check whether servo is accelerating, traveling or decelerating
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn is_moving(&mut self, id: u8) -> DriverResult<bool> {
+        Ok(self.query_status_flags(id).await?.is_moving())
+    }
+
+    /// Wait until servo reports holding or limp
+    ///
+    /// Polls status of the servo. Useful for sequencing moves without hard coded sleeps.
+    /// Returns `TimeoutError` if servo doesn't stop in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to wait for
+    /// * `timeout` - Maximum time to wait
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_position(5, 90.0).await.unwrap();
+    ///     driver.wait_until_stopped(5, Duration::from_secs(2)).await.unwrap();
+    /// }
+    /// ```
+    pub async fn wait_until_stopped(&mut self, id: u8, timeout: Duration) -> DriverResult<()> {
+        time::timeout(timeout, async {
+            loop {
+                let status = self.query_status_flags(id).await?;
+                if status.is_holding() || status.is_limp() {
+                    return Ok(());
+                }
+                time::sleep(MOTION_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| LssDriverError::TimeoutError)?
+    }
+
+    /// Query safety status of a motor
+    ///
+    /// View more on [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HQueryStatus28Q29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_safety_status(&mut self, id: u8) -> DriverResult<SafeModeStatus> {
+        self.query(LssCommand::simple(id, "Q1"), |response| {
+            let (_, value) = response.separate("Q")?;
+            SafeModeStatus::from_i32(value)
+        })
+        .await
+    }
+
+    /// Set rotation direction (gyre) for this session
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HGyreDirection28G29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `direction` - Rotation direction
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{Direction, LSSDriver};
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_gyre_direction(5, Direction::CounterClockwise).await;
+    /// }
+    /// ```
+    pub async fn set_gyre_direction(&mut self, id: u8, direction: Direction) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "G", direction as i32))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_gyre_direction(id).await?;
+            verify_write("QG", direction, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure rotation direction (gyre)
+    /// Saved to EEPROM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HGyreDirection28G29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `direction` - Rotation direction
+    pub async fn configure_gyre_direction(
+        &mut self,
+        id: u8,
+        direction: Direction,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "CG", direction as i32))
+            .await?;
+        self.verify_setting(id, "QG", QueryScope::Configured, direction as i32)
+            .await
+    }
+
+    /// Query rotation direction (gyre)
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HGyreDirection28G29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_gyre_direction(&mut self, id: u8) -> DriverResult<Direction> {
+        self.query(LssCommand::simple(id, "QG"), |response| {
+            let (_, value) = response.separate("QG")?;
+            Direction::from_i32(value)
+        })
+        .await
+    }
+
+    /// Set motion profile enabled or disabled.
+    /// If the motion profile is enabled, angular acceleration (AA) and angular deceleration(AD) will have an effect on the motion. Also, SD/S and T modifiers can be used.
+    ///
+    /// With motion profile enabled servos will follow a motion curve
+    /// With motion profile disabled servos move towards target location at full speed
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `motion_profile` - set motion profile on/off
+    pub async fn set_motion_profile(&mut self, id: u8, motion_profile: bool) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "EM", motion_profile as i32))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_motion_profile(id).await?;
+            verify_write("QEM", motion_profile, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure motion profile enabled or disabled.
+    /// Saved to EEPROM
+    ///
+    /// With motion profile enabled servos will follow a motion curve
+    /// With motion profile disabled servos move towards target location at full speed
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `motion_profile` - set motion profile on/off
+    pub async fn configure_motion_profile(
+        &mut self,
+        id: u8,
+        motion_profile: bool,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::with_param(id, "CEM", motion_profile as i32))
+            .await?;
+        self.verify_setting(id, "QEM", QueryScope::Configured, motion_profile as i32)
+            .await
+    }
+
+    /// query motion profile enabled or disabled.
+    /// If the motion profile is enabled, angular acceleration (AA) and angular deceleration(AD) will have an effect on the motion. Also, SD/S and T modifiers can be used.
+    ///
+    /// With motion profile enabled servos will follow a motion curve
+    /// With motion profile disabled servos move towards target location at full speed
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_motion_profile(&mut self, id: u8) -> DriverResult<bool> {
+        self.query(LssCommand::simple(id, "QEM"), |response| {
+            let (_, value) = response.separate("QEM")?;
+            Ok(value != 0)
+        })
+        .await
+    }
+
+    /// Set filter position count
+    ///
+    /// Change the Filter Position Count value for this session.
+    /// Affects motion only when motion profile is disabled (EM0)
+    ///
+    /// more info at the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HFilterPositionCount28FPC29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `filter_position_count` - default if 5
+    pub async fn set_filter_position_count(
+        &mut self,
+        id: u8,
+        filter_position_count: u8,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::with_param(
+            id,
+            "FPC",
+            filter_position_count as i32,
+        ))
+        .await?;
+        if self.should_verify(id) {
+            let actual = self.query_filter_position_count(id).await?;
+            verify_write("QFPC", filter_position_count, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure filter position count
+    /// Saved to EEPROM
+    ///
+    /// Affects motion only when motion profile is disabled (EM0)
+    ///
+    /// more info at the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HFilterPositionCount28FPC29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `filter_position_count` - default if 5
+    pub async fn configure_filter_position_count(
+        &mut self,
+        id: u8,
+        filter_position_count: u8,
+    ) -> DriverResult<()> {
+        self.send(LssCommand::with_param(
+            id,
+            "CFPC",
+            filter_position_count as i32,
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QFPC",
+            QueryScope::Configured,
+            filter_position_count as i32,
+        )
+        .await
+    }
+
+    /// Query filter position count
+    ///
+    /// Query the Filter Position Count value.
+    /// Affects motion only when motion profile is disabled (EM0)
+    ///
+    /// more info at the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HFilterPositionCount28FPC29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_filter_position_count(&mut self, id: u8) -> DriverResult<u8> {
+        self.query(LssCommand::simple(id, "QFPC"), |response| {
+            let (_, value) = response.separate_as("QFPC")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set angular stiffness
+    ///
+    /// Read more about [Angular stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularStiffness28AS29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_stiffness` - value for angular stiffness (-10 to 10) (recommended -4 to 4)
+    pub async fn set_angular_stiffness(
+        &mut self,
+        id: u8,
+        angular_stiffness: i32,
+    ) -> DriverResult<()> {
+        check_range("angular stiffness", angular_stiffness, -10..=10)?;
+        self.send(LssCommand::with_param(id, "AS", angular_stiffness))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_angular_stiffness(id).await?;
+            verify_write("QAS", angular_stiffness, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure angular stiffness
+    /// Saved to EEPROM
+    ///
+    /// Read more about [Angular stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularStiffness28AS29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_stiffness` - value for angular stiffness (-10 to 10) (recommended -4 to 4)
+    pub async fn configure_angular_stiffness(
+        &mut self,
+        id: u8,
+        angular_stiffness: i32,
+    ) -> DriverResult<()> {
+        check_range("angular stiffness", angular_stiffness, -10..=10)?;
+        self.send(LssCommand::with_param(id, "CAS", angular_stiffness))
+            .await?;
+        self.verify_setting(id, "QAS", QueryScope::Configured, angular_stiffness)
+            .await
+    }
+
+    /// Query angular stiffness
+    ///
+    /// Read more about [Angular stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularStiffness28AS29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_angular_stiffness(&mut self, id: u8) -> DriverResult<i32> {
+        self.query(LssCommand::simple(id, "QAS"), |response| {
+            let (_, value) = response.separate("QAS")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Query angular stiffness from either the session or EEPROM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `scope` - Whether to return session or configured value
+    pub async fn query_angular_stiffness_in_scope(
+        &mut self,
+        id: u8,
+        scope: QueryScope,
+    ) -> DriverResult<i32> {
+        let value = self.query_in_scope(id, "QAS", scope).await?;
+        Ok(value)
+    }
+
+    /// Set angular holding stiffness
+    ///
+    /// Read more about [Angular holding stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularHoldingStiffness28AH29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_holding` - value for angular holding stiffness (-10 to 10)
+    pub async fn set_angular_holding_stiffness(
+        &mut self,
+        id: u8,
+        angular_holding: i32,
+    ) -> DriverResult<()> {
+        check_range("angular holding stiffness", angular_holding, -10..=10)?;
+        self.send(LssCommand::with_param(id, "AH", angular_holding))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_angular_holding_stiffness(id).await?;
+            verify_write("QAH", angular_holding, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure angular holding stiffness
+    /// Saved to EEPROM
+    ///
+    /// Read more about [Angular holding stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularHoldingStiffness28AH29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_holding` - value for angular holding stiffness (-10 to 10)
+    pub async fn configure_angular_holding_stiffness(
+        &mut self,
+        id: u8,
+        angular_holding: i32,
+    ) -> DriverResult<()> {
+        check_range("angular holding stiffness", angular_holding, -10..=10)?;
+        self.send(LssCommand::with_param(id, "CAH", angular_holding))
+            .await?;
+        self.verify_setting(id, "QAH", QueryScope::Configured, angular_holding)
+            .await
+    }
+
+    /// Query angular holding stiffness
+    ///
+    /// Read more about [Angular holding stiffness](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularHoldingStiffness28AH29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    pub async fn query_angular_holding_stiffness(&mut self, id: u8) -> DriverResult<i32> {
+        self.query(LssCommand::simple(id, "QAH"), |response| {
+            let (_, value) = response.separate("QAH")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Query angular holding stiffness from either the session or EEPROM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `scope` - Whether to return session or configured value
+    pub async fn query_angular_holding_stiffness_in_scope(
+        &mut self,
+        id: u8,
+        scope: QueryScope,
+    ) -> DriverResult<i32> {
+        let value = self.query_in_scope(id, "QAH", scope).await?;
+        Ok(value)
+    }
+
+    /// Set angular acceleration in degrees per second squared (°/s2)
+    ///
+    /// Accepts values between 1 and 100. Increments of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularAcceleration28AA29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_acceleration` - value for angular acceleration (1 to 100, Increments 10)
+    pub async fn set_angular_acceleration(
+        &mut self,
+        id: u8,
+        angular_acceleration: i32,
+    ) -> DriverResult<()> {
+        check_range("angular acceleration", angular_acceleration, 1..=100)?;
+        self.send(LssCommand::with_param(id, "AA", angular_acceleration))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_angular_acceleration(id).await?;
+            verify_write("QAA", angular_acceleration, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure angular acceleration in degrees per second squared (°/s2)
+    /// Saved to EEPROM
+    ///
+    /// Accepts values between 1 and 100. Increments of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularAcceleration28AA29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_acceleration` - value for angular acceleration (1 to 100, Increments 10)
+    pub async fn configure_angular_acceleration(
+        &mut self,
+        id: u8,
+        angular_acceleration: i32,
+    ) -> DriverResult<()> {
+        check_range("angular acceleration", angular_acceleration, 1..=100)?;
+        self.send(LssCommand::with_param(id, "CAA", angular_acceleration))
+            .await?;
+        self.verify_setting(id, "QAA", QueryScope::Configured, angular_acceleration)
+            .await
+    }
+
+    /// Query angular acceleration in degrees per second squared (°/s2)
+    ///
+    /// Accepts values between 1 and 100. Increments  of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularAcceleration28AA29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_angular_acceleration(&mut self, id: u8) -> DriverResult<i32> {
+        self.query(LssCommand::simple(id, "QAA"), |response| {
+            let (_, value) = response.separate("QAA")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set angular deceleration in degrees per second squared (°/s2)
+    ///
+    /// Accepts values between 1 and 100. Increments of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularDeceleration28AD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_deceleration` - value for angular deceleration (1 to 100, Increments 10)
+    pub async fn set_angular_deceleration(
+        &mut self,
+        id: u8,
+        angular_deceleration: i32,
+    ) -> DriverResult<()> {
+        check_range("angular deceleration", angular_deceleration, 1..=100)?;
+        self.send(LssCommand::with_param(id, "AD", angular_deceleration))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_angular_deceleration(id).await?;
+            verify_write("QAD", angular_deceleration, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure angular deceleration in degrees per second squared (°/s2)
+    /// Saved to EEPROM
+    ///
+    /// Accepts values between 1 and 100. Increments of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularDeceleration28AD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `angular_deceleration` - value for angular deceleration (1 to 100, Increments 10)
+    pub async fn configure_angular_deceleration(
+        &mut self,
+        id: u8,
+        angular_deceleration: i32,
+    ) -> DriverResult<()> {
+        check_range("angular deceleration", angular_deceleration, 1..=100)?;
+        self.send(LssCommand::with_param(id, "CAD", angular_deceleration))
+            .await?;
+        self.verify_setting(id, "QAD", QueryScope::Configured, angular_deceleration)
+            .await
+    }
+
+    /// Query angular deceleration in degrees per second squared (°/s2)
+    ///
+    /// Accepts values between 1 and 100. Increments  of 10
+    /// Only used when motion profile is enabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularDeceleration28AD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_angular_deceleration(&mut self, id: u8) -> DriverResult<i32> {
+        self.query(LssCommand::simple(id, "QAD"), |response| {
+            let (_, value) = response.separate("QAD")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set maximum motor duty
+    ///
+    /// Accepts values between 255 and 1023
+    /// Only used when motion profile is disabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumMotorDuty28MMD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_motor_duty` - value for maximum motor duty (255 to 1023)
+    pub async fn set_maximum_motor_duty(
+        &mut self,
+        id: u8,
+        maximum_motor_duty: i32,
+    ) -> DriverResult<()> {
+        check_range("maximum motor duty", maximum_motor_duty, 255..=1023)?;
+        self.send(LssCommand::with_param(id, "MMD", maximum_motor_duty))
+            .await?;
+        if self.should_verify(id) {
+            let actual = self.query_maximum_motor_duty(id).await?;
+            verify_write("QMMD", maximum_motor_duty, actual)?;
+        }
+        Ok(())
+    }
+
+    /// Configure maximum motor duty
+    /// Saved to EEPROM
+    ///
+    /// Accepts values between 255 and 1023
+    /// Only used when motion profile is disabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumMotorDuty28MMD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_motor_duty` - value for maximum motor duty (255 to 1023)
+    pub async fn configure_maximum_motor_duty(
+        &mut self,
+        id: u8,
+        maximum_motor_duty: i32,
+    ) -> DriverResult<()> {
+        check_range("maximum motor duty", maximum_motor_duty, 255..=1023)?;
+        self.send(LssCommand::with_param(id, "CMMD", maximum_motor_duty))
+            .await?;
+        self.verify_setting(id, "QMMD", QueryScope::Configured, maximum_motor_duty)
+            .await
+    }
+
+    /// Query maximum motor duty
+    ///
+    /// Accepts values between 255 and 1023
+    /// Only used when motion profile is disabled
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumMotorDuty28MMD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_maximum_motor_duty(&mut self, id: u8) -> DriverResult<i32> {
+        self.query(LssCommand::simple(id, "QMMD"), |response| {
+            let (_, value) = response.separate("QMMD")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set maximum speed in degrees per second
+    ///
+    /// Accepts values up to 180.0
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinDegrees28SD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_speed` - value for maximum speed
+    pub async fn set_maximum_speed(&mut self, id: u8, maximum_speed: f32) -> DriverResult<()> {
+        check_range("maximum speed", maximum_speed, 0.0..=MAX_SPEED)?;
+        self.send(LssCommand::with_param(
+            id,
+            "SD",
+            units::deg_per_s_to_native(maximum_speed),
+        ))
+        .await?;
+        if self.should_verify(id) {
+            let actual = self.query_maximum_speed(id).await?;
+            verify_write(
+                "QSD",
+                units::deg_per_s_to_native(maximum_speed),
+                units::deg_per_s_to_native(actual),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Configure maximum speed in degrees per second
+    /// Saved to EEPROM
+    ///
+    /// Accepts values up to 180.0
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinDegrees28SD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_speed` - value for maximum speed
+    pub async fn configure_maximum_speed(
+        &mut self,
+        id: u8,
+        maximum_speed: f32,
+    ) -> DriverResult<()> {
+        check_range("maximum speed", maximum_speed, 0.0..=MAX_SPEED)?;
+        self.send(LssCommand::with_param(
+            id,
+            "CSD",
+            units::deg_per_s_to_native(maximum_speed),
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QSD",
+            QueryScope::Configured,
+            units::deg_per_s_to_native(maximum_speed),
+        )
+        .await
+    }
+
+    /// Query maximum speed in degrees per second
+    ///
+    /// Accepts values up to 180.0
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinDegrees28SD29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_maximum_speed(&mut self, id: u8) -> DriverResult<f32> {
+        self.query(LssCommand::simple(id, "QSD"), |response| {
+            let (_, value) = response.separate("QSD")?;
+            Ok(units::native_to_deg_per_s(value))
+        })
+        .await
+    }
+
+    /// Query maximum speed in degrees per second from either the session or EEPROM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `scope` - Whether to return session or configured value
+    pub async fn query_maximum_speed_in_scope(
+        &mut self,
+        id: u8,
+        scope: QueryScope,
+    ) -> DriverResult<f32> {
+        let value = self.query_in_scope(id, "QSD", scope).await?;
+        Ok(units::native_to_deg_per_s(value))
+    }
+
+    /// Set maximum speed in RPM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinRPM28SR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_speed` - value for maximum speed in RPM (0 to 30)
+    pub async fn set_maximum_speed_rpm(&mut self, id: u8, maximum_speed: f32) -> DriverResult<()> {
+        check_range("maximum speed", maximum_speed, 0.0..=MAX_SPEED_RPM)?;
+        self.send(LssCommand::with_param(
+            id,
+            "SR",
+            units::wheel_rpm_to_native(maximum_speed),
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QSR",
+            QueryScope::Session,
+            units::wheel_rpm_to_native(maximum_speed),
+        )
+        .await
+    }
+
+    /// Configure maximum speed in RPM
+    /// Saved to EEPROM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinRPM28SR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `maximum_speed` - value for maximum speed in RPM (0 to 30)
+    pub async fn configure_maximum_speed_rpm(
+        &mut self,
+        id: u8,
+        maximum_speed: f32,
+    ) -> DriverResult<()> {
+        check_range("maximum speed", maximum_speed, 0.0..=MAX_SPEED_RPM)?;
+        self.send(LssCommand::with_param(
+            id,
+            "CSR",
+            units::wheel_rpm_to_native(maximum_speed),
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QSR",
+            QueryScope::Configured,
+            units::wheel_rpm_to_native(maximum_speed),
+        )
+        .await
+    }
+
+    /// Query maximum speed in RPM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HMaximumSpeedinRPM28SR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_maximum_speed_rpm(&mut self, id: u8) -> DriverResult<f32> {
+        self.query(LssCommand::simple(id, "QSR"), |response| {
+            let (_, value) = response.separate("QSR")?;
+            Ok(units::native_to_wheel_rpm(value))
+        })
+        .await
+    }
+
+    /// Disables power to motor allowing it to be back driven
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    pub async fn limp(&mut self, id: u8) -> DriverResult<()> {
+        self.send(LssCommand::simple(id, "L")).await?;
+        Ok(())
+    }
+
+    /// Disables power to all servos on the bus
+    ///
+    /// Sends a single `L` to `BROADCAST_ID`, making the whole robot back drivable.
+    /// Useful on shutdown or panic. Servos don't reply to it so nothing is awaited.
+    pub async fn limp_all(&mut self) -> DriverResult<()> {
+        self.limp(BROADCAST_ID).await
+    }
+
+    /// Stops any ongoing motor motion and actively holds position
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    pub async fn halt_hold(&mut self, id: u8) -> DriverResult<()> {
+        self.send(LssCommand::simple(id, "H")).await?;
+        Ok(())
+    }
+
+    /// Stops motion of all servos on the bus and holds position
+    ///
+    /// Sends a single `H` to `BROADCAST_ID` so it can be used as a software emergency stop.
+    /// Servos don't reply to it so nothing is awaited.
+    /// Only servos that receive the frame are stopped, there is no confirmation.
+    pub async fn halt_hold_all(&mut self) -> DriverResult<()> {
+        self.halt_hold(BROADCAST_ID).await
+    }
+
+    /// Disables power to multiple motors
+    ///
+    /// Attempts every servo even if some of them fail.
+    /// Useful for shutdown where one unresponsive servo shouldn't prevent the rest from going limp.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to control
+    pub async fn try_limp_all(&mut self, ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>> {
+        self.try_each(
+            ids.iter().copied().zip(iter::repeat(())),
+            |driver, id, _| Box::pin(driver.limp(id)),
+        )
+        .await
+    }
+
+    /// Stops motion of multiple motors and actively holds position
+    ///
+    /// Attempts every servo even if some of them fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to control
+    pub async fn try_halt_hold_all(&mut self, ids: &[u8]) -> Result<(), Vec<(u8, LssDriverError)>> {
+        self.try_each(
+            ids.iter().copied().zip(iter::repeat(())),
+            |driver, id, _| Box::pin(driver.halt_hold(id)),
+        )
+        .await
+    }
+
+    /// Move multiple motors to absolute positions in degrees
+    ///
+    /// Attempts every servo even if some of them fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - Pairs of servo ID and absolute position in degrees
+    pub async fn try_move_group(
+        &mut self,
+        positions: &[(u8, f32)],
+    ) -> Result<(), Vec<(u8, LssDriverError)>> {
+        self.try_each(positions.iter().copied(), |driver, id, position| {
+            Box::pin(driver.move_to_position(id, position))
+        })
+        .await
+    }
+
+    /// Run `operation` for every servo and collect the errors instead of stopping at the first one
+    async fn try_each<T, F>(
+        &mut self,
+        targets: impl IntoIterator<Item = (u8, T)>,
+        mut operation: F,
+    ) -> Result<(), Vec<(u8, LssDriverError)>>
+    where
+        F: for<'a> FnMut(&'a mut LSSDriver, u8, T) -> BoxFuture<'a, DriverResult<()>>,
+    {
+        let mut errors = vec![];
+        for (id, argument) in targets {
+            if let Err(error) = operation(self, id, argument).await {
+                errors.push((id, error));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Query voltage of motor in volts
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to Query
+    pub async fn query_voltage(&mut self, id: u8) -> DriverResult<f32> {
+        // response message looks like *5QV11200<cr>
+        // Response is in mV
+        let voltage = self
+            .query(LssCommand::simple(id, "QV"), |response| {
+                let (_, value) = response.separate("QV")?;
+                Ok(units::native_to_volts(value))
+            })
+            .await?;
+        self.remember(id, |known| known.voltage = Some(Timestamped::now(voltage)));
+        Ok(voltage)
+    }
+
+    /// Query temperature of motor in celsius
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to Query
+    pub async fn query_temperature(&mut self, id: u8) -> DriverResult<f32> {
+        // response message looks like *5QT441<cr>
+        // Response is in 10s of celsius
+        // 441 would be 44.1 celsius
+        let temperature = self
+            .query(LssCommand::simple(id, "QT"), |response| {
+                let (_, value) = response.separate("QT")?;
+                Ok(units::native_to_celsius(value))
+            })
+            .await?;
+        self.remember(id, |known| {
+            known.temperature = Some(Timestamped::now(temperature))
+        });
+        Ok(temperature)
+    }
+
+    /// Query current of motor in Amps
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to Query
+    pub async fn query_current(&mut self, id: u8) -> DriverResult<f32> {
+        // response message looks like *5QT441<cr>
+        // Response is in mA
+        let current = self
+            .query(LssCommand::simple(id, "QC"), |response| {
+                let (_, value) = response.separate("QC")?;
+                Ok(units::native_to_amps(value))
+            })
+            .await?;
+        self.remember(id, |known| known.current = Some(Timestamped::now(current)));
+        Ok(current)
+    }
+
+    /// Query absolute current position of multiple servos in degrees
+    ///
+    /// Writes all queries before reading any replies so the whole bus is read in one round trip.
+    /// Results are in the same order as `ids`. Servos that don't answer yield `TimeoutError`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to query
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let positions = driver.query_positions(&[1, 2, 3]).await.unwrap();
+    ///     for position in positions {
+    ///         println!("{:?}", position);
+    ///     }
+    /// }
+    /// ```
+    pub async fn query_positions(&mut self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let positions = self
+            .query_pipelined(ids, "QD", |value| Ok(units::native_to_degrees(value)))
+            .await?;
+        self.remember_all(ids, &positions, |known, position| {
+            known.position = Some(position)
+        });
+        Ok(positions)
+    }
+
+    /// Query voltage of multiple servos in volts
+    ///
+    /// Same as [query_positions](LSSDriver::query_positions) for voltage.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to query
+    pub async fn query_voltages(&mut self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let voltages = self
+            .query_pipelined(ids, "QV", |value| Ok(units::native_to_volts(value)))
+            .await?;
+        self.remember_all(ids, &voltages, |known, voltage| {
+            known.voltage = Some(voltage)
+        });
+        Ok(voltages)
+    }
+
+    /// Query temperature of multiple servos in celsius
+    ///
+    /// Same as [query_positions](LSSDriver::query_positions) for temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to query
+    pub async fn query_temperatures(&mut self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let temperatures = self
+            .query_pipelined(ids, "QT", |value| Ok(units::native_to_celsius(value)))
+            .await?;
+        self.remember_all(ids, &temperatures, |known, temperature| {
+            known.temperature = Some(temperature)
+        });
+        Ok(temperatures)
+    }
+
+    /// Query current of multiple servos in amps
+    ///
+    /// Same as [query_positions](LSSDriver::query_positions) for current.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to query
+    pub async fn query_currents(&mut self, ids: &[u8]) -> DriverResult<Vec<DriverResult<f32>>> {
+        let currents = self
+            .query_pipelined(ids, "QC", |value| Ok(units::native_to_amps(value)))
+            .await?;
+        self.remember_all(ids, &currents, |known, current| {
+            known.current = Some(current)
+        });
+        Ok(currents)
+    }
+
+    /// Query status of multiple servos
+    ///
+    /// Same as [query_positions](LSSDriver::query_positions) for status.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to query
+    pub async fn query_statuses(
+        &mut self,
+        ids: &[u8],
+    ) -> DriverResult<Vec<DriverResult<MotorStatus>>> {
+        let statuses = self
+            .query_pipelined(ids, "Q", MotorStatus::from_i32)
+            .await?;
+        self.remember_all(ids, &statuses, |known, status| known.status = Some(status));
+        Ok(statuses)
+    }
+
+    /// Query telemetry of a servo
+    ///
+    /// Queries position, speed, voltage, temperature, current and status back to back
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to Query
+    pub async fn query_telemetry(&mut self, id: u8) -> DriverResult<ServoTelemetry> {
+        Ok(ServoTelemetry {
+            position: self.query_position(id).await?,
+            speed: self.query_speed_dps(id).await?,
+            voltage: self.query_voltage(id).await?,
+            temperature: self.query_temperature(id).await?,
+            current: self.query_current(id).await?,
+            status: self.query_status(id).await?,
+        })
+    }
+
+    /// Stream telemetry of multiple servos
+    ///
+    /// Queries servos round-robin waiting `interval` between each query.
+    /// Servo that fails to respond yields an error without ending the stream.
+    /// Stream is empty if no IDs are provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - IDs of servos you want to Query
+    /// * `interval` - Time between queries of two consecutive servos
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let stream = driver.telemetry_stream(vec![1, 2, 3], Duration::from_millis(20));
+    ///     futures::pin_mut!(stream);
+    ///     while let Some((id, telemetry)) = stream.next().await {
+    ///         println!("{} {:?}", id, telemetry);
+    ///     }
+    /// }
+    /// ```
+    pub fn telemetry_stream(
+        &mut self,
+        ids: Vec<u8>,
+        interval: Duration,
+    ) -> impl Stream<Item = (u8, DriverResult<ServoTelemetry>)> + '_ {
+        time::every(interval, (self, ids, 0), |(driver, ids, index)| {
+            Box::pin(async move {
+                let id = *ids.get(*index)?;
+                *index = (*index + 1) % ids.len();
+                Some((id, driver.query_telemetry(id).await))
+            })
+        })
+    }
+
+    /// Stream position of a servo
+    ///
+    /// Absolute position in degrees queried every `interval`. Same as [query_position](LSSDriver::query_position) in a loop.
+    /// Failed queries yield an error without ending the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `interval` - Time between queries
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let stream = driver.position_stream(5, Duration::from_millis(20));
+    ///     futures::pin_mut!(stream);
+    ///     while let Some(Ok(position)) = stream.next().await {
+    ///         println!("{}", position);
+    ///     }
+    /// }
+    /// ```
+    pub fn position_stream(
+        &mut self,
+        id: u8,
+        interval: Duration,
+    ) -> impl Stream<Item = DriverResult<f32>> + '_ {
+        time::every(interval, self, move |driver| {
+            Box::pin(async move { Some(driver.query_position(id).await) })
+        })
+    }
+
+    /// Stream voltage of a servo
+    ///
+    /// Voltage in volts queried every `interval`. Same as [query_voltage](LSSDriver::query_voltage) in a loop.
+    /// Failed queries yield an error without ending the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `interval` - Time between queries
+    pub fn voltage_stream(
+        &mut self,
+        id: u8,
+        interval: Duration,
+    ) -> impl Stream<Item = DriverResult<f32>> + '_ {
+        time::every(interval, self, move |driver| {
+            Box::pin(async move { Some(driver.query_voltage(id).await) })
+        })
+    }
+
+    /// Stream temperature of a servo
+    ///
+    /// Temperature in celsius queried every `interval`. Same as [query_temperature](LSSDriver::query_temperature) in a loop.
+    /// Failed queries yield an error without ending the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `interval` - Time between queries
+    pub fn temperature_stream(
+        &mut self,
+        id: u8,
+        interval: Duration,
+    ) -> impl Stream<Item = DriverResult<f32>> + '_ {
+        time::every(interval, self, move |driver| {
+            Box::pin(async move { Some(driver.query_temperature(id).await) })
+        })
+    }
+
+    /// Stream current of a servo
+    ///
+    /// Current in amps queried every `interval`. Same as [query_current](LSSDriver::query_current) in a loop.
+    /// Failed queries yield an error without ending the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `interval` - Time between queries
+    pub fn current_stream(
+        &mut self,
+        id: u8,
+        interval: Duration,
+    ) -> impl Stream<Item = DriverResult<f32>> + '_ {
+        time::every(interval, self, move |driver| {
+            Box::pin(async move { Some(driver.query_current(id).await) })
+        })
+    }
+
+    /// Stream status of a servo
+    ///
+    /// Status queried every `interval`. Same as [query_status](LSSDriver::query_status) in a loop.
+    /// Failed queries yield an error without ending the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `interval` - Time between queries
+    pub fn status_stream(
+        &mut self,
+        id: u8,
+        interval: Duration,
+    ) -> impl Stream<Item = DriverResult<MotorStatus>> + '_ {
+        time::every(interval, self, move |driver| {
+            Box::pin(async move { Some(driver.query_status(id).await) })
+        })
+    }
+
+    /// Query model string
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_model(&mut self, id: u8) -> DriverResult<Model> {
+        self.query(LssCommand::simple(id, "QMS"), |response| {
+            let (_, value) = response.separate_string("QMS")?;
+            Ok(Model::from_str(&value))
+        })
+        .await
+    }
+
+    /// Query firmware version
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_firmware_version(&mut self, id: u8) -> DriverResult<FirmwareVersion> {
+        self.query(LssCommand::simple(id, "QF"), |response| {
+            let (_, value) = response.separate_string("QF")?;
+            FirmwareVersion::from_str(&value)
+        })
+        .await
+    }
+
+    /// Query serial number
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_serial_number(&mut self, id: u8) -> DriverResult<String> {
+        self.query(LssCommand::simple(id, "QN"), |response| {
+            let (_, value) = response.separate_string("QN")?;
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set LED blinking mode
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HConfigureLEDBlinking28CLB29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `blinking_mode` - Blinking mode desired. Can be combination to make motor blink during multiple modes
+    pub async fn set_led_blinking(
+        &mut self,
+        id: u8,
+        blinking_mode: Vec<LedBlinking>,
+    ) -> DriverResult<()> {
+        let sum = blinking_mode
+            .iter()
+            .map(|item| *item as i32)
+            .sum::<i32>()
+            .min(LedBlinking::AlwaysBlink as i32);
+        self.send(LssCommand::with_param(id, "CLB", sum)).await?;
+        self.verify_setting(id, "QLB", QueryScope::Configured, sum)
+            .await
+    }
+
+    /// Query LED blinking mode
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HConfigureLEDBlinking28CLB29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    pub async fn query_led_blinking(&mut self, id: u8) -> DriverResult<Vec<LedBlinking>> {
+        self.query(LssCommand::simple(id, "QLB"), |response| {
+            let (_, value) = response.separate("QLB")?;
+            LedBlinking::from_i32_mask(value)
+        })
+        .await
+    }
+
+    /// Query origin offset in degrees
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HOriginOffset28O29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let origin_offset = driver.query_origin_offset(5).await;
+    /// }
+    /// ```
+    pub async fn query_origin_offset(&mut self, id: u8) -> DriverResult<f32> {
+        // response messages looks like *5QO-13
+        // Response is in tenths of degrees
+        self.query(LssCommand::simple(id, "QO"), |response| {
+            let (_, value) = response.separate("QO")?;
+            Ok(units::native_to_degrees(value))
+        })
+        .await
+    }
+
+    /// Query origin offset in degrees from either the session or EEPROM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `scope` - Whether to return session or configured value
+    pub async fn query_origin_offset_in_scope(
+        &mut self,
+        id: u8,
+        scope: QueryScope,
+    ) -> DriverResult<f32> {
+        let value = self.query_in_scope(id, "QO", scope).await?;
+        Ok(units::native_to_degrees(value))
+    }
+
+    /// Query the angular range in degrees
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularRange28AR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to query
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let angular_range = driver.query_angular_range(5).await.unwrap();
+    /// }
+    /// ```
+    pub async fn query_angular_range(&mut self, id: u8) -> DriverResult<f32> {
+        // Response looks like *5QAR1800 where 1800 is range in 1/10 degrees
+        // Contrary what the wiki says, which is *5AR1800 as example, the servo I used (HT1) returns
+        // *5QAR1800
+        let range = self
+            .query(LssCommand::simple(id, "QAR"), |response| {
+                let (_, value) = response.separate("QAR")?;
+
+                Ok(units::native_to_degrees(value))
+            })
+            .await?;
+        self.remember_angular_range(id, range);
+        Ok(range)
+    }
+
+    /// Query angular range in degrees from either the session or EEPROM
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to query
+    /// * `scope` - Whether to return session or configured value
+    pub async fn query_angular_range_in_scope(
+        &mut self,
+        id: u8,
+        scope: QueryScope,
+    ) -> DriverResult<f32> {
+        let value = self.query_in_scope(id, "QAR", scope).await?;
+        let range = units::native_to_degrees(value);
+        if scope == QueryScope::Session {
+            self.remember_angular_range(id, range);
+        }
+        Ok(range)
+    }
+
+    /// Set the angular range in degrees
+    /// Saved to EEPROM
+    ///
+    /// Same as [configure_angular_range](LSSDriver::configure_angular_range).
+    /// Use [set_session_angular_range](LSSDriver::set_session_angular_range) to change it only until the servo resets.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularRange28AR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `range` - Angular range in degrees (0 to 360)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_angular_range(5, 180.0).await;
+    /// }
+    /// ```
+    pub async fn set_angular_range(&mut self, id: u8, range: f32) -> DriverResult<()> {
+        self.configure_angular_range(id, range).await
+    }
+
+    /// Set the angular range in degrees for this session
+    ///
+    /// Lost when the servo resets. Use [configure_angular_range](LSSDriver::configure_angular_range) to save it to EEPROM.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularRange28AR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `range` - Angular range in degrees (0 to 360)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_session_angular_range(5, 180.0).await;
+    /// }
+    /// ```
+    pub async fn set_session_angular_range(&mut self, id: u8, range: f32) -> DriverResult<()> {
+        check_range("angular range", range, 0.0..=MAX_ANGULAR_RANGE)?;
+        self.send(LssCommand::with_param(
+            id,
+            "AR",
+            units::degrees_to_native(range),
+        ))
+        .await?;
+        self.remember_angular_range(id, range);
+        if self.should_verify(id) {
+            let actual = self.query_angular_range(id).await?;
+            verify_write(
+                "QAR",
+                units::degrees_to_native(range),
+                units::degrees_to_native(actual),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Configure the angular range in degrees
+    /// Saved to EEPROM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HAngularRange28AR29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `range` - Angular range in degrees (0 to 360)
+    pub async fn configure_angular_range(&mut self, id: u8, range: f32) -> DriverResult<()> {
+        check_range("angular range", range, 0.0..=MAX_ANGULAR_RANGE)?;
+        self.send(LssCommand::with_param(
+            id,
+            "CAR",
+            units::degrees_to_native(range),
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QAR",
+            QueryScope::Configured,
+            units::degrees_to_native(range),
+        )
+        .await
+    }
+
+    /// Queries the position in µs.
+    ///
+    /// When the position is inside the angular range it will return a value between 500 and 2500,
+    /// when the position is outside the angular range it will return either -500 or -2500.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HPositioninPWM28P29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let pwm_position = driver.query_pwm_position(5).await.unwrap();
+    /// }
+    /// ```
+    pub async fn query_pwm_position(&mut self, id: u8) -> DriverResult<i32> {
+        // Response looks like *5QP2334 where 2335 is in µs
+        self.query(LssCommand::simple(id, "QP"), |response| {
+            let (_, value) = response.separate("QP")?;
+
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Set origin offset in degrees
+    /// Saved to EEPROM
+    ///
+    /// Same as [configure_origin_offset](LSSDriver::configure_origin_offset).
+    /// Use [set_session_origin_offset](LSSDriver::set_session_origin_offset) to change it only until the servo resets.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HOriginOffset28O29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `origin_offset` - Offset from factory 0 in degrees (-180 to 180)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_origin_offset(5, -1.3).await;
+    /// }
+    /// ```
+    pub async fn set_origin_offset(&mut self, id: u8, origin_offset: f32) -> DriverResult<()> {
+        self.configure_origin_offset(id, origin_offset).await
+    }
+
+    /// Set origin offset in degrees for this session
+    ///
+    /// Lost when the servo resets. Use [configure_origin_offset](LSSDriver::configure_origin_offset) to save it to EEPROM.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HOriginOffset28O29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `origin_offset` - Offset from factory 0 in degrees (-180 to 180)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.set_session_origin_offset(5, -1.3).await;
+    /// }
+    /// ```
+    pub async fn set_session_origin_offset(
+        &mut self,
+        id: u8,
+        origin_offset: f32,
+    ) -> DriverResult<()> {
+        check_range(
+            "origin offset",
+            origin_offset,
+            -MAX_ORIGIN_OFFSET..=MAX_ORIGIN_OFFSET,
+        )?;
+        self.send(LssCommand::with_param(
+            id,
+            "O",
+            units::degrees_to_native(origin_offset),
+        ))
+        .await?;
+        if self.should_verify(id) {
+            let actual = self.query_origin_offset(id).await?;
+            verify_write(
+                "QO",
+                units::degrees_to_native(origin_offset),
+                units::degrees_to_native(actual),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Configure origin offset in degrees
+    /// Saved to EEPROM
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HOriginOffset28O29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to control
+    /// * `origin_offset` - Offset from factory 0 in degrees (-180 to 180)
+    pub async fn configure_origin_offset(
+        &mut self,
+        id: u8,
+        origin_offset: f32,
+    ) -> DriverResult<()> {
+        check_range(
+            "origin offset",
+            origin_offset,
+            -MAX_ORIGIN_OFFSET..=MAX_ORIGIN_OFFSET,
+        )?;
+        self.send(LssCommand::with_param(
+            id,
+            "CO",
+            units::degrees_to_native(origin_offset),
+        ))
+        .await?;
+        self.verify_setting(
+            id,
+            "QO",
+            QueryScope::Configured,
+            units::degrees_to_native(origin_offset),
+        )
+        .await
+    }
+
+    /// Move to PWM position in µs.
+    ///
+    /// You can use [set_angular_range](LSSDriver::set_angular_range) to range.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HPositioninPWM28P29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `position` - Position in µs in the range `500..=2500`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_pwm_position(5, 2334).await;
+    /// }
+    /// ```
+    pub async fn move_to_pwm_position(&mut self, id: u8, position: i32) -> DriverResult<()> {
+        check_range("PWM position", position, PWM_POSITION_RANGE)?;
+        self.send(LssCommand::with_param(id, "P", position)).await?;
+
+        Ok(())
+    }
+
+    /// Move to RC pulse position in µs.
+    ///
+    /// Same as [move_to_pwm_position](LSSDriver::move_to_pwm_position)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `position` - Position in µs in the range `500..=2500`
+    pub async fn move_to_pulse(&mut self, id: u8, position: i32) -> DriverResult<()> {
+        self.move_to_pwm_position(id, position).await
+    }
+
+    /// Queries the RC pulse position in µs.
+    ///
+    /// Same as [query_pwm_position](LSSDriver::query_pwm_position)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to query
+    pub async fn query_pulse_position(&mut self, id: u8) -> DriverResult<i32> {
+        self.query_pwm_position(id).await
+    }
+
+    /// Move to PWM position in µs with modifier.
+    ///
+    /// You can use [set_angular_range](LSSDriver::set_angular_range) to range.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HPositioninPWM28P29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `position` - Position in µs in the range `500..=2500`
+    /// * `modifier` - Modifier applied to this motion. Look at the type for more info.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, CommandModifier};
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_pwm_position_with_modifier(5, 2334, CommandModifier::Speed(750)).await;
+    /// }
+    /// ```
+    pub async fn move_to_pwm_position_with_modifier(
+        &mut self,
+        id: u8,
+        position: i32,
+        modifier: CommandModifier,
+    ) -> DriverResult<()> {
+        check_range("PWM position", position, PWM_POSITION_RANGE)?;
+        self.send(LssCommand::with_param_modifier(id, "P", position, modifier))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Move to PWM position in µs with modifiers.
+    ///
+    /// You can use [set_angular_range](LSSDriver::set_angular_range) to range.
+    ///
+    /// Read more on the [wiki](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/#HPositioninPWM28P29)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the servo you want to control
+    /// * `position` - Position in µs in the range `500..=2500`
+    /// * `modifiers` - Array of modifiers applied to this motion. Look at the type for more info.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, CommandModifier};
+    ///
+    /// async fn async_main() {
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     driver.move_to_pwm_position_with_modifiers(5, 2334, &[CommandModifier::Speed(750), CommandModifier::Timed(2500)]).await;
+    /// }
+    /// ```
+    pub async fn move_to_pwm_position_with_modifiers(
+        &mut self,
+        id: u8,
+        position: i32,
+        modifiers: &[CommandModifier],
+    ) -> DriverResult<()> {
+        check_range("PWM position", position, PWM_POSITION_RANGE)?;
+        self.send(LssCommand::with_param_modifiers(
+            id, "P", position, modifiers,
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find mechanical limits of a joint by driving it into its hard stops
+    ///
+    /// Rotates the servo in negative direction until the current stays over `current_threshold`
+    /// for several samples, records the position, then does the same in positive direction.
+    /// Current is ignored for a short time after the servo starts moving so inrush current isn't mistaken for a hard stop.
+    /// Servo is left holding position at the upper limit.
+    ///
+    /// Servo is halted on every exit path. That includes failed queries and running out of time.
+    /// If the future is dropped, or the halt itself fails, halt is written before the next command this driver sends.
+    /// Drivers dropped right after cancelling never send it, halt the servo yourself in that case.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want to calibrate
+    /// * `probe_speed` - Speed in °/s used to approach the hard stops
+    /// * `current_threshold` - Current in Amps indicating that servo hit a hard stop
+    /// * `timeout` - Time allowed for finding both limits. Fails with `TimeoutError` when it runs out
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::LSSDriver;
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let mut driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let (lower, upper) = driver
+    ///         .auto_find_limits(5, 10.0, 0.5, Duration::from_secs(20))
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn auto_find_limits(
+        &mut self,
+        id: u8,
+        probe_speed: f32,
+        current_threshold: f32,
+        timeout: Duration,
+    ) -> DriverResult<(f32, f32)> {
+        let deadline = Instant::now() + timeout;
+        let probe_speed = probe_speed.abs();
+        let lower = self
+            .find_hard_stop(id, -probe_speed, current_threshold, deadline)
+            .await?;
+        let upper = self
+            .find_hard_stop(id, probe_speed, current_threshold, deadline)
+            .await?;
+        Ok((lower, upper))
+    }
+
+    async fn find_hard_stop(
+        &mut self,
+        id: u8,
+        speed: f32,
+        current_threshold: f32,
+        deadline: Instant,
+    ) -> DriverResult<f32> {
+        let mut guard = HaltOnDrop {
+            driver: self,
+            id,
+            armed: true,
+        };
+        let stalled = guard
+            .driver
+            .rotate_until_stalled(id, speed, current_threshold, deadline)
+            .await;
+        guard.armed = false;
+        let driver = &mut *guard.driver;
+        if let Err(error) = driver.halt_hold(id).await {
+            // servo might still be rotating, that matters more than why it was stopped
+            driver.halt_pending = Some(id);
+            return Err(error);
+        }
+        stalled?;
+        driver.query_position(id).await
+    }
+
+    async fn rotate_until_stalled(
+        &mut self,
+        id: u8,
+        speed: f32,
+        current_threshold: f32,
+        deadline: Instant,
+    ) -> DriverResult<()> {
+        self.set_rotation_speed(id, speed).await?;
+        time::sleep(HARD_STOP_SETTLE_TIME).await;
+        let mut samples_over_threshold = 0;
+        while samples_over_threshold < HARD_STOP_SAMPLES {
+            if Instant::now() >= deadline {
+                return Err(LssDriverError::TimeoutError);
+            }
+            if self.query_current(id).await? >= current_threshold {
+                samples_over_threshold += 1;
+            } else {
+                samples_over_threshold = 0;
+            }
+            time::sleep(HARD_STOP_SAMPLE_INTERVAL).await;
+        }
+        Ok(())
+    }
+}
+
+/// Marks a servo to be halted before the next command if dropped while armed
+///
+/// Covers the futures of motion routines being dropped before they stop the servo themselves.
+/// Nothing is written from drop so it never blocks the executor.
+struct HaltOnDrop<'a> {
+    driver: &'a mut LSSDriver,
+    id: u8,
+    armed: bool,
+}
+
+impl Drop for HaltOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.driver.halt_pending = Some(self.id);
+        }
+    }
+}
+
+/// Widest angular range in degrees accepted by the servo
+const MAX_ANGULAR_RANGE: f32 = 360.0;
+
+/// Largest origin offset in degrees in either direction accepted by the servo
+const MAX_ORIGIN_OFFSET: f32 = 180.0;
+
+/// Fastest maximum speed in degrees per second accepted by the servo
+const MAX_SPEED: f32 = 180.0;
+
+/// Fastest maximum speed in RPM accepted by the servo. Same as `MAX_SPEED`
+const MAX_SPEED_RPM: f32 = MAX_SPEED / 6.0;
+
+/// Pulse widths in µs covering the angular range of RC/PWM moves
+const PWM_POSITION_RANGE: std::ops::RangeInclusive<i32> = 500..=2500;
+
+/// Validate value before sending it to the servo
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    name: &str,
+    value: T,
+    range: std::ops::RangeInclusive<T>,
+) -> DriverResult<()> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(LssDriverError::ValueOutOfRange(format!(
+            "{} {} not in {}..={}",
+            name,
+            value,
+            range.start(),
+            range.end()
+        )))
+    }
+}
+
+/// Add sent command and raw reply to parsing errors for easier debugging
+pub(crate) fn with_frames(
+    error: LssDriverError,
+    sent: &LssCommand,
+    received: &LssResponse,
+) -> LssDriverError {
+    let sent = Some(sent.as_str().to_owned());
+    match error {
+        LssDriverError::PacketParsingError(reason) | LssDriverError::ParseError { reason, .. } => {
+            LssDriverError::ParseError {
+                reason,
+                sent,
+                received: received.as_str().to_owned(),
+            }
+        }
+        LssDriverError::UnexpectedResponse { received, .. } => {
+            LssDriverError::UnexpectedResponse { sent, received }
+        }
+        error => error,
+    }
+}
+
+/// Compare value queried back with the one that was set
+fn verify_write<T: PartialEq + std::fmt::Debug>(
+    query: &str,
+    expected: T,
+    actual: T,
+) -> DriverResult<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(LssDriverError::VerificationFailed(format!(
+            "{} expected {:?} got {:?}",
+            query, expected, actual
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::serial_driver::LssResponse;
+    use super::*;
+    use approx::assert_relative_eq;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    struct MockedDriver {
+        expected_send: Vec<String>,
+        receive: Vec<String>,
+    }
+
+    #[async_trait]
+    impl FramedDriver for MockedDriver {
+        async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+            let expected = self.expected_send.pop().unwrap();
+            assert_eq!(expected, command.as_str().to_owned());
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            Ok(LssResponse::new(self.receive.pop().unwrap()))
+        }
+    }
+
+    /// Bus answering commands with the frames configured for them
+    ///
+    /// Frames queued by several commands are received newest command first
+    /// so tests catch code relying on the order of replies. Receives time out once nothing is queued.
+    #[derive(Default)]
+    struct MockBus {
+        replies: HashMap<String, Vec<String>>,
+        sent: Arc<Mutex<Vec<String>>>,
+        pending: Vec<String>,
+        delay: Option<Duration>,
+        send_delay: Option<Duration>,
+        failing: Option<String>,
+        writes_before_reads: bool,
+        reading: bool,
+    }
+
+    impl MockBus {
+        fn new() -> MockBus {
+            MockBus::default()
+        }
+
+        /// Answer `command` with `frames` in order
+        fn reply(mut self, command: &str, frames: &[&str]) -> MockBus {
+            let frames = frames.iter().map(|frame| frame.to_string()).collect();
+            self.replies.insert(command.to_owned(), frames);
+            self
+        }
+
+        /// Record every write into `sent`
+        fn recording(mut self, sent: &Arc<Mutex<Vec<String>>>) -> MockBus {
+            self.sent = sent.clone();
+            self
+        }
+
+        /// Wait before every receive
+        fn delayed(mut self, delay: Duration) -> MockBus {
+            self.delay = Some(delay);
+            self
+        }
+
+        /// Wait before every send like an RS-485 transport waiting for the transceiver
+        fn delayed_sends(mut self, delay: Duration) -> MockBus {
+            self.send_delay = Some(delay);
+            self
+        }
+
+        /// Fail sending `command` without recording it
+        fn failing(mut self, command: &str) -> MockBus {
+            self.failing = Some(command.to_owned());
+            self
+        }
+
+        /// Fail the test if anything is written after the first read
+        fn writes_before_reads(mut self) -> MockBus {
+            self.writes_before_reads = true;
+            self
+        }
+    }
+
+    #[async_trait]
+    impl FramedDriver for MockBus {
+        async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+            assert!(
+                !(self.writes_before_reads && self.reading),
+                "all writes have to happen before the first read"
+            );
+            if let Some(delay) = self.send_delay {
+                time::sleep(delay).await;
+            }
+            if self.failing.as_deref() == Some(command.as_str()) {
+                return Err(LssDriverError::SendingError);
+            }
+            self.sent.lock().unwrap().push(command.as_str().to_owned());
+            if let Some(frames) = self.replies.get(command.as_str()) {
+                self.pending.extend(frames.iter().rev().cloned());
+            }
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            self.reading = true;
+            if let Some(delay) = self.delay {
+                time::sleep(delay).await;
+            }
+            self.pending
+                .pop()
+                .map(LssResponse::new)
+                .ok_or(LssDriverError::TimeoutError)
+        }
+    }
+
+    /// Fails sending commands addressed to `failing_id`
+    struct PartiallyFailingDriver {
+        failing_id: u8,
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl FramedDriver for PartiallyFailingDriver {
+        async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+            if command
+                .as_str()
+                .starts_with(&format!("#{}", self.failing_id))
+            {
+                return Err(LssDriverError::SendingError);
+            }
+            self.sent.lock().unwrap().push(command.as_str().to_owned());
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            Err(LssDriverError::TimeoutError)
+        }
+    }
+
+    #[tokio::test]
+    async fn async_test_builds() {}
+
+    #[tokio::test]
+    async fn test_limp_color_move_hold() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#5QV\r".to_owned(),
+                "#4H\r".to_owned(),
+                "#3D1800\r".to_owned(),
+                "#2LED1\r".to_owned(),
+                "#1L\r".to_owned(),
+            ],
+            receive: vec!["*5QV11200\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver.limp(1).await.unwrap();
+        driver.set_color(2, LedColor::Red).await.unwrap();
+        driver.move_to_position(3, 180.0).await.unwrap();
+        driver.halt_hold(4).await.unwrap();
+        let voltage = driver.query_voltage(5).await.unwrap();
+        assert_relative_eq!(voltage, 11.2);
+    }
+
+    macro_rules! test_command {
+        ($name:ident, $expected:expr, $command:expr) => {
+            #[tokio::test]
+            #[allow(clippy::redundant_closure_call)]
+            async fn $name() {
+                let mocked_framed_driver = MockedDriver {
+                    expected_send: vec![$expected.to_owned()],
+                    receive: vec![],
+                };
+                let driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+                ($command)(driver).await;
+            }
+        };
+    }
+
+    macro_rules! test_query {
+        ($name:ident, $expected:expr, $recv:expr, $command:expr, $val:expr) => {
+            #[tokio::test]
+            #[allow(clippy::redundant_closure_call)]
+            async fn $name() {
+                let mocked_framed_driver = MockedDriver {
+                    expected_send: vec![$expected.to_owned()],
+                    receive: vec![$recv.to_owned()],
+                };
+                let driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+                let res = ($command)(driver).await;
+                assert_eq!(res, $val);
+            }
+        };
+    }
+
+    macro_rules! test_query_float {
+        ($name:ident, $expected:expr, $recv:expr, $command:expr, $val:expr) => {
+            #[tokio::test]
+            #[allow(clippy::redundant_closure_call)]
+            async fn $name() {
+                let mocked_framed_driver = MockedDriver {
+                    expected_send: vec![$expected.to_owned()],
+                    receive: vec![$recv.to_owned()],
+                };
+                let driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+                let res = ($command)(driver).await;
+                assert_relative_eq!(res, $val);
+            }
+        };
+    }
+
+    test_command!(
+        test_hold_command,
+        "#4H\r",
+        |mut driver: LSSDriver| async move {
+            driver.halt_hold(4_u8).await.unwrap();
+        }
+    );
+
+    test_query!(
+        test_query_id,
+        "#254QID\r",
+        "*QID5\r",
+        |mut driver: LSSDriver| async move { driver.query_id(BROADCAST_ID).await.unwrap() },
+        5
+    );
+    test_command!(
+        test_set_id,
+        "#1CID2\r",
+        |mut driver: LSSDriver| async move { driver.set_id(1, 2).await.unwrap() }
+    );
+    test_command!(
+        test_set_baud_rate,
+        "#1CB500000\r",
+        |mut driver: LSSDriver| async move {
+            driver.set_baud_rate(1, BaudRate::Baud500000).await.unwrap()
+        }
+    );
+    #[tokio::test]
+    async fn test_change_baud_rate() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec!["#254RESET\r".to_owned(), "#254CB500000\r".to_owned()],
+            receive: vec![],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .change_baud_rate(BROADCAST_ID, BaudRate::Baud500000)
+            .await
+            .unwrap();
+    }
+    test_query!(
+        test_query_baud_rate,
+        "#5QB\r",
+        "*5QB115200\r",
+        |mut driver: LSSDriver| async move { driver.query_baud_rate(5).await.unwrap() },
+        BaudRate::Baud115200
+    );
+
+    // Motion
+    test_command!(
+        test_move_to,
+        "#1D200\r",
+        |mut driver: LSSDriver| async move { driver.move_to_position(1, 20.0).await.unwrap() }
+    );
+    test_command!(
+        test_move_to_with_modifier,
+        "#1D200T15000\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_with_modifier(1, 20.0, CommandModifier::Timed(15000))
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_to_with_modifiers,
+        "#1D200T15000CL400\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_with_modifiers(
+                    1,
+                    20.0,
+                    &[
+                        CommandModifier::Timed(15000),
+                        CommandModifier::CurrentLimp(400),
+                    ],
+                )
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_to_with_speed,
+        "#1D200SD455\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_with_speed(1, 20.0, 45.5)
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_to_in,
+        "#1D200T800\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_in(1, 20.0, Duration::from_millis(800))
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_relative,
+        "#1MD-125\r",
+        |mut driver: LSSDriver| async move { driver.move_relative(1, -12.5).await.unwrap() }
+    );
+    test_command!(
+        test_move_relative_with_modifiers,
+        "#1MD900SD100\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_relative_with_modifiers(1, 90.0, &[CommandModifier::SpeedDegrees(100)])
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_to_with_current_hold,
+        "#1D450CH400\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_with_current_hold(1, 45.0, 400)
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_move_to_with_current_limp,
+        "#1D450CL400\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_position_with_current_limp(1, 45.0, 400)
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_configure_first_position,
+        "#1CFD-900\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .configure_first_position(1, Some(-90.0))
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_disable_first_position,
+        "#1CFD\r",
+        |mut driver: LSSDriver| async move { driver.configure_first_position(1, None).await.unwrap() }
+    );
+    test_query!(
+        test_query_first_position,
+        "#1QFD\r",
+        "*1QFD450\r",
+        |mut driver: LSSDriver| async move { driver.query_first_position(1).await.unwrap() },
+        Some(45.0)
+    );
+    test_query!(
+        test_query_disabled_first_position,
+        "#1QFD\r",
+        "*1QFD\r",
+        |mut driver: LSSDriver| async move { driver.query_first_position(1).await.unwrap() },
+        None
+    );
+    test_command!(
+        test_set_target_position,
+        "#1D200\r",
+        |mut driver: LSSDriver| async move { driver.set_target_position(1, 20.0).await.unwrap() }
+    );
+    #[tokio::test]
+    async fn test_move_to_in_matching_mode() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec!["#1D200\r".to_owned(), "#1QEM\r".to_owned()],
+            receive: vec!["*1QEM1\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .move_to_position_in_mode(1, 20.0, ExpectedMode::Profiled)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_move_to_in_mismatching_mode() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec!["#1QEM\r".to_owned()],
+            receive: vec!["*1QEM0\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        let res = driver
+            .move_to_position_in_mode(1, 20.0, ExpectedMode::Profiled)
+            .await;
+        assert!(matches!(
+            res,
+            Err(LssDriverError::MotionProfileMismatch(
+                ExpectedMode::Profiled
+            ))
+        ));
+    }
+    test_query_float!(
+        test_query_current_position,
+        "#5QD\r",
+        "*5QD132\r",
+        |mut driver: LSSDriver| async move { driver.query_position(5).await.unwrap() },
+        13.2
+    );
+    test_query_float!(
+        test_query_target_position,
+        "#5QDT\r",
+        "*5QDT6783\r",
+        |mut driver: LSSDriver| async move { driver.query_target_position(5).await.unwrap() },
+        678.3
+    );
+    #[tokio::test]
+    async fn test_query_position_error() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec!["#5QD\r".to_owned(), "#5QDT\r".to_owned()],
+            receive: vec!["*5QD850\r".to_owned(), "*5QDT900\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        let error = driver.query_position_error(5).await.unwrap();
+        assert_relative_eq!(error, 5.0);
+    }
+
+    // Wheel mode
+    test_command!(
+        test_set_rotation_speed_degrees,
+        "#5WD90\r",
+        |mut driver: LSSDriver| async move { driver.set_rotation_speed(5, 90.0).await.unwrap() }
+    );
+    test_query_float!(
+        test_query_rotation_speed_degrees,
+        "#5QWD\r",
+        "*5QWD90\r",
+        |mut driver: LSSDriver| async move { driver.query_rotation_speed(5).await.unwrap() },
+        90.0
+    );
+
+    test_command!(
+        test_set_rotation_speed_dps,
+        "#5WD-45\r",
+        |mut driver: LSSDriver| async move { driver.set_rotation_speed_dps(5, -45.0).await.unwrap() }
+    );
+    test_command!(
+        test_set_rotation_speed_rpm,
+        "#5WR12\r",
+        |mut driver: LSSDriver| async move { driver.set_rotation_speed_rpm(5, 12.0).await.unwrap() }
+    );
+    test_query_float!(
+        test_query_rotation_speed_rpm,
+        "#5QWR\r",
+        "*5QWR-12\r",
+        |mut driver: LSSDriver| async move { driver.query_rotation_speed_rpm(5).await.unwrap() },
+        -12.0
+    );
+    test_query_float!(
+        test_query_speed_dps,
+        "#5QWD\r",
+        "*5QWD-30\r",
+        |mut driver: LSSDriver| async move { driver.query_speed_dps(5).await.unwrap() },
+        -30.0
+    );
+    test_query_float!(
+        test_query_speed_rpm,
+        "#5QWR\r",
+        "*5QWR5\r",
+        |mut driver: LSSDriver| async move { driver.query_speed_rpm(5).await.unwrap() },
+        5.0
+    );
+
+    // Status
+    test_query!(
+        test_unknown_status,
+        "#5Q\r",
+        "*5Q0\r",
+        |mut driver: LSSDriver| async move { driver.query_status(5).await.unwrap() },
+        MotorStatus::Unknown
+    );
+    test_query!(
+        test_holding_status,
+        "#5Q\r",
+        "*5Q6\r",
+        |mut driver: LSSDriver| async move { driver.query_status(5).await.unwrap() },
+        MotorStatus::Holding
+    );
+    test_query!(
+        test_status_flags,
+        "#5Q\r",
+        "*5Q8\r",
+        |mut driver: LSSDriver| async move { driver.query_status_flags(5).await.unwrap() },
+        StatusFlags::STUCK
+    );
+    test_query!(
+        test_safety_status,
+        "#5Q1\r",
+        "*5Q3\r",
+        |mut driver: LSSDriver| async move { driver.query_safety_status(5).await.unwrap() },
+        SafeModeStatus::TemperatureLimit
+    );
+
+    test_command!(test_limp, "#5L\r", |mut driver: LSSDriver| async move {
+        driver.limp(5).await.unwrap()
+    });
+    test_command!(
+        test_limp_all,
+        "#254L\r",
+        |mut driver: LSSDriver| async move { driver.limp_all().await.unwrap() }
+    );
+    test_command!(
+        test_halt_hold,
+        "#5H\r",
+        |mut driver: LSSDriver| async move { driver.halt_hold(5).await.unwrap() }
+    );
+    test_command!(
+        test_halt_hold_all,
+        "#254H\r",
+        |mut driver: LSSDriver| async move { driver.halt_hold_all().await.unwrap() }
+    );
+
+    // LED
+    test_command!(
+        test_set_led,
+        "#5LED3\r",
+        |mut driver: LSSDriver| async move { driver.set_color(5, LedColor::Blue).await.unwrap() }
+    );
+    test_command!(
+        test_configure_led,
+        "#5CLED6\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_color(5, LedColor::Magenta).await.unwrap()
+        }
+    );
+    test_query!(
+        test_query_led,
+        "#5QLED\r",
+        "*5QLED5\r",
+        |mut driver: LSSDriver| async move { driver.query_color(5).await.unwrap() },
+        LedColor::Cyan
+    );
+
+    // gyre
+    test_command!(
+        test_set_gyre_direction,
+        "#5G-1\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_gyre_direction(5, Direction::CounterClockwise)
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_configure_gyre_direction,
+        "#5CG1\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .configure_gyre_direction(5, Direction::Clockwise)
+                .await
+                .unwrap()
+        }
+    );
+    test_query!(
+        test_query_gyre_direction,
+        "#5QG\r",
+        "*5QG-1\r",
+        |mut driver: LSSDriver| async move { driver.query_gyre_direction(5).await.unwrap() },
+        Direction::CounterClockwise
+    );
+
+    // motion profile
+    test_command!(
+        test_motion_profile_on,
+        "#5EM1\r",
+        |mut driver: LSSDriver| async move { driver.set_motion_profile(5, true).await.unwrap() }
+    );
+    test_command!(
+        test_motion_profile_off,
+        "#5EM0\r",
+        |mut driver: LSSDriver| async move { driver.set_motion_profile(5, false).await.unwrap() }
+    );
+    test_command!(
+        test_configure_motion_profile,
+        "#1CEM1\r",
+        |mut driver: LSSDriver| async move { driver.configure_motion_profile(1, true).await.unwrap() }
+    );
+    test_query!(
+        test_query_motion_profile_on,
+        "#5QEM\r",
+        "*5QEM1\r",
+        |mut driver: LSSDriver| async move { driver.query_motion_profile(5).await.unwrap() },
+        true
+    );
+    test_query!(
+        test_query_motion_profile_off,
+        "#5QEM\r",
+        "*5QEM0\r",
+        |mut driver: LSSDriver| async move { driver.query_motion_profile(5).await.unwrap() },
+        false
+    );
+
+    test_command!(
+        test_set_filter_position_count,
+        "#5FPC10\r",
+        |mut driver: LSSDriver| async move { driver.set_filter_position_count(5, 10).await.unwrap() }
+    );
+    test_command!(
+        test_configure_filter_position_count,
+        "#1CFPC10\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_filter_position_count(1, 10).await.unwrap()
+        }
+    );
+    test_query!(
+        test_query_filter_position_count,
+        "#5QFPC\r",
+        "*5QFPC10\r",
+        |mut driver: LSSDriver| async move { driver.query_filter_position_count(5).await.unwrap() },
+        10
+    );
+
+    #[tokio::test]
+    async fn test_query_filter_position_count_out_of_range() {
+        for reply in ["*5QFPC256\r", "*5QFPC-3\r"] {
+            let mocked_framed_driver = MockedDriver {
+                expected_send: vec!["#5QFPC\r".to_owned()],
+                receive: vec![reply.to_owned()],
+            };
+            let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+            let res = driver.query_filter_position_count(5).await;
+            assert!(matches!(res, Err(LssDriverError::ParseError { .. })));
+        }
+    }
+    test_command!(
+        test_set_angular_stiffness,
+        "#5AS-2\r",
+        |mut driver: LSSDriver| async move { driver.set_angular_stiffness(5, -2).await.unwrap() }
+    );
+    test_command!(
+        test_configure_angular_stiffness,
+        "#1CAS-2\r",
+        |mut driver: LSSDriver| async move { driver.configure_angular_stiffness(1, -2).await.unwrap() }
+    );
+    test_query!(
+        test_query_angular_stiffness,
+        "#5QAS\r",
+        "*5QAS-2\r",
+        |mut driver: LSSDriver| async move { driver.query_angular_stiffness(5).await.unwrap() },
+        -2
+    );
+
+    test_command!(
+        test_set_angular_holding_stiffness,
+        "#5AH3\r",
+        |mut driver: LSSDriver| async move { driver.set_angular_holding_stiffness(5, 3).await.unwrap() }
+    );
+    test_command!(
+        test_configure_angular_holding_stiffness,
+        "#1CAH3\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .configure_angular_holding_stiffness(1, 3)
+                .await
+                .unwrap()
+        }
+    );
+    test_query!(
+        test_query_angular_holding_stiffness,
+        "#5QAH\r",
+        "*5QAH3\r",
+        |mut driver: LSSDriver| async move { driver.query_angular_holding_stiffness(5).await.unwrap() },
+        3
+    );
+
+    test_command!(
+        test_set_angular_acceleration,
+        "#5AA30\r",
+        |mut driver: LSSDriver| async move { driver.set_angular_acceleration(5, 30).await.unwrap() }
+    );
+    test_command!(
+        test_configure_angular_acceleration,
+        "#1CAA30\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_angular_acceleration(1, 30).await.unwrap()
+        }
+    );
+    test_query!(
+        test_query_angular_acceleration,
+        "#5QAA\r",
+        "*5QAA30\r",
+        |mut driver: LSSDriver| async move { driver.query_angular_acceleration(5).await.unwrap() },
+        30
+    );
+
+    test_command!(
+        test_set_angular_deceleration,
+        "#5AD30\r",
+        |mut driver: LSSDriver| async move { driver.set_angular_deceleration(5, 30).await.unwrap() }
+    );
+    test_command!(
+        test_configure_angular_deceleration,
+        "#1CAD30\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_angular_deceleration(1, 30).await.unwrap()
+        }
+    );
+    test_query!(
+        test_query_angular_deceleration,
+        "#5QAD\r",
+        "*5QAD30\r",
+        |mut driver: LSSDriver| async move { driver.query_angular_deceleration(5).await.unwrap() },
+        30
+    );
+
+    test_command!(
+        test_maximum_motor_duty,
+        "#5MMD512\r",
+        |mut driver: LSSDriver| async move { driver.set_maximum_motor_duty(5, 512).await.unwrap() }
+    );
+    test_command!(
+        test_configure_maximum_motor_duty,
+        "#1CMMD512\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_maximum_motor_duty(1, 512).await.unwrap()
+        }
+    );
+    test_query!(
+        test_query_maximum_motor_duty,
+        "#5QMMD\r",
+        "*5QMMD512\r",
+        |mut driver: LSSDriver| async move { driver.query_maximum_motor_duty(5).await.unwrap() },
+        512
+    );
+
+    test_command!(
+        test_maximum_speed,
+        "#5SD1800\r",
+        |mut driver: LSSDriver| async move { driver.set_maximum_speed(5, 180.).await.unwrap() }
+    );
+    test_query_float!(
+        test_query_maximum_speed,
+        "#5QSD\r",
+        "*5QSD1800\r",
+        |mut driver: LSSDriver| async move { driver.query_maximum_speed(5).await.unwrap() },
+        180.
+    );
+
+    test_command!(
+        test_configure_maximum_speed,
+        "#1CSD1705\r",
+        |mut driver: LSSDriver| async move { driver.configure_maximum_speed(1, 170.5).await.unwrap() }
+    );
+
+    test_command!(
+        test_maximum_speed_rpm,
+        "#1SR30\r",
+        |mut driver: LSSDriver| async move { driver.set_maximum_speed_rpm(1, 30.0).await.unwrap() }
+    );
+    test_command!(
+        test_configure_maximum_speed_rpm,
+        "#1CSR30\r",
+        |mut driver: LSSDriver| async move {
+            driver.configure_maximum_speed_rpm(1, 30.0).await.unwrap()
+        }
+    );
+    test_query_float!(
+        test_query_maximum_speed_rpm,
+        "#1QSR\r",
+        "*1QSR45\r",
+        |mut driver: LSSDriver| async move { driver.query_maximum_speed_rpm(1).await.unwrap() },
+        45.0
+    );
+
+    // test telemetry queries
+    test_query_float!(
+        test_query_voltage,
+        "#5QV\r",
+        "*5QV11200\r",
+        |mut driver: LSSDriver| async move { driver.query_voltage(5).await.unwrap() },
+        11.2
+    );
+    test_query_float!(
+        test_query_temperature,
+        "#5QT\r",
+        "*5QT564\r",
+        |mut driver: LSSDriver| async move { driver.query_temperature(5).await.unwrap() },
+        56.4
+    );
+    test_query_float!(
+        test_query_current,
+        "#5QC\r",
+        "*5QC140\r",
+        |mut driver: LSSDriver| async move { driver.query_current(5).await.unwrap() },
+        0.14
+    );
+
+    test_query!(
+        test_query_model_string,
+        "#5QMS\r",
+        "*5QMSLSS-HS1\r",
+        |mut driver: LSSDriver| async move { driver.query_model(5).await.unwrap() },
+        Model::HS1
+    );
+    test_query!(
+        test_query_firmware_version,
+        "#5QF\r",
+        "*5QF368\r",
+        |mut driver: LSSDriver| async move { driver.query_firmware_version(5).await.unwrap() },
+        FirmwareVersion {
+            major: 368,
+            minor: 0,
+            patch: 0
+        }
+    );
+    test_query!(
+        test_query_serial_number,
+        "#5QN\r",
+        "*5QN12345678\r",
+        |mut driver: LSSDriver| async move { driver.query_serial_number(5).await.unwrap() },
+        "12345678".to_owned()
+    );
+
+    // Test blinking modes
+    test_command!(
+        test_blinking_mode_1,
+        "#5CLB0\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(5, vec![LedBlinking::NoBlinking])
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_blinking_mode_2,
+        "#5CLB1\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(5, vec![LedBlinking::Limp])
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_blinking_mode_3,
+        "#5CLB2\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(5, vec![LedBlinking::Holding])
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_blinking_mode_4,
+        "#5CLB12\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(
+                    5,
+                    vec![LedBlinking::Accelerating, LedBlinking::Decelerating],
+                )
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_blinking_mode_5,
+        "#5CLB48\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(5, vec![LedBlinking::Free, LedBlinking::Travelling])
+                .await
+                .unwrap()
+        }
+    );
+    test_command!(
+        test_blinking_mode_6,
+        "#5CLB63\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .set_led_blinking(5, vec![LedBlinking::AlwaysBlink])
+                .await
+                .unwrap()
+        }
+    );
+
+    test_query!(
+        test_query_blinking_mode,
+        "#5QLB\r",
+        "*5QLB12\r",
+        |mut driver: LSSDriver| async move { driver.query_led_blinking(5).await.unwrap() },
+        vec![LedBlinking::Accelerating, LedBlinking::Decelerating]
+    );
+
+    test_command!(
+        test_reset,
+        "#254RESET\r",
+        |mut driver: LSSDriver| async move { driver.reset(BROADCAST_ID).await.unwrap() }
+    );
+
+    test_query_float!(
+        test_query_origin_offset,
+        "#5QO\r",
+        "*5QO-13\r",
+        |mut driver: LSSDriver| async move { driver.query_origin_offset(5).await.unwrap() },
+        -1.3
+    );
+
+    test_query_float!(
+        test_query_origin_offset_session,
+        "#5QO\r",
+        "*5QO-13\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .query_origin_offset_in_scope(5, QueryScope::Session)
+                .await
+                .unwrap()
+        },
+        -1.3
+    );
+    test_query_float!(
+        test_query_origin_offset_configured,
+        "#5QO1\r",
+        "*5QO-25\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .query_origin_offset_in_scope(5, QueryScope::Configured)
+                .await
+                .unwrap()
+        },
+        -2.5
+    );
+    test_query!(
+        test_query_angular_stiffness_configured,
+        "#5QAS1\r",
+        "*5QAS-2\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .query_angular_stiffness_in_scope(5, QueryScope::Configured)
+                .await
+                .unwrap()
+        },
+        -2
+    );
+
+    test_command!(
+        test_execute_move_command,
+        "#5D900SD450T800CH600\r",
+        |mut driver: LSSDriver| async move {
+            let command = MoveCommand::to(90.0)
+                .speed_dps(45.0)
+                .timed(Duration::from_millis(800))
+                .current_limit_ma(600);
+            driver.execute(5, command).await.unwrap()
+        }
+    );
+    test_command!(
+        test_execute_relative_move_command,
+        "#5MD-100T500\r",
+        |mut driver: LSSDriver| async move {
+            let command = MoveCommand::by(-10.0).timed(Duration::from_millis(500));
+            driver.execute(5, command).await.unwrap()
+        }
+    );
+
+    #[tokio::test]
+    async fn test_identify_restores_color() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec![
+                "#5LED3\r".to_owned(),
+                "#5LED0\r".to_owned(),
+                "#5LED7\r".to_owned(),
+                "#5QLED\r".to_owned(),
+            ],
+            receive: vec!["*5QLED3\r".to_owned()],
+        }));
+        driver
+            .identify(5, Duration::from_millis(500))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_identify_restores_color_after_failed_blink() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#5QLED\r", &["*5QLED3\r"])
+                .failing("#5LED0\r")
+                .recording(&sent),
+        ));
+        let result = driver.identify(5, Duration::from_millis(500)).await;
+        assert!(matches!(result, Err(LssDriverError::SendingError)));
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["#5QLED\r", "#5LED7\r", "#5LED3\r"]
+        );
+    }
+
+    test_query!(
+        test_is_moving,
+        "#5Q\r",
+        "*5Q3\r",
+        |mut driver: LSSDriver| async move { driver.is_moving(5).await.unwrap() },
+        true
+    );
+
+    #[tokio::test]
+    async fn test_wait_until_stopped() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5Q\r".to_owned(), "#5Q\r".to_owned(), "#5Q\r".to_owned()],
+            receive: vec![
+                "*5Q6\r".to_owned(),
+                "*5Q4\r".to_owned(),
+                "*5Q2\r".to_owned(),
+            ],
+        }));
+        driver
+            .wait_until_stopped(5, Duration::from_secs(1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_stopped_times_out() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5Q\r".to_owned(); 100],
+            receive: vec!["*5Q3\r".to_owned(); 100],
+        }));
+        let result = driver
+            .wait_until_stopped(5, Duration::from_millis(50))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+    }
+
+    #[tokio::test]
+    async fn test_query_retries_garbled_reply() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QV\r".to_owned(), "#5QV\r".to_owned()],
+            receive: vec!["*5QV11200\r".to_owned(), "*5Q#V1\r".to_owned()],
+        }));
+        driver.set_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+        let voltage = driver.query_voltage(5).await.unwrap();
+        assert_relative_eq!(voltage, 11.2);
+    }
+
+    #[tokio::test]
+    async fn test_query_retries_are_limited() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QV\r".to_owned(), "#5QV\r".to_owned()],
+            receive: vec!["*5QV\r".to_owned(), "*5Q#V1\r".to_owned()],
+        }));
+        driver.set_retry_policy(RetryPolicy::new(2, Duration::from_millis(1)));
+        let result = driver.query_voltage(5).await;
+        assert!(matches!(result, Err(LssDriverError::ParseError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_query_skips_reply_from_other_servo() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QV\r".to_owned()],
+            receive: vec!["*5QV11200\r".to_owned(), "*4QV12000\r".to_owned()],
+        }));
+        let voltage = driver.query_voltage(5).await.unwrap();
+        assert_relative_eq!(voltage, 11.2);
+    }
+
+    #[tokio::test]
+    async fn test_query_rejects_unexpected_command() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QV\r".to_owned()],
+            receive: vec!["*5QT441\r".to_owned()],
+        }));
+        match driver.query_voltage(5).await {
+            Err(LssDriverError::UnexpectedResponse { sent, received }) => {
+                assert_eq!(sent.as_deref(), Some("#5QV\r"));
+                assert_eq!(received, "*5QT441\r");
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QLED\r".to_owned(), "#5LED1\r".to_owned()],
+            receive: vec!["*5QLED1\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        driver.set_color(5, LedColor::Red).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes_detects_mismatch() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QO\r".to_owned(), "#5O-13\r".to_owned()],
+            receive: vec!["*5QO0\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        let result = driver.set_session_origin_offset(5, -1.3).await;
+        assert!(matches!(result, Err(LssDriverError::VerificationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes_queries_configured_value() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QAS1\r".to_owned(), "#5CAS3\r".to_owned()],
+            receive: vec!["*5QAS3\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        driver.configure_angular_stiffness(5, 3).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes_detects_configured_mismatch() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QLB1\r".to_owned(), "#5CLB1\r".to_owned()],
+            receive: vec!["*5QLB0\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        let result = driver.set_led_blinking(5, vec![LedBlinking::Limp]).await;
+        assert!(matches!(result, Err(LssDriverError::VerificationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes_maximum_speed_rpm() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QSR\r".to_owned(), "#5SR10\r".to_owned()],
+            receive: vec!["*5QSR10\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        driver.set_maximum_speed_rpm(5, 10.0).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_writes_disabled_first_position() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QFD\r".to_owned(), "#5CFD\r".to_owned()],
+            receive: vec!["*5QFD\r".to_owned()],
+        }));
+        driver.set_verify_writes(true);
+        driver.configure_first_position(5, None).await.unwrap();
+    }
+
+    test_command!(
+        test_verify_writes_skips_broadcast,
+        "#254EM0\r",
+        |mut driver: LSSDriver| async move {
+            driver.set_verify_writes(true);
+            driver
+                .set_motion_profile(BROADCAST_ID, false)
+                .await
+                .unwrap()
+        }
+    );
+
+    #[tokio::test]
+    async fn test_parsing_error_contains_frames() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QLED\r".to_owned()],
+            receive: vec!["*5QLED9\r".to_owned()],
+        }));
+        let error = driver.query_color(5).await.unwrap_err();
+        match error {
+            LssDriverError::ParseError {
+                reason,
+                sent,
+                received,
+            } => {
+                assert!(reason.contains("Failed parsing LedColor from 9"));
+                assert_eq!(sent.as_deref(), Some("#5QLED\r"));
+                assert_eq!(received, "*5QLED9\r");
+            }
+            error => panic!("Unexpected error {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_parsing_error_contains_frames() {
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#2QV\r", &["*2QVabc\r"])
+                .writes_before_reads(),
+        ));
+        let voltages = driver.query_voltages(&[1, 2]).await.unwrap();
+        assert_relative_eq!(*voltages[0].as_ref().unwrap(), 11.2);
+        match &voltages[1] {
+            Err(LssDriverError::ParseError { sent, received, .. }) => {
+                assert_eq!(sent.as_deref(), Some("#2QV\r"));
+                assert_eq!(received, "*2QVabc\r");
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_setters_validate_range() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec![],
+            receive: vec![],
+        }));
+        let result = driver.set_angular_stiffness(5, 11).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.configure_angular_acceleration(5, 0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_maximum_motor_duty(5, 1024).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_angular_range(5, 400.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_session_angular_range(5, -10.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_origin_offset(5, 190.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_session_origin_offset(5, -180.5).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_maximum_speed(5, 180.5).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.configure_maximum_speed(5, -1.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.move_to_pwm_position(5, 2501).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver
+            .move_to_pwm_position_with_modifier(5, 499, CommandModifier::Speed(750))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_maximum_speed_rpm(5, 30.5).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.configure_maximum_speed_rpm(5, -1.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_rotation_speed(5, 181.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_rotation_speed_dps(5, -181.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_rotation_speed_rpm(5, -31.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.move_to_position(5, 361.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.set_target_position(5, -361.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver
+            .move_to_position_in(5, 400.0, Duration::from_millis(100))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.move_to_position_with_speed(5, 90.0, -45.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.move_relative(5, 721.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver
+            .execute(5, MoveCommand::to(90.0).speed_dps(-45.0))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.execute(5, MoveCommand::by(-800.0)).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+    }
+
+    #[tokio::test]
+    async fn test_moves_validate_session_angular_range() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec![
+                "#6D-1700\r".to_owned(),
+                "#6QAR\r".to_owned(),
+                "#5MD1800\r".to_owned(),
+                "#5D900\r".to_owned(),
+                "#5AR1800\r".to_owned(),
+            ],
+            receive: vec!["*6QAR3600\r".to_owned()],
+        }));
+        driver.set_session_angular_range(5, 180.0).await.unwrap();
+        driver.move_to_position(5, 90.0).await.unwrap();
+        let result = driver.move_to_position(5, 91.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        let result = driver.execute(5, MoveCommand::to(-100.0)).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        driver.move_relative(5, 180.0).await.unwrap();
+        let result = driver.move_relative(5, 181.0).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+        assert_relative_eq!(driver.query_angular_range(6).await.unwrap(), 360.0);
+        driver.move_to_position(6, -170.0).await.unwrap();
+        let result = driver.move_to_position(6, 180.5).await;
+        assert!(matches!(result, Err(LssDriverError::ValueOutOfRange(_))));
+    }
+
+    #[tokio::test]
+    async fn test_servo_handle() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QV\r".to_owned(), "#5D900\r".to_owned()],
+            receive: vec!["*5QV11200\r".to_owned()],
+        }));
+        let mut servo = driver.servo(5);
+        assert_eq!(servo.id(), 5);
+        servo.move_to_position(90.0).await.unwrap();
+        let voltage = servo.query_voltage().await.unwrap();
+        assert_relative_eq!(voltage, 11.2);
+    }
+
+    #[tokio::test]
+    async fn test_query_positions_pipelined() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QD\r", &["*1QD100\r"])
+                .reply("#2QD\r", &["*2QD-250\r"])
+                .reply("#4QD\r", &["*4QD900\r"])
+                .writes_before_reads()
+                .recording(&sent),
+        ));
+        let positions = driver.query_positions(&[1, 2, 3, 4]).await.unwrap();
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["#1QD\r", "#2QD\r", "#3QD\r", "#4QD\r"]
+        );
+        assert_relative_eq!(*positions[0].as_ref().unwrap(), 10.0);
+        assert_relative_eq!(*positions[1].as_ref().unwrap(), -25.0);
+        assert!(matches!(positions[2], Err(LssDriverError::TimeoutError)));
+        assert_relative_eq!(*positions[3].as_ref().unwrap(), 90.0);
+    }
+
+    #[tokio::test]
+    async fn test_query_statuses_pipelined() {
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1Q\r", &["*1Q6\r"])
+                .reply("#2Q\r", &["*2Q1\r"])
+                .writes_before_reads(),
+        ));
+        let statuses = driver.query_statuses(&[1, 2]).await.unwrap();
+        assert_eq!(*statuses[0].as_ref().unwrap(), MotorStatus::Holding);
+        assert_eq!(*statuses[1].as_ref().unwrap(), MotorStatus::Limp);
+    }
+
+    #[tokio::test]
+    async fn test_handle_safety_lane_preempts_queue() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let handle = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent))).spawn();
+        let (started, wait_started) = tokio::sync::oneshot::channel();
+        let (release, wait_release) = tokio::sync::oneshot::channel::<()>();
+        let busy = handle.clone();
+        let busy = tokio::spawn(async move {
+            busy.run(move |_| {
+                Box::pin(async move {
+                    started.send(()).unwrap();
+                    wait_release.await.unwrap();
+                })
+            })
+            .await
+        });
+        wait_started.await.unwrap();
+        let mut moves = vec![];
+        for id in 1..=3 {
+            let handle = handle.clone();
+            moves.push(tokio::spawn(async move {
+                handle.move_to_position(id, 0.0).await
+            }));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let safety = handle.clone();
+        let limp = tokio::spawn(async move { safety.limp(5).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        release.send(()).unwrap();
+        busy.await.unwrap().unwrap();
+        limp.await.unwrap().unwrap();
+        for motion in moves {
+            motion.await.unwrap().unwrap();
+        }
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 4);
+        assert_eq!(sent[0], "#5L\r");
+    }
+
+    #[tokio::test]
+    async fn test_pacing_spaces_frames() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        driver.set_pacing(Pacing::min_gap(Duration::from_millis(20)));
+        let start = std::time::Instant::now();
+        for id in 1..=3 {
+            driver.limp(id).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert_eq!(sent.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_bus_manager_routes_by_joint() {
+        let left_sent = Arc::new(Mutex::new(vec![]));
+        let right_sent = Arc::new(Mutex::new(vec![]));
+        let mut manager = BusManager::new();
+        let left = manager.add_bus(LSSDriver::with_driver(Box::new(
+            MockBus::new().recording(&left_sent),
+        )));
+        let right = manager.add_bus(LSSDriver::with_driver(Box::new(
+            MockBus::new().recording(&right_sent),
+        )));
+        manager.map_joint("left_hip", left, 1);
+        manager.map_joint("right_hip", right, 1);
+        manager.map_joint("right_knee", right, 2);
+        assert_eq!(manager.location("right_knee"), Some((right, 2)));
+        manager.servo("right_knee").unwrap().limp().await.unwrap();
+        manager.servo("left_hip").unwrap().limp().await.unwrap();
+        assert!(matches!(
+            manager.servo("neck"),
+            Err(LssDriverError::UnknownJoint(_))
+        ));
+        assert_eq!(*left_sent.lock().unwrap(), vec!["#1L\r"]);
+        assert_eq!(*right_sent.lock().unwrap(), vec!["#2L\r"]);
+    }
+
+    #[tokio::test]
+    async fn test_bus_manager_query_positions() {
+        let mut manager = BusManager::new();
+        let left = manager.add_bus(LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QD\r", &["*1QD100\r"])
+                .writes_before_reads(),
+        )));
+        let right = manager.add_bus(LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QD\r", &["*1QD200\r"])
+                .reply("#2QD\r", &["*2QD300\r"])
+                .writes_before_reads(),
+        )));
+        manager.map_joint("left_hip", left, 1);
+        manager.map_joint("right_hip", right, 1);
+        manager.map_joint("right_knee", right, 2);
+        let positions = manager
+            .query_positions(&["right_knee", "neck", "left_hip", "right_hip"])
+            .await
+            .unwrap();
+        assert_relative_eq!(*positions[0].as_ref().unwrap(), 30.0);
+        assert!(matches!(positions[1], Err(LssDriverError::UnknownJoint(_))));
+        assert_relative_eq!(*positions[2].as_ref().unwrap(), 10.0);
+        assert_relative_eq!(*positions[3].as_ref().unwrap(), 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_routes_concurrent_queries() {
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#1QD\r", &["*1QD100\r"])
+                .reply("#2QD\r", &["*2QD200\r"])
+                .reply("#2QDT\r", &["*2QDT300\r"])
+                .delayed(Duration::from_millis(5)),
+        ));
+        driver.set_receive_timeout(Duration::from_millis(200));
+        let dispatcher = driver.dispatcher();
+        let (voltage, first, second, target, missing) = futures::join!(
+            dispatcher.query(LssCommand::simple(1, "QV")),
+            dispatcher.query(LssCommand::simple(1, "QD")),
+            dispatcher.query(LssCommand::simple(2, "QD")),
+            dispatcher.query(LssCommand::simple(2, "QDT")),
+            dispatcher.query(LssCommand::simple(3, "QD")),
+        );
+        assert_eq!(voltage.unwrap().separate("QV").unwrap(), (1, 11200));
+        assert_eq!(first.unwrap().separate("QD").unwrap(), (1, 100));
+        assert_eq!(second.unwrap().separate("QD").unwrap(), (2, 200));
+        assert_eq!(target.unwrap().separate("QDT").unwrap(), (2, 300));
+        assert!(matches!(missing, Err(LssDriverError::TimeoutError)));
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_typed_queries() {
+        struct QueryGyre;
+
+        impl LssQuery for QueryGyre {
+            type Output = i32;
+
+            fn command(&self) -> &str {
+                "QG"
+            }
+
+            fn parse(&self, response: &LssResponse) -> DriverResult<i32> {
+                let (_, value) = response.separate("QG")?;
+                Ok(value)
+            }
+        }
+
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#2QD\r", &["*2QD200\r"])
+                .reply("#3QG\r", &["*3QG-1\r"])
+                .reply("#4Q\r", &["*4Qabc\r"])
+                .delayed(Duration::from_millis(5)),
+        ));
+        driver.set_receive_timeout(Duration::from_millis(200));
+        let dispatcher = driver.dispatcher();
+        let (voltage, position, gyre, status) = futures::join!(
+            dispatcher.query_voltage(1),
+            dispatcher.query_position(2),
+            dispatcher.run_query(3, &QueryGyre),
+            dispatcher.query_status(4),
+        );
+        assert_relative_eq!(voltage.unwrap(), 11.2);
+        assert_relative_eq!(position.unwrap(), 20.0);
+        assert_eq!(gyre.unwrap(), -1);
+        match status {
+            Err(LssDriverError::ParseError { sent, received, .. }) => {
+                assert_eq!(sent.as_deref(), Some("#4Q\r"));
+                assert_eq!(received, "*4Qabc\r");
+            }
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(LssCommand::simple(5, "QDT").name(), "QDT");
+        assert_eq!(LssCommand::with_param(12, "D", -300).name(), "D");
+        assert_eq!(LssCommand::simple(254, "QID").name(), "QID");
+    }
+
+    #[tokio::test]
+    async fn test_events_report_bus_activity() {
+        use futures::StreamExt;
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#5QV\r", &["*5QV11200\r"])
+                .reply("#6QV\r", &["*6QVabc\r"]),
+        ));
+        let events = driver.events();
+        driver.limp(5).await.unwrap();
+        driver.query_voltage(5).await.unwrap();
+        driver.query_voltage(6).await.unwrap_err();
+        driver.query_voltage(7).await.unwrap_err();
+        drop(driver);
+        let events: Vec<_> = events.collect().await;
+        let parse_error = LssDriverError::ParseError {
+            reason: "Failed parsing value".to_owned(),
+            sent: Some("#6QV\r".to_owned()),
+            received: "*6QVabc\r".to_owned(),
+        };
+        assert_eq!(
+            events,
+            vec![
+                DriverEvent::CommandSent(LssCommand::simple(5, "L")),
+                DriverEvent::CommandSent(LssCommand::simple(5, "QV")),
+                DriverEvent::ResponseReceived(LssResponse::new("*5QV11200\r".to_owned())),
+                DriverEvent::CommandSent(LssCommand::simple(6, "QV")),
+                DriverEvent::ResponseReceived(LssResponse::new("*6QVabc\r".to_owned())),
+                DriverEvent::ParseError {
+                    response: LssResponse::new("*6QVabc\r".to_owned()),
+                    error: parse_error.to_string(),
+                },
+                DriverEvent::CommandSent(LssCommand::simple(7, "QV")),
+                DriverEvent::Timeout,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_safety_guard_limps_on_drop() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let guard = SafetyGuard::new(driver, &[1, 2], SafeAction::Limp);
+        drop(guard);
+        assert_eq!(*sent.lock().unwrap(), vec!["#1L\r", "#2L\r"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_safety_guard_halts_on_panic() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let task = tokio::spawn(async move {
+            let mut guard = SafetyGuard::new(driver, &[3], SafeAction::HaltHold);
+            guard.move_to_position(3, 10.0).await.unwrap();
+            panic!("control loop crashed");
+        });
+        assert!(task.await.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["#3D100\r", "#3H\r"]);
+    }
+
+    #[test]
+    fn test_safety_guard_halts_on_panic_in_current_thread_runtime() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            runtime.block_on(async move {
+                let mut guard = SafetyGuard::new(driver, &[3], SafeAction::HaltHold);
+                guard.move_to_position(3, 10.0).await.unwrap();
+                panic!("control loop crashed");
+            })
+        }));
+        drop(runtime);
+        assert!(result.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["#3D100\r", "#3H\r"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_safety_guard_waits_for_timers_on_single_worker() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let bus = MockBus::new()
+            .recording(&sent)
+            .delayed_sends(Duration::from_millis(5));
+        let driver = LSSDriver::with_driver(Box::new(bus));
+        let task = tokio::spawn(async move {
+            let mut guard = SafetyGuard::new(driver, &[3, 4], SafeAction::Limp);
+            guard.move_to_position(3, 10.0).await.unwrap();
+            panic!("control loop crashed");
+        });
+        assert!(task.await.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["#3D100\r", "#3L\r", "#4L\r"]);
+    }
+
+    #[test]
+    fn test_safety_guard_waits_for_timers_in_current_thread_runtime() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let bus = MockBus::new()
+            .recording(&sent)
+            .delayed_sends(Duration::from_millis(5));
+        let driver = LSSDriver::with_driver(Box::new(bus));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            runtime.block_on(async move {
+                let mut guard = SafetyGuard::new(driver, &[3, 4], SafeAction::Limp);
+                guard.move_to_position(3, 10.0).await.unwrap();
+                panic!("control loop crashed");
+            })
+        }));
+        drop(runtime);
+        assert!(result.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["#3D100\r", "#3L\r", "#4L\r"]);
+    }
+
+    #[test]
+    fn test_safety_guard_into_inner_disarms() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let driver = SafetyGuard::new(driver, &[1], SafeAction::Limp).into_inner();
+        drop(driver);
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_builder_applies_settings() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::builder()
+            .transport(Box::new(MockBus::new().recording(&sent)))
+            .timeout(Duration::from_millis(50))
+            .retries(2)
+            .build()
+            .unwrap();
+        assert_eq!(driver.receive_timeout(), Duration::from_millis(50));
+        let result = driver.query_voltage(5).await;
+        assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+        assert_eq!(sent.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_builder_requires_port() {
+        assert!(matches!(
+            LSSDriver::builder().baud_rate(BaudRate::Baud9600).build(),
+            Err(LssDriverError::FailedOpeningSerialPort)
+        ));
+    }
+
+    #[test]
+    fn test_with_baud_rate_rejects_unsupported_baud_rate() {
+        assert!(matches!(
+            LSSDriver::with_baud_rate("COM3", 115201),
+            Err(LssDriverError::UnsupportedBaudRate(115201))
+        ));
+    }
+
+    #[test]
+    fn test_builder_rejects_port_with_transport() {
+        let result = LSSDriver::builder()
+            .transport(Box::new(MockBus::new()))
+            .port("COM3")
+            .build();
+        assert!(matches!(
+            result,
+            Err(LssDriverError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn test_builder_rejects_unsupported_baud_rate() {
+        let result = LSSDriver::builder()
+            .port("COM3")
+            .serial_options(SerialOptions::new(115201))
+            .build();
+        assert!(matches!(
+            result,
+            Err(LssDriverError::UnsupportedBaudRate(115201))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_last_known_values() {
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#5QV\r", &["*5QV11200\r"])
+                .reply("#6QV\r", &["*6QV11500\r"]),
+        ));
+        assert_eq!(driver.last_known(5), LastKnown::default());
+        driver.query_voltage(5).await.unwrap();
+        driver.query_voltage(7).await.unwrap_err();
+        let known = driver.last_known(5);
+        assert_relative_eq!(known.voltage.unwrap().value, 11.2);
+        assert!(known.voltage.unwrap().age() < Duration::from_secs(1));
+        assert!(known.position.is_none());
+        assert_eq!(driver.last_known(7), LastKnown::default());
+        let voltages = driver.query_voltages(&[6]).await.unwrap();
+        assert!(voltages[0].is_ok());
+        assert_relative_eq!(driver.last_known(6).voltage.unwrap().value, 11.5);
+    }
+
+    #[tokio::test]
+    async fn test_query_broadcast_collects_replies() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockBus::new().reply(
+            "#254QD\r",
+            &["*3QD100\r", "*1QD-50\r", "*2*4QD1QD3\r", "*5QD900\r"],
+        )));
+        let positions = driver.query_broadcast("QD").await.unwrap();
+        assert_eq!(positions, vec![(1, -50), (3, 100), (5, 900)]);
+    }
+
+    struct SetGyre(i32);
+
+    impl LssCommandSpec for SetGyre {
+        fn command(&self) -> &str {
+            "G"
+        }
+
+        fn value(&self) -> Option<i32> {
+            Some(self.0)
+        }
+
+        fn modifiers(&self) -> Vec<CommandModifier> {
+            vec![CommandModifier::Speed(100)]
+        }
+    }
+
+    struct QueryGyre;
+
+    impl LssQuery for QueryGyre {
+        type Output = bool;
+
+        fn command(&self) -> &str {
+            "QG"
+        }
+
+        fn parse(&self, response: &LssResponse) -> DriverResult<bool> {
+            let (_, value) = response.separate("QG")?;
+            Ok(value > 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_command_and_query() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec!["#5QG\r".to_owned(), "#5G-1S100\r".to_owned()],
+            receive: vec!["*5QG-1\r".to_owned()],
+        }));
+        driver.send_command(5, &SetGyre(-1)).await.unwrap();
+        assert!(!driver.run_query(5, &QueryGyre).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_raw_command_and_query() {
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec![
+                "#5QMS\r".to_owned(),
+                "#5G-1\r".to_owned(),
+                "#5RESET\r".to_owned(),
+            ],
+            receive: vec!["*5QMSLSS-HS1\r".to_owned()],
+        }));
+        driver.send_raw(5, "RESET", None).await.unwrap();
+        driver.send_raw(5, "G", Some(-1)).await.unwrap();
+        assert_eq!(driver.query_raw(5, "QMS").await.unwrap(), "LSS-HS1");
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_poller_keeps_latest_values() {
+        let handle = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QD\r", &["*1QD100\r"])
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#1QT\r", &["*1QT441\r"])
+                .reply("#1QC\r", &["*1QC500\r"])
+                .delayed(Duration::from_millis(5)),
+        ))
+        .spawn();
+        let poller = handle.telemetry_poller(&[1, 2], Duration::from_millis(10));
+        poller.keep_history(16);
+        for _ in 0..100 {
+            if poller.latest(1).is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let latest = poller.latest(1).unwrap();
+        assert_relative_eq!(latest.position.unwrap().value, 10.0);
+        assert_relative_eq!(latest.voltage.unwrap().value, 11.2);
+        assert_relative_eq!(latest.temperature.unwrap().value, 44.1);
+        assert_relative_eq!(latest.current.unwrap().value, 0.5);
+        assert_eq!(poller.latest(2), Some(LastKnown::default()));
+        let stats = poller.stats(1, Duration::from_secs(10)).unwrap();
+        assert_relative_eq!(stats.voltage.unwrap().max, 11.2);
+        assert_eq!(
+            poller.stats(2, Duration::from_secs(10)),
+            Some(TelemetryStats::default())
+        );
+        assert_eq!(poller.stats(3, Duration::from_secs(10)), None);
+        // user commands still get through while polling
+        handle.limp(1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_poller_raises_alarm_once() {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let handle = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QD\r", &["*1QD100\r"])
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#1QT\r", &["*1QT441\r"])
+                .reply("#1QC\r", &["*1QC500\r"])
+                .delayed(Duration::from_millis(5)),
+        ))
+        .spawn();
+        let poller = handle.telemetry_poller(&[1], Duration::from_millis(10));
+        poller.set_thresholds(
+            1,
+            AlarmThresholds {
+                max_temperature: Some(60.0),
+                min_voltage: Some(12.0),
+                ..AlarmThresholds::default()
+            },
+        );
+        let raised = Arc::new(AtomicUsize::new(0));
+        let counter = raised.clone();
+        poller.on_alarm(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut alarms = Box::pin(poller.alarms());
+        let alarm = alarms.next().await.unwrap();
+        assert_eq!(alarm.id, 1);
+        assert_eq!(alarm.kind, AlarmKind::UnderVoltage);
+        assert_relative_eq!(alarm.value, 11.2);
+        // voltage stays low for the following rounds without raising again
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(raised.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_position_stream() {
+        use futures::StreamExt;
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send: vec![
+                "#5QD\r".to_owned(),
+                "#5QD\r".to_owned(),
+                "#5QD\r".to_owned(),
+            ],
+            receive: vec![
+                "*5QD300\r".to_owned(),
+                "*5QDx\r".to_owned(),
+                "*5QD100\r".to_owned(),
+            ],
+        }));
+        let start = std::time::Instant::now();
+        let positions: Vec<_> = driver
+            .position_stream(5, Duration::from_millis(10))
+            .take(3)
+            .collect()
+            .await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_relative_eq!(*positions[0].as_ref().unwrap(), 10.0);
+        assert!(positions[1].is_err());
+        assert_relative_eq!(*positions[2].as_ref().unwrap(), 30.0);
+    }
+
+    #[tokio::test]
+    async fn test_handle_position_stream_interleaves_requests() {
+        use futures::StreamExt;
+        let sent = Arc::new(Mutex::new(vec![]));
+        let handle = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#5QD\r", &["*5QD100\r"])
+                .recording(&sent),
+        ))
+        .spawn();
+        let stream = handle.position_stream(5, Duration::from_millis(10));
+        futures::pin_mut!(stream);
+        assert_relative_eq!(stream.next().await.unwrap().unwrap(), 10.0);
+        handle.limp(6).await.unwrap();
+        assert_relative_eq!(stream.next().await.unwrap().unwrap(), 10.0);
+        assert_eq!(*sent.lock().unwrap(), vec!["#5QD\r", "#6L\r", "#5QD\r"]);
+    }
+
+    test_command!(
+        test_set_origin_offset,
+        "#5CO-24\r",
+        |mut driver: LSSDriver| async move { driver.set_origin_offset(5, -2.4).await.unwrap() }
+    );
+
+    test_command!(
+        test_set_session_origin_offset,
+        "#5O-24\r",
+        |mut driver: LSSDriver| async move { driver.set_session_origin_offset(5, -2.4).await.unwrap() }
+    );
+
+    test_command!(
+        test_configure_origin_offset,
+        "#5CO-24\r",
+        |mut driver: LSSDriver| async move { driver.configure_origin_offset(5, -2.4).await.unwrap() }
+    );
+
+    test_query_float!(
+        test_query_angular_range,
+        "#5QAR\r",
+        "*5QAR1800\r",
+        |mut driver: LSSDriver| async move { driver.query_angular_range(5).await.unwrap() },
+        180.0
+    );
+
+    test_command!(
+        test_set_angular_range,
+        "#5CAR1800\r",
+        |mut driver: LSSDriver| async move { driver.set_angular_range(5, 180.0).await.unwrap() }
+    );
+
+    test_command!(
+        test_set_session_angular_range,
+        "#5AR1800\r",
+        |mut driver: LSSDriver| async move { driver.set_session_angular_range(5, 180.0).await.unwrap() }
+    );
+
+    test_command!(
+        test_configure_angular_range,
+        "#5CAR1800\r",
+        |mut driver: LSSDriver| async move { driver.configure_angular_range(5, 180.0).await.unwrap() }
+    );
+
+    test_query!(
+        test_query_pwm_position,
+        "#5QP\r",
+        "*5QP2334\r",
+        |mut driver: LSSDriver| async move { driver.query_pwm_position(5).await.unwrap() },
+        2334
+    );
+
+    test_command!(
+        test_move_to_pwm_position,
+        "#5P2334\r",
+        |mut driver: LSSDriver| async move { driver.move_to_pwm_position(5, 2334).await.unwrap() }
+    );
+
+    test_command!(
+        test_move_to_pulse,
+        "#5P1500\r",
+        |mut driver: LSSDriver| async move { driver.move_to_pulse(5, 1500).await.unwrap() }
+    );
+
+    test_query!(
+        test_query_pulse_position,
+        "#5QP\r",
+        "*5QP-500\r",
+        |mut driver: LSSDriver| async move { driver.query_pulse_position(5).await.unwrap() },
+        -500
+    );
+
+    test_command!(
+        test_move_to_pwm_position_with_modifier,
+        "#5P2334S750\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_pwm_position_with_modifier(5, 2334, CommandModifier::Speed(750))
+                .await
+                .unwrap()
+        }
+    );
+
+    test_command!(
+        test_move_to_pwm_position_with_modifiers,
+        "#5P2334S750T2500\r",
+        |mut driver: LSSDriver| async move {
+            driver
+                .move_to_pwm_position_with_modifiers(
+                    5,
+                    2334,
+                    &[CommandModifier::Speed(750), CommandModifier::Timed(2500)],
+                )
+                .await
+                .unwrap()
+        }
+    );
+
+    #[tokio::test]
+    async fn test_auto_find_limits() {
+        let mut expected_send = vec!["#5WD-10\r".to_owned()];
+        expected_send.extend(vec!["#5QC\r".to_owned(); 6]);
+        expected_send.extend([
+            "#5H\r".to_owned(),
+            "#5QD\r".to_owned(),
+            "#5WD10\r".to_owned(),
+        ]);
+        expected_send.extend(vec!["#5QC\r".to_owned(); 3]);
+        expected_send.extend(["#5H\r".to_owned(), "#5QD\r".to_owned()]);
+        expected_send.reverse();
+        let mut receive: Vec<String> = [
+            // single sample over threshold doesn't count as a hard stop
+            "*5QC100\r",
+            "*5QC800\r",
+            "*5QC150\r",
+            "*5QC800\r",
+            "*5QC850\r",
+            "*5QC900\r",
+            "*5QD-900\r",
+            "*5QC900\r",
+            "*5QC900\r",
+            "*5QC900\r",
+            "*5QD1350\r",
+        ]
+        .iter()
+        .map(|reply| reply.to_string())
+        .collect();
+        receive.reverse();
+        let mut driver = LSSDriver::with_driver(Box::new(MockedDriver {
+            expected_send,
+            receive,
+        }));
+        let (lower, upper) = driver
+            .auto_find_limits(5, 10.0, 0.5, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_relative_eq!(lower, -90.0);
+        assert_relative_eq!(upper, 135.0);
+    }
+
+    #[tokio::test]
+    async fn test_auto_find_limits_halts_on_error() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let result = driver
+            .auto_find_limits(5, 10.0, 0.5, Duration::from_secs(5))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.first().unwrap(), "#5WD-10\r");
+        assert_eq!(sent.last().unwrap(), "#5H\r");
+    }
+
+    #[tokio::test]
+    async fn test_auto_find_limits_halts_when_cancelled() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(MockBus::new().recording(&sent)));
+        let result = tokio::time::timeout(
+            Duration::from_millis(50),
+            driver.auto_find_limits(5, 10.0, 0.5, Duration::from_secs(5)),
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(*sent.lock().unwrap(), vec!["#5WD-10\r"]);
+        driver.set_color(1, LedColor::Red).await.unwrap();
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec!["#5WD-10\r", "#5H\r", "#1LED1\r"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_find_limits_returns_failed_halt() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver =
+            LSSDriver::with_driver(Box::new(MockBus::new().failing("#5H\r").recording(&sent)));
+        let result = driver
+            .auto_find_limits(5, 10.0, 0.5, Duration::from_secs(5))
+            .await;
+        assert!(matches!(result, Err(LssDriverError::SendingError)));
+        // halt is attempted again before the next command
+        let result = driver.set_color(1, LedColor::Red).await;
+        assert!(matches!(result, Err(LssDriverError::SendingError)));
+        assert!(!sent.lock().unwrap().contains(&"#1LED1\r".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_stream() {
+        use futures::StreamExt;
+
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#1Q\r".to_owned(),
+                "#1QC\r".to_owned(),
+                "#1QT\r".to_owned(),
+                "#1QV\r".to_owned(),
+                "#1QWD\r".to_owned(),
+                "#1QD\r".to_owned(),
+                "#2QV\r".to_owned(),
+                "#2QWD\r".to_owned(),
+                "#2QD\r".to_owned(),
+                "#1Q\r".to_owned(),
+                "#1QC\r".to_owned(),
+                "#1QT\r".to_owned(),
+                "#1QV\r".to_owned(),
+                "#1QWD\r".to_owned(),
+                "#1QD\r".to_owned(),
+            ],
+            receive: vec![
+                "*1Q6\r".to_owned(),
+                "*1QC200\r".to_owned(),
+                "*1QT441\r".to_owned(),
+                "*1QV11200\r".to_owned(),
+                "*1QWD0\r".to_owned(),
+                "*1QD-900\r".to_owned(),
+                "*2QV\r".to_owned(),
+                "*2QWD0\r".to_owned(),
+                "*2QD100\r".to_owned(),
+                "*1Q4\r".to_owned(),
+                "*1QC200\r".to_owned(),
+                "*1QT441\r".to_owned(),
+                "*1QV11200\r".to_owned(),
+                "*1QWD90\r".to_owned(),
+                "*1QD900\r".to_owned(),
+            ],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        let stream = driver.telemetry_stream(vec![1, 2], Duration::from_millis(1));
+        let results: Vec<_> = stream.take(3).collect().await;
+        let (id, telemetry) = &results[0];
+        assert_eq!(*id, 1);
+        let telemetry = telemetry.as_ref().unwrap();
+        assert_relative_eq!(telemetry.position, 90.0);
+        assert_relative_eq!(telemetry.speed, 90.0);
+        assert_relative_eq!(telemetry.voltage, 11.2);
+        assert_relative_eq!(telemetry.temperature, 44.1);
+        assert_relative_eq!(telemetry.current, 0.2);
+        assert_eq!(telemetry.status, MotorStatus::Traveling);
+        let (id, telemetry) = &results[1];
+        assert_eq!(*id, 2);
+        assert!(telemetry.is_err());
+        let (id, telemetry) = &results[2];
+        assert_eq!(*id, 1);
+        assert_relative_eq!(telemetry.as_ref().unwrap().position, -90.0);
+    }
+
+    #[tokio::test]
+    async fn test_try_limp_all_collects_errors() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(PartiallyFailingDriver {
+            failing_id: 2,
+            sent: sent.clone(),
+        }));
+        let errors = driver.try_limp_all(&[1, 2, 3]).await.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 2);
+        assert!(matches!(errors[0].1, LssDriverError::SendingError));
+        assert_eq!(*sent.lock().unwrap(), vec!["#1L\r", "#3L\r"]);
+    }
+
+    #[tokio::test]
+    async fn test_try_move_group_sends_to_all() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec!["#2D-100\r".to_owned(), "#1D200\r".to_owned()],
+            receive: vec![],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .try_move_group(&[(1, 20.0), (2, -10.0)])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_query_filtered_position() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#2QD\r".to_owned(),
+                "#1QD\r".to_owned(),
+                "#1QD\r".to_owned(),
+                "#1QD\r".to_owned(),
+            ],
+            receive: vec![
+                "*2QD500\r".to_owned(),
+                "*1QD90\r".to_owned(),
+                "*1QD110\r".to_owned(),
+                "*1QD100\r".to_owned(),
+            ],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver.set_position_filter(0.5);
+        assert_relative_eq!(driver.query_filtered_position(1).await.unwrap(), 10.0);
+        assert_relative_eq!(driver.query_filtered_position(1).await.unwrap(), 10.5);
+        assert_relative_eq!(driver.query_filtered_position(1).await.unwrap(), 9.75);
+        // other servos keep their own state
+        assert_relative_eq!(driver.query_filtered_position(2).await.unwrap(), 50.0);
+    }
+
+    /// Replies to every receive after a delay
+    struct DelayedDriver {
+        receive: Vec<String>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl FramedDriver for DelayedDriver {
+        async fn send(&mut self, _command: LssCommand) -> DriverResult<()> {
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            tokio::time::sleep(self.delay).await;
+            Ok(LssResponse::new(self.receive.pop().unwrap()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_query_reply_is_discarded() {
+        let mut driver = LSSDriver::with_driver(Box::new(DelayedDriver {
+            receive: vec!["*5QD200\r".to_owned(), "*5QD100\r".to_owned()],
+            delay: Duration::from_millis(20),
+        }));
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(5), driver.query_position(5)).await;
+        assert!(cancelled.is_err());
+        let position = driver.query_position(5).await.unwrap();
+        assert_relative_eq!(position, 20.0);
+    }
+
+    /// Fails until reconnected. First `failed_reconnects` reconnects fail
+    struct UnpluggedDriver {
+        connected: bool,
+        failed_reconnects: usize,
+        sent: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl FramedDriver for UnpluggedDriver {
+        async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+            if !self.connected {
+                return Err(LssDriverError::SendingError);
+            }
+            self.sent.lock().unwrap().push(command.as_str().to_owned());
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            if self.connected {
+                Ok(LssResponse::new("*5QV11200\r".to_owned()))
+            } else {
+                Err(LssDriverError::Disconnected)
+            }
+        }
+
+        async fn reconnect(&mut self) -> DriverResult<()> {
+            if self.failed_reconnects > 0 {
+                self.failed_reconnects -= 1;
+                return Err(LssDriverError::FailedOpeningSerialPort);
+            }
+            self.connected = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_reconnect_resends_command() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut driver = LSSDriver::with_driver(Box::new(UnpluggedDriver {
+            connected: false,
+            failed_reconnects: 2,
+            sent: sent.clone(),
+        }));
+        driver.set_auto_reconnect(Some(RetryPolicy::new(3, Duration::from_millis(1))));
+        let mut events = driver.connection_events();
+        driver.limp(5).await.unwrap();
+        assert_eq!(*sent.lock().unwrap(), vec!["#5L\r"]);
+        assert_eq!(events.recv().await.unwrap(), ConnectionEvent::Disconnected);
+        assert_eq!(events.recv().await.unwrap(), ConnectionEvent::Reconnected);
+    }
+
+    #[tokio::test]
+    async fn test_auto_reconnect_gives_up() {
+        let mut driver = LSSDriver::with_driver(Box::new(UnpluggedDriver {
+            connected: false,
+            failed_reconnects: 5,
+            sent: Arc::new(Mutex::new(vec![])),
+        }));
+        driver.set_auto_reconnect(Some(RetryPolicy::new(2, Duration::from_millis(1))));
+        let result = driver.query_voltage(5).await;
+        assert!(matches!(
+            result,
+            Err(LssDriverError::FailedOpeningSerialPort)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_without_auto_reconnect() {
+        let mut driver = LSSDriver::with_driver(Box::new(UnpluggedDriver {
+            connected: false,
+            failed_reconnects: 0,
+            sent: Arc::new(Mutex::new(vec![])),
+        }));
+        let result = driver.limp(5).await;
+        assert!(matches!(result, Err(LssDriverError::SendingError)));
+    }
+
+    #[tokio::test]
+    async fn test_detect_id_conflicts() {
+        let mut driver = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1Q\r", &["*1Q6\r"])
+                .reply("#2Q\r", &["*2Q6\r", "*2Q1\r"])
+                .reply("#3Q\r", &["*3*3Q6Q1\r"]),
+        ));
+        let report = driver.detect_id_conflicts_in(&[0, 1, 2, 3]).await.unwrap();
+        assert_eq!(report.responding, vec![1]);
+        assert_eq!(report.conflicting, vec![2, 3]);
+        assert!(!report.is_ok());
+    }
+
+    /// Byte stream answering status queries with raw bytes configured per ID
+    struct NoisyBusIo {
+        replies: HashMap<u8, Vec<u8>>,
+        pending: Vec<u8>,
+    }
+
+    impl futures::io::AsyncRead for NoisyBusIo {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut [u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            if self.pending.is_empty() {
+                // nothing more to say, let the receive timeout expire
+                return std::task::Poll::Pending;
+            }
+            let read = buf.len().min(self.pending.len());
+            buf[..read].copy_from_slice(&self.pending[..read]);
+            self.pending.drain(..read);
+            std::task::Poll::Ready(Ok(read))
+        }
+    }
+
+    impl futures::io::AsyncWrite for NoisyBusIo {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            let id = std::str::from_utf8(buf)
+                .ok()
+                .and_then(|command| {
+                    command
+                        .trim_start_matches('#')
+                        .trim_end_matches("Q\r")
+                        .parse()
+                        .ok()
+                })
+                .unwrap();
+            self.pending = self.replies.get(&id).cloned().unwrap_or_default();
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_id_conflicts_through_codec() {
+        let mut replies = HashMap::new();
+        replies.insert(1, b"*1Q6\r".to_vec());
+        replies.insert(2, b"*2Q6\r*2Q1\r".to_vec());
+        // collision without a single start of frame
+        replies.insert(3, b"\x13\xfe\x036\r".to_vec());
+        // collision that broke the UTF-8 of the only frame
+        replies.insert(4, b"*4Q\xc6\r".to_vec());
+        let mut driver = LSSDriver::with_io(NoisyBusIo {
+            replies,
+            pending: vec![],
+        });
+        driver.set_receive_timeout(Duration::from_millis(20));
+        let report = driver
+            .detect_id_conflicts_in(&[0, 1, 2, 3, 4])
+            .await
+            .unwrap();
+        assert_eq!(report.responding, vec![1]);
+        assert_eq!(report.conflicting, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_shared_between_tasks() {
+        let handle = LSSDriver::with_driver(Box::new(
+            MockBus::new()
+                .reply("#1QV\r", &["*1QV11200\r"])
+                .reply("#2QV\r", &["*2QV7400\r"]),
+        ))
+        .spawn();
+        let tasks: Vec<_> = (0..10)
+            .map(|index| {
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    let id = index % 2 + 1;
+                    (id, handle.query_voltage(id).await.unwrap())
+                })
+            })
+            .collect();
+        for task in tasks {
+            let (id, voltage) = task.await.unwrap();
+            let expected = if id == 1 { 11.2 } else { 7.4 };
+            assert_relative_eq!(voltage, expected);
+        }
+        assert!(handle.try_limp_all(&[1, 2]).await.is_ok());
+    }
+
+    /// Fails all receives until reconnected
+    struct DisconnectedDriver {
+        connected: Arc<AtomicBool>,
+        reconnects: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl FramedDriver for DisconnectedDriver {
+        async fn send(&mut self, _command: LssCommand) -> DriverResult<()> {
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> DriverResult<LssResponse> {
+            if self.connected.load(Ordering::SeqCst) {
+                Ok(LssResponse::new("*5Q6\r".to_owned()))
+            } else {
+                Err(LssDriverError::TimeoutError)
+            }
+        }
+
+        async fn reconnect(&mut self) -> DriverResult<()> {
+            self.reconnects.fetch_add(1, Ordering::SeqCst);
+            self.connected.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reconnects() {
+        let connected = Arc::new(AtomicBool::new(true));
+        let reconnects = Arc::new(AtomicUsize::new(0));
+        let driver = LSSDriver::with_driver(Box::new(DisconnectedDriver {
+            connected: connected.clone(),
+            reconnects: reconnects.clone(),
+        }));
+        let shared = SharedLSSDriver::with_health_check(driver, 5, Duration::from_millis(1));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(reconnects.load(Ordering::SeqCst), 0);
+
+        connected.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(reconnects.load(Ordering::SeqCst), 1);
+        let status = shared.lock().await.query_status(5).await.unwrap();
+        assert_eq!(status, MotorStatus::Holding);
+    }
+
+    #[tokio::test]
+    async fn test_reset_and_wait() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#5Q\r".to_owned(),
+                "#5Q\r".to_owned(),
+                "#5RESET\r".to_owned(),
+            ],
+            receive: vec!["*5Q6\r".to_owned(), "*5\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .reset_and_wait(5, Duration::from_millis(100))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reset_and_wait_times_out() {
+        let mut driver = LSSDriver::with_driver(Box::new(DisconnectedDriver {
+            connected: Arc::new(AtomicBool::new(false)),
+            reconnects: Arc::new(AtomicUsize::new(0)),
+        }));
+        let res = driver.reset_and_wait(5, Duration::from_millis(10)).await;
+        assert!(matches!(res, Err(LssDriverError::TimeoutError)));
+    }
+
+    #[tokio::test]
+    async fn test_restore_factory_defaults() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#0Q\r".to_owned(),
+                "#0CONFIRM\r".to_owned(),
+                "#0DEFAULT\r".to_owned(),
+            ],
+            receive: vec!["*0Q1\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .restore_factory_defaults(0, Duration::from_millis(100))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_factory_defaults_polls_factory_id() {
+        let mocked_framed_driver = MockedDriver {
+            expected_send: vec![
+                "#0Q\r".to_owned(),
+                "#5CONFIRM\r".to_owned(),
+                "#5DEFAULT\r".to_owned(),
+            ],
+            receive: vec!["*0Q1\r".to_owned()],
+        };
+        let mut driver = LSSDriver::with_driver(Box::new(mocked_framed_driver));
+        driver
+            .restore_factory_defaults(5, Duration::from_millis(100))
+            .await
+            .unwrap();
+    }
+}
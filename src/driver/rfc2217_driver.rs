@@ -0,0 +1,441 @@
+//! RFC2217 (telnet com port control) transport
+//!
+//! Serial data is exchanged over telnet in binary mode with `IAC` bytes escaped.
+//! Line settings are sent as com port option subnegotiations.
+//! Option requests from the server are answered, agreeing only to binary mode,
+//! suppress go ahead and the com port option.
+//! [RFC2217](https://datatracker.ietf.org/doc/html/rfc2217)
+
+use crate::driver::message_types::{BaudRate, LssDriverError};
+use crate::driver::serial_driver::{
+    FramedDriver, LssCodec, LssCommand, LssResponse, DEFAULT_RECEIVE_TIMEOUT,
+};
+use crate::driver::time::timeout;
+use async_trait::async_trait;
+use bytes::{Buf, BufMut, BytesMut};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio_util::codec::Decoder;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const BINARY: u8 = 0;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const COM_PORT_OPTION: u8 = 44;
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const PARITY_NONE: u8 = 1;
+const STOPSIZE_ONE: u8 = 1;
+/// Added to the command by the server when acknowledging it
+const SERVER_REPLY_OFFSET: u8 = 100;
+
+/// How long the server has to acknowledge the baud rate
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Telnet command received from the server
+#[derive(Debug, PartialEq)]
+enum Telnet {
+    /// `DO`, `DONT`, `WILL` or `WONT` followed by the option
+    Negotiation(u8, u8),
+    /// Unescaped body of a subnegotiation, starting with the option
+    Subnegotiation(Vec<u8>),
+}
+
+/// Transport for servos attached to a networked serial device server speaking RFC2217
+pub struct Rfc2217Driver {
+    address: SocketAddr,
+    baud_rate: u32,
+    receive_timeout: Duration,
+    stream: TcpStream,
+    raw_buffer: BytesMut,
+    read_buffer: BytesMut,
+    codec: LssCodec,
+    /// Negotiations already sent, never repeated to avoid negotiation loops
+    negotiated: Vec<(u8, u8)>,
+    acknowledged_baud_rate: Option<u32>,
+}
+
+impl Rfc2217Driver {
+    pub async fn connect<A: ToSocketAddrs>(
+        address: A,
+        baud_rate: u32,
+    ) -> DriverResult<Rfc2217Driver> {
+        // servos never answer at other rates
+        BaudRate::try_from(baud_rate)?;
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        let address = stream
+            .peer_addr()
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        let mut driver = Rfc2217Driver {
+            address,
+            baud_rate,
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            stream,
+            raw_buffer: BytesMut::new(),
+            read_buffer: BytesMut::new(),
+            codec: LssCodec::new(),
+            negotiated: vec![],
+            acknowledged_baud_rate: None,
+        };
+        driver.negotiate().await.map_err(|error| match error {
+            LssDriverError::UnsupportedBaudRate(_) => error,
+            _ => LssDriverError::FailedOpeningSerialPort,
+        })?;
+        Ok(driver)
+    }
+
+    /// Enable binary mode and com port option and configure line for the servos (8N1)
+    ///
+    /// Returns once the server acknowledged the baud rate.
+    async fn negotiate(&mut self) -> DriverResult<()> {
+        let _ = self.stream.set_nodelay(true);
+        self.negotiated = vec![(WILL, BINARY), (DO, BINARY), (WILL, COM_PORT_OPTION)];
+        let mut message = vec![];
+        for (verb, option) in &self.negotiated {
+            message.extend([IAC, *verb, *option]);
+        }
+        message.extend(subnegotiation(SET_DATASIZE, &[8]));
+        message.extend(subnegotiation(SET_PARITY, &[PARITY_NONE]));
+        message.extend(subnegotiation(SET_STOPSIZE, &[STOPSIZE_ONE]));
+        self.stream
+            .write_all(&message)
+            .await
+            .map_err(|_| LssDriverError::SendingError)?;
+        self.change_baud_rate(self.baud_rate).await
+    }
+
+    /// Send baud rate and wait for the server to acknowledge it
+    async fn change_baud_rate(&mut self, baud_rate: u32) -> DriverResult<()> {
+        BaudRate::try_from(baud_rate)?;
+        self.acknowledged_baud_rate = None;
+        self.stream
+            .write_all(&subnegotiation(SET_BAUDRATE, &baud_rate.to_be_bytes()))
+            .await
+            .map_err(|_| LssDriverError::SendingError)?;
+        let acknowledged = timeout(NEGOTIATION_TIMEOUT, async {
+            loop {
+                if let Some(acknowledged) = self.acknowledged_baud_rate {
+                    return Ok(acknowledged);
+                }
+                self.read_raw().await?;
+            }
+        })
+        .await
+        .map_err(|_| LssDriverError::TimeoutError)??;
+        if acknowledged != baud_rate {
+            return Err(LssDriverError::UnsupportedBaudRate(baud_rate));
+        }
+        self.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    /// Read from the socket, keep serial data and handle telnet commands
+    async fn read_raw(&mut self) -> DriverResult<()> {
+        let mut read_buffer = [0_u8; 256];
+        let read = self
+            .stream
+            .read(&mut read_buffer)
+            .await
+            .map_err(|_| LssDriverError::Disconnected)?;
+        if read == 0 {
+            return Err(LssDriverError::Disconnected);
+        }
+        self.raw_buffer.extend_from_slice(&read_buffer[..read]);
+        for command in strip_telnet(&mut self.raw_buffer, &mut self.read_buffer) {
+            self.handle_telnet(command).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_telnet(&mut self, command: Telnet) -> DriverResult<()> {
+        match command {
+            Telnet::Negotiation(verb, option) => {
+                let reply = match (verb, option) {
+                    (DO, BINARY | SUPPRESS_GO_AHEAD | COM_PORT_OPTION) => WILL,
+                    (DO, _) => WONT,
+                    (WILL, BINARY | SUPPRESS_GO_AHEAD) => DO,
+                    (WILL, _) => DONT,
+                    _ => return Ok(()),
+                };
+                if self.negotiated.contains(&(reply, option)) {
+                    return Ok(());
+                }
+                self.negotiated.push((reply, option));
+                self.stream
+                    .write_all(&[IAC, reply, option])
+                    .await
+                    .map_err(|_| LssDriverError::Disconnected)
+            }
+            Telnet::Subnegotiation(body) => {
+                if let [COM_PORT_OPTION, command, a, b, c, d] = body[..] {
+                    if command == SET_BAUDRATE + SERVER_REPLY_OFFSET {
+                        self.acknowledged_baud_rate = Some(u32::from_be_bytes([a, b, c, d]));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> DriverResult<LssResponse> {
+        loop {
+            if let Some(response) = self
+                .codec
+                .decode(&mut self.read_buffer)
+                .map_err(|_| LssDriverError::Disconnected)?
+            {
+                return Ok(response);
+            }
+            self.read_raw().await?;
+        }
+    }
+}
+
+/// Build com port option subnegotiation escaping `IAC` in the value
+fn subnegotiation(command: u8, value: &[u8]) -> Vec<u8> {
+    let mut message = vec![IAC, SB, COM_PORT_OPTION, command];
+    message.extend(escape(value));
+    message.extend([IAC, SE]);
+    message
+}
+
+/// Double `IAC` bytes so they are sent as data
+fn escape(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for byte in data {
+        escaped.push(*byte);
+        if *byte == IAC {
+            escaped.push(IAC);
+        }
+    }
+    escaped
+}
+
+/// Move serial data from `raw` to `data` returning telnet commands
+///
+/// Incomplete commands are left in `raw` until more bytes arrive.
+fn strip_telnet(raw: &mut BytesMut, data: &mut BytesMut) -> Vec<Telnet> {
+    let mut commands = vec![];
+    while !raw.is_empty() {
+        if raw[0] != IAC {
+            let end = raw.iter().position(|b| *b == IAC).unwrap_or(raw.len());
+            data.put(raw.split_to(end));
+            continue;
+        }
+        let consumed = match raw.get(1) {
+            None => break,
+            Some(&IAC) => {
+                data.put_u8(IAC);
+                2
+            }
+            Some(&verb @ (DO | DONT | WILL | WONT)) => match raw.get(2) {
+                Some(&option) => {
+                    commands.push(Telnet::Negotiation(verb, option));
+                    3
+                }
+                None => break,
+            },
+            Some(&SB) => match subnegotiation_body(&raw[2..]) {
+                Some((body, length)) => {
+                    commands.push(Telnet::Subnegotiation(body));
+                    length + 2
+                }
+                None => break,
+            },
+            Some(_) => 2,
+        };
+        raw.advance(consumed);
+    }
+    commands
+}
+
+/// Unescape subnegotiation body up to `IAC SE` returning it with the number of bytes consumed
+fn subnegotiation_body(raw: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let mut body = vec![];
+    let mut index = 0;
+    loop {
+        match (raw.get(index)?, raw.get(index + 1)) {
+            (&IAC, Some(&SE)) => return Some((body, index + 2)),
+            (&IAC, Some(&IAC)) => {
+                body.push(IAC);
+                index += 2;
+            }
+            (&IAC, None) => return None,
+            (&byte, _) => {
+                body.push(byte);
+                index += 1;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FramedDriver for Rfc2217Driver {
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        self.read_buffer.clear();
+        Ok(())
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        self.codec.take_dropped_bytes()
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        self.stream
+            .write_all(&escape(command.as_bytes()))
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        timeout(self.receive_timeout, self.read_frame())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+    }
+
+    async fn reconnect(&mut self) -> DriverResult<()> {
+        self.stream = TcpStream::connect(self.address)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        self.raw_buffer.clear();
+        self.read_buffer.clear();
+        self.negotiate().await
+    }
+
+    async fn flush(&mut self) -> DriverResult<()> {
+        self.stream
+            .flush()
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn close(&mut self) -> DriverResult<()> {
+        self.stream
+            .shutdown()
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+
+    async fn set_baud_rate(&mut self, baud_rate: u32) -> DriverResult<()> {
+        self.change_baud_rate(baud_rate).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baud_rate_subnegotiation() {
+        assert_eq!(
+            subnegotiation(SET_BAUDRATE, &115200_u32.to_be_bytes()),
+            vec![
+                IAC,
+                SB,
+                COM_PORT_OPTION,
+                SET_BAUDRATE,
+                0,
+                1,
+                194,
+                0,
+                IAC,
+                SE
+            ]
+        );
+    }
+
+    #[test]
+    fn subnegotiation_escapes_iac() {
+        assert_eq!(
+            subnegotiation(SET_BAUDRATE, &[0, 0, 0, IAC]),
+            vec![
+                IAC,
+                SB,
+                COM_PORT_OPTION,
+                SET_BAUDRATE,
+                0,
+                0,
+                0,
+                IAC,
+                IAC,
+                IAC,
+                SE
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_telnet_commands() {
+        let mut raw = BytesMut::from(&b"*5Q"[..]);
+        raw.extend_from_slice(&[IAC, DO, COM_PORT_OPTION]);
+        raw.extend_from_slice(b"V1");
+        raw.extend_from_slice(&[IAC, SB, COM_PORT_OPTION, 101, 0, 1, 194, 0, IAC, SE]);
+        raw.extend_from_slice(&[IAC, IAC]);
+        raw.extend_from_slice(b"\r");
+        let mut data = BytesMut::new();
+        let commands = strip_telnet(&mut raw, &mut data);
+        assert_eq!(&data[..], b"*5QV1\xff\r");
+        assert!(raw.is_empty());
+        assert_eq!(
+            commands,
+            vec![
+                Telnet::Negotiation(DO, COM_PORT_OPTION),
+                Telnet::Subnegotiation(vec![COM_PORT_OPTION, 101, 0, 1, 194, 0])
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_incomplete_command() {
+        let mut raw = BytesMut::from(&b"*5"[..]);
+        raw.extend_from_slice(&[IAC, SB, COM_PORT_OPTION]);
+        let mut data = BytesMut::new();
+        assert!(strip_telnet(&mut raw, &mut data).is_empty());
+        assert_eq!(&data[..], b"*5");
+        assert_eq!(&raw[..], &[IAC, SB, COM_PORT_OPTION]);
+        raw.extend_from_slice(&[101, IAC, SE]);
+        raw.extend_from_slice(b"QV1\r");
+        assert_eq!(
+            strip_telnet(&mut raw, &mut data),
+            vec![Telnet::Subnegotiation(vec![COM_PORT_OPTION, 101])]
+        );
+        assert_eq!(&data[..], b"*5QV1\r");
+    }
+
+    #[test]
+    fn unescapes_subnegotiation_body() {
+        let mut raw =
+            BytesMut::from(&[IAC, SB, COM_PORT_OPTION, 101, 0, 0, IAC, IAC, SE, IAC, SE][..]);
+        let mut data = BytesMut::new();
+        assert_eq!(
+            strip_telnet(&mut raw, &mut data),
+            vec![Telnet::Subnegotiation(vec![
+                COM_PORT_OPTION,
+                101,
+                0,
+                0,
+                IAC,
+                SE
+            ])]
+        );
+        assert!(data.is_empty());
+        assert!(raw.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Put servos into a safe state when the driver goes away
+//!
+//! [SafetyGuard] owns a driver and limps or halts a set of servos when it's dropped.
+//! This includes unwinding after a panic so a crashed control loop doesn't leave an arm pushing against something.
+
+use crate::driver::time;
+use crate::LSSDriver;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+/// Upper bound on how long a drop waits for the safe action
+const DROP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// State servos are put into when a [SafetyGuard] is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeAction {
+    /// Disable power to the motors
+    Limp,
+    /// Stop and actively hold current position
+    HaltHold,
+}
+
+/// Driver wrapper that limps or halts servos on drop
+///
+/// Dereferences to [LSSDriver] so it can be used in place of the driver.
+/// Servos are put into the safe state when the guard is dropped, including during unwinding after a panic.
+/// Nothing happens if the process aborts, for example when built with `panic = "abort"`.
+///
+/// Commands are written before drop returns. Inside a multi thread runtime the dropping worker hands its
+/// other tasks to a new thread while it waits. Inside a current thread runtime they are written from a helper
+/// thread so dropping also works while the runtime is shutting down after a panic.
+/// Nothing drives the reactor of a current thread runtime while it waits for that thread.
+/// Timers of the transports fall back to `async-io` there, but a port that isn't ready to accept
+/// the commands stalls until the drop gives up after one second. Use a multi thread runtime if that matters.
+pub struct SafetyGuard {
+    driver: Option<LSSDriver>,
+    ids: Vec<u8>,
+    action: SafeAction,
+}
+
+impl SafetyGuard {
+    /// Wrap driver
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - Driver to guard
+    /// * `ids` - IDs of servos put into the safe state. Use `BROADCAST_ID` for every servo on the bus
+    /// * `action` - Safe state
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, SafeAction, SafetyGuard};
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::with_baud_rate("COM1", 115200).unwrap();
+    ///     let mut driver = SafetyGuard::new(driver, &[1, 2, 3], SafeAction::Limp);
+    ///     driver.move_to_position(1, 90.0).await.unwrap();
+    ///     // servos 1, 2 and 3 go limp when driver is dropped
+    /// }
+    /// ```
+    pub fn new(driver: LSSDriver, ids: &[u8], action: SafeAction) -> SafetyGuard {
+        SafetyGuard {
+            driver: Some(driver),
+            ids: ids.to_vec(),
+            action,
+        }
+    }
+
+    /// Return driver without putting servos into the safe state
+    pub fn into_inner(mut self) -> LSSDriver {
+        self.driver.take().expect("Driver is only taken on drop")
+    }
+}
+
+impl Deref for SafetyGuard {
+    type Target = LSSDriver;
+
+    fn deref(&self) -> &LSSDriver {
+        self.driver.as_ref().expect("Driver is only taken on drop")
+    }
+}
+
+impl DerefMut for SafetyGuard {
+    fn deref_mut(&mut self) -> &mut LSSDriver {
+        self.driver.as_mut().expect("Driver is only taken on drop")
+    }
+}
+
+impl Drop for SafetyGuard {
+    fn drop(&mut self) {
+        let mut driver = match self.driver.take() {
+            Some(driver) => driver,
+            None => return,
+        };
+        let ids = std::mem::take(&mut self.ids);
+        let action = self.action;
+        let make_safe = async move {
+            // servos that fail are reported by nobody. Keep going with the rest
+            let _ = match action {
+                SafeAction::Limp => driver.try_limp_all(&ids).await,
+                SafeAction::HaltHold => driver.try_halt_hold_all(&ids).await,
+            };
+            let _ = driver.flush().await;
+        };
+        block_on_drop(make_safe);
+    }
+}
+
+/// Run `future` to completion from a synchronous `drop`
+///
+/// Gives up after [DROP_TIMEOUT] so a transport that stopped responding can't hang unwinding.
+fn block_on_drop<F: Future<Output = ()> + Send>(future: F) {
+    let future = async move {
+        let _ = time::timeout(DROP_TIMEOUT, future).await;
+    };
+    #[cfg(feature = "runtime-tokio")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        match handle.runtime_flavor() {
+            // blocking a worker would stall timers and IO if it's the only one.
+            // block_in_place hands its tasks and the drivers to another thread first
+            tokio::runtime::RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(future))
+            }
+            // only thread of the runtime is the one waiting here, it might also be shutting down.
+            // Blocking isn't allowed on it so wait for a helper thread.
+            // Without a runtime context transport timers fall back to async-io instead of panicking
+            _ => std::thread::scope(|scope| {
+                let helper = scope.spawn(|| futures::executor::block_on(future));
+                // panicking again while unwinding would abort
+                let _ = helper.join();
+            }),
+        }
+        return;
+    }
+    futures::executor::block_on(future)
+}
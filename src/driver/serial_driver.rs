@@ -0,0 +1,949 @@
+#[cfg(feature = "runtime-tokio")]
+use crate::driver::message_types::{BaudRate, Rs485Config};
+use crate::driver::message_types::{CommandModifier, LssDriverError};
+#[cfg(feature = "runtime-tokio")]
+use crate::driver::time::{self, timeout};
+use crate::protocol::{self, ProtocolError};
+use async_trait::async_trait;
+use bytes::{Buf, BufMut, BytesMut};
+#[cfg(feature = "runtime-tokio")]
+use futures::{SinkExt, StreamExt};
+use std::{io, str, time::Duration};
+#[cfg(all(target_family = "windows", feature = "runtime-tokio"))]
+use tokio::sync::Mutex;
+#[cfg(feature = "runtime-tokio")]
+use tokio_serial::{
+    ClearBuffer, DataBits, FlowControl, Parity, SerialPort, SerialPortBuilderExt, StopBits,
+};
+use tokio_util::codec::{Decoder, Encoder};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Single command frame `#<id><cmd><value><modifiers>\r`
+///
+/// Encoded on construction so transports only need to write [as_bytes](LssCommand::as_bytes).
+#[derive(PartialEq, Clone, Debug)]
+pub struct LssCommand {
+    id: u8,
+    message: String,
+}
+
+impl LssCommand {
+    pub(crate) fn encode(
+        id: u8,
+        cmd: &str,
+        val: Option<i32>,
+        modifiers: &[CommandModifier],
+    ) -> LssCommand {
+        let modifiers: Vec<_> = modifiers
+            .iter()
+            .filter_map(CommandModifier::as_pair)
+            .collect();
+        let mut message = String::new();
+        protocol::write_command(&mut message, id, cmd, val, &modifiers)
+            .expect("Writing to String can't fail");
+        LssCommand { id, message }
+    }
+
+    /// Command with a value. e.g. `#5D900\r`
+    pub fn with_param(id: u8, cmd: &str, val: i32) -> LssCommand {
+        LssCommand::encode(id, cmd, Some(val), &[])
+    }
+
+    /// Command with a value and one modifier. e.g. `#5D900S100\r`
+    pub fn with_param_modifier(
+        id: u8,
+        cmd: &str,
+        val: i32,
+        modifier: CommandModifier,
+    ) -> LssCommand {
+        LssCommand::encode(id, cmd, Some(val), &[modifier])
+    }
+
+    /// Command with a value and several modifiers
+    pub fn with_param_modifiers(
+        id: u8,
+        cmd: &str,
+        val: i32,
+        modifiers: &[CommandModifier],
+    ) -> LssCommand {
+        LssCommand::encode(id, cmd, Some(val), modifiers)
+    }
+
+    /// Command without a value. e.g. `#5QD\r`
+    pub fn simple(id: u8, cmd: &str) -> LssCommand {
+        LssCommand::encode(id, cmd, None, &[])
+    }
+
+    /// ID of servo the command is addressed to
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Command letters without ID and value. e.g. `QD` for `#5QD\r`
+    pub fn name(&self) -> &str {
+        let body = self
+            .message
+            .trim_start_matches(protocol::COMMAND_START)
+            .trim_start_matches(|c: char| c.is_ascii_digit());
+        let len = body
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(body.len());
+        &body[..len]
+    }
+
+    /// Encoded frame including the trailing `\r`
+    pub fn as_bytes(&self) -> &[u8] {
+        self.message.as_bytes()
+    }
+
+    /// Encoded frame including the trailing `\r`
+    pub fn as_str(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Single reply frame `*<id><cmd><value>\r` as received from the bus
+///
+/// Not validated on construction. Parsing happens when the value is extracted.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LssResponse {
+    message: String,
+}
+
+impl LssResponse {
+    /// Wrap received frame. Trailing `\r` is optional
+    pub fn new(message: String) -> LssResponse {
+        LssResponse { message }
+    }
+
+    /// Frame as it was received
+    pub fn as_str(&self) -> &str {
+        &self.message
+    }
+
+    fn parsing_error(&self, error: ProtocolError) -> LssDriverError {
+        match error {
+            ProtocolError::UnexpectedCommand => LssDriverError::UnexpectedResponse {
+                sent: None,
+                received: self.message.clone(),
+            },
+            error => self.value_error(error.to_string()),
+        }
+    }
+
+    fn value_error(&self, reason: String) -> LssDriverError {
+        LssDriverError::ParseError {
+            reason,
+            sent: None,
+            received: self.message.clone(),
+        }
+    }
+
+    /// ID of servo that sent the reply
+    ///
+    /// `None` for replies without an ID such as QID
+    pub fn id(&self) -> Option<u8> {
+        protocol::reply_id(&self.message)
+    }
+
+    /// Parse ID and numeric value following `separator`
+    ///
+    /// Fails with `UnexpectedResponse` if the reply is for a different command
+    pub fn separate(&self, separator: &str) -> DriverResult<(u8, i32)> {
+        protocol::parse_reply(&self.message, separator).map_err(|error| self.parsing_error(error))
+    }
+
+    /// Same as separate but validates that value fits into `T`
+    /// instead of silently truncating it
+    pub fn separate_as<T: TryFrom<i32>>(&self, separator: &str) -> DriverResult<(u8, T)> {
+        let (id, value) = self.separate(separator)?;
+        let value = T::try_from(value)
+            .map_err(|_| self.value_error(format!("Value {} out of range", value)))?;
+        Ok((id, value))
+    }
+
+    /// Parse ID and text following `separator`
+    pub fn separate_string(&self, separator: &str) -> DriverResult<(u8, String)> {
+        let (id, value) = protocol::split_reply(&self.message, separator)
+            .map_err(|error| self.parsing_error(error))?;
+        Ok((id, value.to_owned()))
+    }
+
+    /// Similar to separate but doesn't parse the ID
+    /// This is useful for queries that don't return ID
+    ///
+    /// Such as QID
+    pub fn get_val(&self, separator: &str) -> DriverResult<i32> {
+        protocol::parse_reply_value(&self.message, separator)
+            .map_err(|error| self.parsing_error(error))
+    }
+
+    /// Same as get_val but validates that value fits into `T`
+    /// instead of silently truncating it
+    pub fn get_val_as<T: TryFrom<i32>>(&self, separator: &str) -> DriverResult<T> {
+        let value = self.get_val(separator)?;
+        T::try_from(value).map_err(|_| self.value_error(format!("Value {} out of range", value)))
+    }
+}
+
+/// Longest reply the codec waits for before treating buffered bytes as garbage
+const MAX_FRAME_LENGTH: usize = 64;
+
+/// Incremental decoder for LSS replies
+///
+/// Frames start with `*` and end with `\r`.
+/// Tolerates frames split across reads, multiple frames in one read and noise between frames.
+/// A frame missing its `\r` is terminated by the start of the next frame.
+/// Frames that aren't valid UTF-8 are dropped.
+/// Dropped bytes are counted so that garbled replies can still be noticed, see [take_dropped_bytes](LssCodec::take_dropped_bytes).
+#[derive(Debug, Default)]
+pub struct LssCodec {
+    dropped_bytes: usize,
+}
+
+impl LssCodec {
+    pub fn new() -> LssCodec {
+        LssCodec::default()
+    }
+
+    /// Number of noise bytes dropped since the previous call
+    pub fn take_dropped_bytes(&mut self) -> usize {
+        std::mem::take(&mut self.dropped_bytes)
+    }
+}
+
+impl Decoder for LssCodec {
+    type Item = LssResponse;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            // drop noise preceding start of a frame
+            match src.iter().position(|b| *b == b'*') {
+                Some(start) => {
+                    self.dropped_bytes += start;
+                    src.advance(start);
+                }
+                None => {
+                    self.dropped_bytes += src.len();
+                    src.clear();
+                    return Ok(None);
+                }
+            }
+            let frame_end = src
+                .iter()
+                .skip(1)
+                .position(|b| *b == b'\r' || *b == b'*')
+                .map(|n| n + 1);
+            let frame_end = match frame_end {
+                Some(frame_end) => frame_end,
+                None => {
+                    if src.len() > MAX_FRAME_LENGTH {
+                        self.dropped_bytes += src.len();
+                        src.clear();
+                    }
+                    return Ok(None);
+                }
+            };
+            let terminated = src[frame_end] == b'\r';
+            let frame = src.split_to(frame_end);
+            if terminated {
+                src.advance(1);
+            }
+            match str::from_utf8(frame.as_ref()) {
+                Ok(frame) => return Ok(Some(LssResponse::new(format!("{}\r", frame)))),
+                Err(_) => self.dropped_bytes += frame.len(),
+            }
+        }
+    }
+}
+
+impl Encoder<LssCommand> for LssCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, data: LssCommand, buf: &mut BytesMut) -> Result<(), io::Error> {
+        let msg = data.as_bytes();
+        buf.reserve(msg.len());
+        buf.put(msg);
+        Ok(())
+    }
+}
+
+/// Transport carrying frames between the driver and the servos
+///
+/// Implement this to talk to servos over a custom link or to mock the bus in tests
+/// and pass it to [LSSDriver::with_driver](crate::LSSDriver::with_driver).
+/// Implementations are expected to follow these rules:
+///
+/// * `send` writes the whole frame or fails with `SendingError`
+/// * `receive` returns one frame per call and fails with `TimeoutError` if none arrives in time
+/// * Both fail with `Disconnected` once the link is gone so that auto reconnect can kick in
+///
+/// # Example
+///
+/// ```
+/// use lss_driver::{async_trait, FramedDriver, LSSDriver, LssCommand, LssDriverError, LssResponse};
+///
+/// /// Servo 5 that always reports 11.2V
+/// struct FakeBus {
+///     reply: Option<String>,
+/// }
+///
+/// #[async_trait]
+/// impl FramedDriver for FakeBus {
+///     async fn send(&mut self, command: LssCommand) -> Result<(), LssDriverError> {
+///         if command.as_str() == "#5QV\r" {
+///             self.reply = Some("*5QV11200\r".to_owned());
+///         }
+///         Ok(())
+///     }
+///
+///     async fn receive(&mut self) -> Result<LssResponse, LssDriverError> {
+///         self.reply
+///             .take()
+///             .map(LssResponse::new)
+///             .ok_or(LssDriverError::TimeoutError)
+///     }
+/// }
+///
+/// let mut driver = LSSDriver::with_driver(Box::new(FakeBus { reply: None }));
+/// let voltage = futures::executor::block_on(driver.query_voltage(5)).unwrap();
+/// assert_eq!(voltage, 11.2);
+/// ```
+#[async_trait]
+pub trait FramedDriver {
+    /// Write command frame
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()>;
+
+    /// Wait for next received frame
+    async fn receive(&mut self) -> DriverResult<LssResponse>;
+
+    /// Drop received bytes that weren't read yet
+    ///
+    /// Called before each command so that replies left over from earlier commands
+    /// (e.g. several servos answering a broadcast query) aren't read as the reply to the new one.
+    /// Transports without a read buffer can keep the default implementation
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        Ok(())
+    }
+
+    /// Number of received bytes dropped as noise since the previous call
+    ///
+    /// Lets [detect_id_conflicts](crate::LSSDriver::detect_id_conflicts) notice replies garbled beyond recognition.
+    /// Transports that never drop bytes can keep the default implementation
+    async fn take_dropped_bytes(&mut self) -> usize {
+        0
+    }
+
+    /// Reopen underlying transport
+    ///
+    /// Transports that can't be reopened can keep the default implementation
+    async fn reconnect(&mut self) -> DriverResult<()> {
+        Ok(())
+    }
+
+    /// Write any buffered bytes to the transport
+    ///
+    /// Transports without buffering can keep the default implementation
+    async fn flush(&mut self) -> DriverResult<()> {
+        Ok(())
+    }
+
+    /// Flush and shut down the transport
+    ///
+    /// Transports that don't need shutting down can keep the default implementation
+    async fn close(&mut self) -> DriverResult<()> {
+        self.flush().await
+    }
+
+    /// Change how long to wait for a reply before returning `TimeoutError`
+    ///
+    /// Transports without a timeout can keep the default implementation
+    fn set_receive_timeout(&mut self, _timeout: Duration) {}
+
+    /// Change baud rate of underlying transport
+    ///
+    /// Transports without a baud rate can keep the default implementation
+    async fn set_baud_rate(&mut self, _baud_rate: u32) -> DriverResult<()> {
+        Ok(())
+    }
+}
+
+const TIMEOUT: u64 = 10;
+pub(crate) const DEFAULT_RECEIVE_TIMEOUT: Duration = Duration::from_millis(TIMEOUT);
+
+/// Settings used to open a serial port
+///
+/// Defaults match the servo firmware at 115200 baud with DTR and RTS left at their platform defaults.
+#[cfg(feature = "runtime-tokio")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SerialOptions {
+    pub baud_rate: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    pub flow_control: FlowControl,
+    /// DTR level set when the port is opened. `None` keeps the platform default
+    ///
+    /// Linux asserts DTR briefly while opening regardless of this setting
+    pub dtr_on_open: Option<bool>,
+    /// RTS level set when the port is opened. `None` keeps the platform default
+    ///
+    /// Ignored when `rs485` is set since RTS is then used for direction control
+    pub rts_on_open: Option<bool>,
+    /// Direction control for RS-485 transceivers
+    pub rs485: Option<Rs485Config>,
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl SerialOptions {
+    pub fn new(baud_rate: u32) -> SerialOptions {
+        SerialOptions {
+            baud_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Bits on the wire for each byte including start, parity and stop bits
+    fn bits_per_byte(&self) -> u64 {
+        let data_bits = match self.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        };
+        let parity_bits = match self.parity {
+            Parity::None => 0,
+            Parity::Odd | Parity::Even => 1,
+        };
+        let stop_bits = match self.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        1 + data_bits + parity_bits + stop_bits
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl Default for SerialOptions {
+    fn default() -> Self {
+        SerialOptions {
+            baud_rate: 115200,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            dtr_on_open: None,
+            rts_on_open: None,
+            rs485: None,
+        }
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+pub struct FramedSerialDriver {
+    port: Option<String>,
+    options: SerialOptions,
+    receive_timeout: Duration,
+    #[cfg(target_family = "windows")]
+    framed_port: Mutex<tokio_util::codec::Framed<tokio_serial::SerialStream, LssCodec>>,
+    #[cfg(not(target_family = "windows"))]
+    framed_port: tokio_util::codec::Framed<tokio_serial::SerialStream, LssCodec>,
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl FramedSerialDriver {
+    pub fn new(port: &str) -> DriverResult<FramedSerialDriver> {
+        FramedSerialDriver::with_baud_rate(port, 115200)
+    }
+
+    #[cfg(all(unix, feature = "testutil"))]
+    pub(crate) fn from_serial_stream(
+        serial_port: tokio_serial::SerialStream,
+    ) -> FramedSerialDriver {
+        FramedSerialDriver {
+            port: None,
+            options: SerialOptions::default(),
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            framed_port: LssCodec::new().framed(serial_port),
+        }
+    }
+
+    pub fn with_baud_rate(port: &str, baud_rate: u32) -> DriverResult<FramedSerialDriver> {
+        FramedSerialDriver::with_options(port, SerialOptions::new(baud_rate))
+    }
+
+    pub fn with_options(port: &str, options: SerialOptions) -> DriverResult<FramedSerialDriver> {
+        let serial_port = open_serial_port(port, &options)?;
+        Ok(FramedSerialDriver {
+            port: Some(port.to_owned()),
+            options,
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            #[cfg(target_family = "windows")]
+            framed_port: Mutex::new(LssCodec::new().framed(serial_port)),
+            #[cfg(not(target_family = "windows"))]
+            framed_port: LssCodec::new().framed(serial_port),
+        })
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+fn open_serial_port(
+    port: &str,
+    options: &SerialOptions,
+) -> DriverResult<tokio_serial::SerialStream> {
+    // servos never answer at other rates
+    BaudRate::try_from(options.baud_rate)?;
+    let mut builder = tokio_serial::new(port, options.baud_rate)
+        .data_bits(options.data_bits)
+        .parity(options.parity)
+        .stop_bits(options.stop_bits)
+        .flow_control(options.flow_control)
+        .timeout(std::time::Duration::from_millis(TIMEOUT));
+    if let Some(dtr) = options.dtr_on_open {
+        builder = builder.dtr_on_open(dtr);
+    }
+    let mut serial_port = builder
+        .open_native_async()
+        .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+    match (&options.rs485, options.rts_on_open) {
+        (Some(rs485), _) => set_transmitting(&mut serial_port, rs485, false)?,
+        (None, Some(rts)) => serial_port
+            .write_request_to_send(rts)
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?,
+        (None, None) => (),
+    }
+    Ok(serial_port)
+}
+
+/// Switch RS-485 transceiver between transmitting and receiving
+#[cfg(feature = "runtime-tokio")]
+fn set_transmitting(
+    serial_port: &mut tokio_serial::SerialStream,
+    rs485: &Rs485Config,
+    transmitting: bool,
+) -> DriverResult<()> {
+    serial_port
+        .write_request_to_send(transmitting == rs485.rts_active_high)
+        .map_err(|_| LssDriverError::SendingError)
+}
+
+/// Enabled RS-485 transmitter of a port
+///
+/// Switches back to receiving when dropped so that a cancelled send doesn't keep the bus blocked.
+#[cfg(feature = "runtime-tokio")]
+struct Transmitter<'a> {
+    port: &'a mut tokio_util::codec::Framed<tokio_serial::SerialStream, LssCodec>,
+    rs485: Rs485Config,
+    armed: bool,
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl Transmitter<'_> {
+    /// Switch back to receiving reporting failure
+    fn release(mut self) -> DriverResult<()> {
+        self.armed = false;
+        set_transmitting(self.port.get_mut(), &self.rs485, false)
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl Drop for Transmitter<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = set_transmitting(self.port.get_mut(), &self.rs485, false);
+        }
+    }
+}
+
+/// Time it takes to shift `bytes` out of the port
+#[cfg(feature = "runtime-tokio")]
+fn transmission_time(bytes: usize, options: &SerialOptions) -> Duration {
+    let bits = bytes as u64 * options.bits_per_byte();
+    Duration::from_micros(bits * 1_000_000 / options.baud_rate.max(1) as u64)
+}
+
+#[cfg(feature = "runtime-tokio")]
+#[async_trait]
+impl FramedDriver for FramedSerialDriver {
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        port.read_buffer_mut().clear();
+        port.get_ref()
+            .clear(ClearBuffer::Input)
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        let rs485 = match self.options.rs485 {
+            Some(rs485) => rs485,
+            None => {
+                return port
+                    .send(command)
+                    .await
+                    .map_err(|_| LssDriverError::SendingError)
+            }
+        };
+        set_transmitting(port.get_mut(), &rs485, true)?;
+        let transmitter = Transmitter {
+            port: &mut *port,
+            rs485,
+            armed: true,
+        };
+        time::sleep(rs485.delay_before_send).await;
+        let length = command.as_bytes().len();
+        let sent = transmitter.port.send(command).await;
+        // bytes are still in the UART after the write returns.
+        // Releasing the bus early would cut off the end of the command
+        time::sleep(transmission_time(length, &self.options) + rs485.delay_after_send).await;
+        transmitter.release()?;
+        sent.map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        let response = timeout(self.receive_timeout, port.next())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+            .ok_or(LssDriverError::Disconnected)?
+            .map_err(|_| LssDriverError::Disconnected)?;
+        Ok(response)
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        port.codec_mut().take_dropped_bytes()
+    }
+
+    async fn flush(&mut self) -> DriverResult<()> {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        SinkExt::<LssCommand>::flush(&mut *port)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn close(&mut self) -> DriverResult<()> {
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        SinkExt::<LssCommand>::close(&mut *port)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+
+    async fn reconnect(&mut self) -> DriverResult<()> {
+        let port = self
+            .port
+            .as_deref()
+            .ok_or(LssDriverError::FailedOpeningSerialPort)?;
+        let serial_port = open_serial_port(port, &self.options)?;
+        #[cfg(not(target_family = "windows"))]
+        {
+            self.framed_port = LssCodec::new().framed(serial_port);
+        }
+        #[cfg(target_family = "windows")]
+        {
+            *self.framed_port.lock().await = LssCodec::new().framed(serial_port);
+        }
+        Ok(())
+    }
+
+    async fn set_baud_rate(&mut self, baud_rate: u32) -> DriverResult<()> {
+        BaudRate::try_from(baud_rate)?;
+        #[cfg(not(target_family = "windows"))]
+        let port = &mut self.framed_port;
+        #[cfg(target_family = "windows")]
+        let mut port = self.framed_port.lock().await;
+        port.get_mut()
+            .set_baud_rate(baud_rate)
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        self.options.baud_rate = baud_rate;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn framing_returns_none() {
+        let mut payload = BytesMut::from("*5QV11200");
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap();
+        assert_eq!(res, None);
+    }
+
+    #[test]
+    fn framing_returns_twice() {
+        let mut payload = BytesMut::from("*1QV1\r*2QV2\r");
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        let (id, val) = res.separate("QV").unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(val, 1);
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        let (id, val) = res.separate("QV").unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(val, 2);
+        let res = codec.decode(&mut payload).unwrap();
+        assert_eq!(res, None);
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[test]
+    fn transmission_time_covers_start_and_stop_bits() {
+        let options = SerialOptions::new(115200);
+        assert_eq!(transmission_time(5, &options), Duration::from_micros(434));
+        let options = SerialOptions {
+            parity: Parity::Even,
+            stop_bits: StopBits::Two,
+            ..SerialOptions::new(9600)
+        };
+        assert_eq!(
+            transmission_time(12, &options),
+            Duration::from_micros(15000)
+        );
+    }
+
+    #[test]
+    fn framing_joins_split_frames() {
+        let mut payload = BytesMut::from("*5QV11");
+        let mut codec = LssCodec::new();
+        assert_eq!(codec.decode(&mut payload).unwrap(), None);
+        payload.extend_from_slice(b"200\r");
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (5, 11200));
+    }
+
+    #[test]
+    fn framing_skips_leading_noise() {
+        let mut payload = BytesMut::from(&b"\0\xff#?\r*5QV11200\r"[..]);
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (5, 11200));
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn framing_discards_noise_without_frame() {
+        let mut payload = BytesMut::from("garbage\r");
+        let mut codec = LssCodec::new();
+        assert_eq!(codec.decode(&mut payload).unwrap(), None);
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn framing_terminates_frame_missing_carriage_return() {
+        let mut payload = BytesMut::from("*1QV1*2QV2\r");
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (1, 1));
+        assert_eq!(res.get_val("QV").unwrap(), 1);
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (2, 2));
+    }
+
+    #[test]
+    fn framing_drops_invalid_utf8_frame() {
+        let mut payload = BytesMut::from(&b"*1QV\xff\r*2QV2\r"[..]);
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (2, 2));
+    }
+
+    #[test]
+    fn framing_counts_dropped_bytes() {
+        let mut payload = BytesMut::from(&b"\0\xff*1QV\xff\r*2QV2\r#?"[..]);
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        assert_eq!(res.separate("QV").unwrap(), (2, 2));
+        assert_eq!(codec.decode(&mut payload).unwrap(), None);
+        assert_eq!(codec.take_dropped_bytes(), 2 + 5 + 2);
+        assert_eq!(codec.take_dropped_bytes(), 0);
+    }
+
+    #[test]
+    fn framing_drops_oversized_garbage() {
+        let mut payload = BytesMut::from("*");
+        payload.extend_from_slice(&[b'1'; MAX_FRAME_LENGTH]);
+        let mut codec = LssCodec::new();
+        assert_eq!(codec.decode(&mut payload).unwrap(), None);
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn query_voltage_gets_extracted_from_frame() {
+        let mut payload = BytesMut::from("*5QV11200\r");
+        let mut codec = LssCodec::new();
+        let res = codec.decode(&mut payload).unwrap().unwrap();
+        let (id, val) = res.separate("QV").unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(val, 11200);
+    }
+
+    #[test]
+    fn framing_encodes_single_command() {
+        let mut payload = BytesMut::default();
+        let mut codec = LssCodec::new();
+        let command = LssCommand::simple(5, "QV");
+        codec.encode(command, &mut payload).unwrap();
+        assert_eq!(&payload[..], b"#5QV\r");
+    }
+
+    #[test]
+    fn framing_encodes_multiple_commands() {
+        let mut payload = BytesMut::default();
+        let mut codec = LssCodec::new();
+        let command_1 = LssCommand::simple(5, "QV");
+        let command_2 = LssCommand::simple(5, "QT");
+        codec.encode(command_1, &mut payload).unwrap();
+        codec.encode(command_2, &mut payload).unwrap();
+        assert_eq!(&payload[..], b"#5QV\r#5QT\r");
+    }
+
+    #[test]
+    fn simple_command_serializes() {
+        let command = LssCommand::simple(1, "QV");
+        assert_eq!(command.as_bytes(), b"#1QV\r")
+    }
+
+    #[test]
+    fn param_command_serializes() {
+        let command = LssCommand::with_param(1, "D", 10);
+        assert_eq!(command.as_bytes(), b"#1D10\r")
+    }
+
+    #[test]
+    fn response_splits() {
+        let res = LssResponse::new("*5QF42\r".to_owned());
+        let (id, val) = res.separate("QF").unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(val, 42);
+    }
+
+    #[test]
+    fn response_splits_string() {
+        let res = LssResponse::new("*5QFHEH\r".to_owned());
+        let (id, val) = res.separate_string("QF").unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(val, "HEH");
+    }
+
+    #[test]
+    fn response_fail_missing_val() {
+        let res = LssResponse::new("*5QF\r".to_owned());
+        let err = res.separate("QF");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_fail_missing_id() {
+        let res = LssResponse::new("*QF1\r".to_owned());
+        let err = res.separate("QF");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_fail_wrong_key_split() {
+        let res = LssResponse::new("*1QF2\r".to_owned());
+        let err = res.separate("ZA");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_fail_separator_not_after_id() {
+        let res = LssResponse::new("*5QTS12QT3\r".to_owned());
+        let err = res.separate("QT");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_fail_stale_reply() {
+        let res = LssResponse::new("*5QDT100\r".to_owned());
+        let err = res.separate("QD");
+        assert!(err.is_err());
+        let res = LssResponse::new("*5QD100\r".to_owned());
+        let err = res.separate("QT");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_mismatched_command_is_unexpected() {
+        let res = LssResponse::new("*5QT441\r".to_owned());
+        let err = res.separate("QV");
+        assert!(matches!(
+            err,
+            Err(LssDriverError::UnexpectedResponse { sent: None, .. })
+        ));
+    }
+
+    #[test]
+    fn response_id() {
+        assert_eq!(LssResponse::new("*5QV11200\r".to_owned()).id(), Some(5));
+        assert_eq!(LssResponse::new("*QID5\r".to_owned()).id(), None);
+    }
+
+    #[test]
+    fn response_fail_missing_prefix() {
+        let res = LssResponse::new("5QT100\r".to_owned());
+        let err = res.separate("QT");
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn response_string_keeps_separator_in_value() {
+        let res = LssResponse::new("*5QNQN12\r".to_owned());
+        let (id, val) = res.separate_string("QN").unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(val, "QN12");
+    }
+
+    #[test]
+    fn response_splits_in_range() {
+        let res = LssResponse::new("*5QFPC255\r".to_owned());
+        let (id, val) = res.separate_as::<u8>("QFPC").unwrap();
+        assert_eq!(id, 5);
+        assert_eq!(val, 255);
+    }
+
+    #[test]
+    fn response_fail_out_of_range() {
+        let res = LssResponse::new("*5QFPC256\r".to_owned());
+        assert!(res.separate_as::<u8>("QFPC").is_err());
+        let res = LssResponse::new("*5QFPC-1\r".to_owned());
+        assert!(res.separate_as::<u8>("QFPC").is_err());
+        let res = LssResponse::new("*QID300\r".to_owned());
+        assert!(res.get_val_as::<u8>("QID").is_err());
+    }
+
+    #[test]
+    fn response_val_only() {
+        let res = LssResponse::new("*QID5\r".to_owned());
+        let val = res.get_val("QID").unwrap();
+        assert_eq!(val, 5);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::driver::message_types::*;
+use crate::LSSDriver;
+use std::time::Duration;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+macro_rules! servo_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Same as [", stringify!($name), "](crate::LSSDriver::", stringify!($name), ") for this servo")]
+            pub async fn $name(&mut self, $($arg: $ty),*) -> $ret {
+                self.driver.$name(self.id, $($arg),*).await
+            }
+        )*
+    };
+}
+
+/// Single servo on the bus
+///
+/// Created with [LSSDriver::servo](crate::LSSDriver::servo).
+/// Has the same methods as [LSSDriver](crate::LSSDriver) without the `id` parameter.
+pub struct ServoHandle<'a> {
+    driver: &'a mut LSSDriver,
+    id: u8,
+}
+
+impl<'a> ServoHandle<'a> {
+    pub(crate) fn new(driver: &'a mut LSSDriver, id: u8) -> ServoHandle<'a> {
+        ServoHandle { driver, id }
+    }
+
+    /// ID of the servo
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Same as [send_raw](crate::LSSDriver::send_raw) for this servo
+    pub async fn send_raw(&mut self, cmd: &str, value: Option<i32>) -> DriverResult<()> {
+        self.driver.send_raw(self.id, cmd, value).await
+    }
+
+    /// Same as [query_raw](crate::LSSDriver::query_raw) for this servo
+    pub async fn query_raw(&mut self, cmd: &str) -> DriverResult<String> {
+        self.driver.query_raw(self.id, cmd).await
+    }
+
+    /// Same as [last_known](crate::LSSDriver::last_known) for this servo
+    pub fn last_known(&self) -> LastKnown {
+        self.driver.last_known(self.id)
+    }
+
+    servo_methods! {
+        reset() -> DriverResult<()>;
+        reset_and_wait(timeout: Duration) -> DriverResult<()>;
+        restore_factory_defaults(timeout: Duration) -> DriverResult<()>;
+        query_id() -> DriverResult<u8>;
+        set_id(new_id: u8) -> DriverResult<()>;
+        set_baud_rate(baud_rate: BaudRate) -> DriverResult<()>;
+        change_baud_rate(baud_rate: BaudRate) -> DriverResult<()>;
+        query_baud_rate() -> DriverResult<BaudRate>;
+        set_color(color: LedColor) -> DriverResult<()>;
+        configure_color(color: LedColor) -> DriverResult<()>;
+        query_color() -> DriverResult<LedColor>;
+        identify(duration: Duration) -> DriverResult<()>;
+        move_to_position(position: f32) -> DriverResult<()>;
+        move_to_position_in_mode(position: f32, expected_mode: ExpectedMode) -> DriverResult<()>;
+        move_to_position_with_modifier(position: f32, modifier: CommandModifier) -> DriverResult<()>;
+        move_to_position_with_speed(position: f32, speed: f32) -> DriverResult<()>;
+        move_to_position_in(position: f32, duration: Duration) -> DriverResult<()>;
+        move_to_position_with_current_hold(position: f32, current: u32) -> DriverResult<()>;
+        move_to_position_with_current_limp(position: f32, current: u32) -> DriverResult<()>;
+        move_to_position_with_modifiers(position: f32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        execute(command: MoveCommand) -> DriverResult<()>;
+        configure_first_position(position: Option<f32>) -> DriverResult<()>;
+        query_first_position() -> DriverResult<Option<f32>>;
+        move_relative(delta: f32) -> DriverResult<()>;
+        move_relative_with_modifiers(delta: f32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        set_target_position(position: f32) -> DriverResult<()>;
+        query_position() -> DriverResult<f32>;
+        query_filtered_position() -> DriverResult<f32>;
+        query_target_position() -> DriverResult<f32>;
+        query_position_error() -> DriverResult<f32>;
+        set_rotation_speed(speed: f32) -> DriverResult<()>;
+        query_rotation_speed() -> DriverResult<f32>;
+        set_rotation_speed_dps(speed: f32) -> DriverResult<()>;
+        set_rotation_speed_rpm(speed: f32) -> DriverResult<()>;
+        query_rotation_speed_rpm() -> DriverResult<f32>;
+        query_speed_dps() -> DriverResult<f32>;
+        query_speed_rpm() -> DriverResult<f32>;
+        query_status() -> DriverResult<MotorStatus>;
+        query_status_flags() -> DriverResult<StatusFlags>;
+        is_moving() -> DriverResult<bool>;
+        wait_until_stopped(timeout: Duration) -> DriverResult<()>;
+        query_safety_status() -> DriverResult<SafeModeStatus>;
+        set_gyre_direction(direction: Direction) -> DriverResult<()>;
+        configure_gyre_direction(direction: Direction) -> DriverResult<()>;
+        query_gyre_direction() -> DriverResult<Direction>;
+        set_motion_profile(motion_profile: bool) -> DriverResult<()>;
+        configure_motion_profile(motion_profile: bool) -> DriverResult<()>;
+        query_motion_profile() -> DriverResult<bool>;
+        set_filter_position_count(filter_position_count: u8) -> DriverResult<()>;
+        configure_filter_position_count(filter_position_count: u8) -> DriverResult<()>;
+        query_filter_position_count() -> DriverResult<u8>;
+        set_angular_stiffness(angular_stiffness: i32) -> DriverResult<()>;
+        configure_angular_stiffness(angular_stiffness: i32) -> DriverResult<()>;
+        query_angular_stiffness() -> DriverResult<i32>;
+        query_angular_stiffness_in_scope(scope: QueryScope) -> DriverResult<i32>;
+        set_angular_holding_stiffness(angular_holding: i32) -> DriverResult<()>;
+        configure_angular_holding_stiffness(angular_holding: i32) -> DriverResult<()>;
+        query_angular_holding_stiffness() -> DriverResult<i32>;
+        query_angular_holding_stiffness_in_scope(scope: QueryScope) -> DriverResult<i32>;
+        set_angular_acceleration(angular_acceleration: i32) -> DriverResult<()>;
+        configure_angular_acceleration(angular_acceleration: i32) -> DriverResult<()>;
+        query_angular_acceleration() -> DriverResult<i32>;
+        set_angular_deceleration(angular_deceleration: i32) -> DriverResult<()>;
+        configure_angular_deceleration(angular_deceleration: i32) -> DriverResult<()>;
+        query_angular_deceleration() -> DriverResult<i32>;
+        set_maximum_motor_duty(maximum_motor_duty: i32) -> DriverResult<()>;
+        configure_maximum_motor_duty(maximum_motor_duty: i32) -> DriverResult<()>;
+        query_maximum_motor_duty() -> DriverResult<i32>;
+        set_maximum_speed(maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed(maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed() -> DriverResult<f32>;
+        query_maximum_speed_in_scope(scope: QueryScope) -> DriverResult<f32>;
+        set_maximum_speed_rpm(maximum_speed: f32) -> DriverResult<()>;
+        configure_maximum_speed_rpm(maximum_speed: f32) -> DriverResult<()>;
+        query_maximum_speed_rpm() -> DriverResult<f32>;
+        limp() -> DriverResult<()>;
+        halt_hold() -> DriverResult<()>;
+        query_voltage() -> DriverResult<f32>;
+        query_temperature() -> DriverResult<f32>;
+        query_current() -> DriverResult<f32>;
+        query_telemetry() -> DriverResult<ServoTelemetry>;
+        query_model() -> DriverResult<Model>;
+        query_firmware_version() -> DriverResult<FirmwareVersion>;
+        query_serial_number() -> DriverResult<String>;
+        set_led_blinking(blinking_mode: Vec<LedBlinking>) -> DriverResult<()>;
+        query_led_blinking() -> DriverResult<Vec<LedBlinking>>;
+        query_origin_offset() -> DriverResult<f32>;
+        query_origin_offset_in_scope(scope: QueryScope) -> DriverResult<f32>;
+        query_angular_range() -> DriverResult<f32>;
+        query_angular_range_in_scope(scope: QueryScope) -> DriverResult<f32>;
+        set_angular_range(range: f32) -> DriverResult<()>;
+        set_session_angular_range(range: f32) -> DriverResult<()>;
+        configure_angular_range(range: f32) -> DriverResult<()>;
+        query_pwm_position() -> DriverResult<i32>;
+        set_origin_offset(origin_offset: f32) -> DriverResult<()>;
+        set_session_origin_offset(origin_offset: f32) -> DriverResult<()>;
+        configure_origin_offset(origin_offset: f32) -> DriverResult<()>;
+        move_to_pwm_position(position: i32) -> DriverResult<()>;
+        move_to_pulse(position: i32) -> DriverResult<()>;
+        query_pulse_position() -> DriverResult<i32>;
+        move_to_pwm_position_with_modifier(position: i32, modifier: CommandModifier) -> DriverResult<()>;
+        move_to_pwm_position_with_modifiers(position: i32, modifiers: &[CommandModifier]) -> DriverResult<()>;
+        auto_find_limits(probe_speed: f32, current_threshold: f32, timeout: Duration) -> DriverResult<(f32, f32)>;
+    }
+}
@@ -0,0 +1,106 @@
+use crate::LSSDriver;
+use std::sync::Arc;
+#[cfg(feature = "runtime-tokio")]
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+#[cfg(feature = "runtime-tokio")]
+use tokio::task::JoinHandle;
+
+/// Driver that can be shared between multiple tasks
+///
+/// Cloning is cheap and all clones talk to the same bus.
+/// Each command is issued while holding a lock so queries from different tasks don't interleave.
+#[derive(Clone)]
+pub struct SharedLSSDriver {
+    driver: Arc<Mutex<LSSDriver>>,
+    #[cfg(feature = "runtime-tokio")]
+    _health_check: Option<Arc<HealthCheck>>,
+}
+
+#[cfg(feature = "runtime-tokio")]
+struct HealthCheck {
+    task: JoinHandle<()>,
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl Drop for HealthCheck {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl SharedLSSDriver {
+    /// Create new shared driver
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, SharedLSSDriver};
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::new("COM1").unwrap();
+    ///     let shared = SharedLSSDriver::new(driver);
+    ///     shared.lock().await.limp(5).await.unwrap();
+    /// }
+    /// ```
+    pub fn new(driver: LSSDriver) -> SharedLSSDriver {
+        SharedLSSDriver {
+            driver: Arc::new(Mutex::new(driver)),
+            #[cfg(feature = "runtime-tokio")]
+            _health_check: None,
+        }
+    }
+
+    /// Create new shared driver with a background health check
+    ///
+    /// Servo with `id` is queried every `interval`.
+    /// If it fails to respond the driver is reconnected so that the bus is restored
+    /// before the next command is issued.
+    /// Health check stops when all clones of this driver are dropped.
+    ///
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - Driver to share
+    /// * `id` - ID of servo used to check the bus
+    /// * `interval` - Time between health checks
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::{LSSDriver, SharedLSSDriver};
+    /// use std::time::Duration;
+    /// async fn async_main(){
+    ///     let driver = LSSDriver::new("COM1").unwrap();
+    ///     let shared = SharedLSSDriver::with_health_check(driver, 5, Duration::from_secs(1));
+    /// }
+    /// ```
+    #[cfg(feature = "runtime-tokio")]
+    pub fn with_health_check(driver: LSSDriver, id: u8, interval: Duration) -> SharedLSSDriver {
+        let driver = Arc::new(Mutex::new(driver));
+        let task_driver = driver.clone();
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let mut driver = task_driver.lock().await;
+                if driver.query_status(id).await.is_err() {
+                    // failed reconnect is retried on next tick
+                    let _ = driver.reconnect().await;
+                }
+            }
+        });
+        SharedLSSDriver {
+            driver,
+            _health_check: Some(Arc::new(HealthCheck { task })),
+        }
+    }
+
+    /// Lock the driver for exclusive use
+    ///
+    /// Hold the guard for the duration of a command and its response
+    pub async fn lock(&self) -> MutexGuard<'_, LSSDriver> {
+        self.driver.lock().await
+    }
+}
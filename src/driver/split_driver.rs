@@ -0,0 +1,116 @@
+//! Independent sending and receiving halves of a driver
+//!
+//! Transport is moved into a background task which writes queued commands
+//! and forwards every received frame without pairing it with a command.
+//! Pairing replies with commands is left to the user.
+
+use crate::driver::message_types::LssDriverError;
+use crate::driver::serial_driver::{FramedDriver, LssCommand, LssResponse};
+use futures::future::{self, Either};
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::{mpsc, oneshot};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+type Transport = Box<dyn FramedDriver + Send + Sync>;
+type QueuedCommand = (LssCommand, oneshot::Sender<DriverResult<()>>);
+
+/// Number of commands that can be queued before senders have to wait
+const COMMAND_QUEUE_CAPACITY: usize = 32;
+/// Number of received frames buffered before the transport stops being read
+const RESPONSE_QUEUE_CAPACITY: usize = 64;
+
+/// Sending half created with [LSSDriver::split](crate::LSSDriver::split)
+///
+/// Cheap to clone. Background task keeps running while either half is alive.
+#[derive(Clone)]
+pub struct CommandSender {
+    commands: mpsc::Sender<QueuedCommand>,
+}
+
+impl CommandSender {
+    /// Write command to the transport
+    ///
+    /// Returns once the command was written. Replies are delivered to the [ResponseReceiver].
+    pub async fn send(&self, command: LssCommand) -> DriverResult<()> {
+        let (result, written) = oneshot::channel();
+        self.commands
+            .send((command, result))
+            .await
+            .map_err(|_| LssDriverError::Disconnected)?;
+        written.await.map_err(|_| LssDriverError::Disconnected)?
+    }
+}
+
+/// Receiving half created with [LSSDriver::split](crate::LSSDriver::split)
+///
+/// Yields every frame received from the bus in order.
+/// Ends after the transport disconnects.
+pub struct ResponseReceiver {
+    responses: mpsc::Receiver<DriverResult<LssResponse>>,
+}
+
+impl ResponseReceiver {
+    /// Wait for next frame
+    ///
+    /// Returns `None` once the transport disconnected
+    pub async fn recv(&mut self) -> Option<DriverResult<LssResponse>> {
+        self.responses.recv().await
+    }
+}
+
+impl Stream for ResponseReceiver {
+    type Item = DriverResult<LssResponse>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.responses.poll_recv(cx)
+    }
+}
+
+pub(crate) fn split(mut transport: Transport) -> (CommandSender, ResponseReceiver) {
+    let (commands, mut command_queue) = mpsc::channel::<QueuedCommand>(COMMAND_QUEUE_CAPACITY);
+    let (response_queue, responses) = mpsc::channel(RESPONSE_QUEUE_CAPACITY);
+    tokio::spawn(async move {
+        let mut senders_alive = true;
+        loop {
+            let next = {
+                let received = transport.receive();
+                futures::pin_mut!(received);
+                if senders_alive {
+                    let command = command_queue.recv();
+                    futures::pin_mut!(command);
+                    match future::select(command, received).await {
+                        Either::Left((command, _)) => Either::Left(command),
+                        Either::Right((response, _)) => Either::Right(response),
+                    }
+                } else {
+                    Either::Right(received.await)
+                }
+            };
+            match next {
+                Either::Left(Some((command, result))) => {
+                    // sender may have stopped waiting
+                    let _ = result.send(transport.send(command).await);
+                }
+                Either::Left(None) => senders_alive = false,
+                // nothing received within receive timeout
+                Either::Right(Err(LssDriverError::TimeoutError)) => {
+                    if response_queue.is_closed() && !senders_alive {
+                        return;
+                    }
+                }
+                Either::Right(response) => {
+                    let disconnected = matches!(response, Err(LssDriverError::Disconnected));
+                    if response_queue.send(response).await.is_err() && !senders_alive {
+                        return;
+                    }
+                    if disconnected {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    (CommandSender { commands }, ResponseReceiver { responses })
+}
@@ -0,0 +1,94 @@
+use crate::driver::message_types::LssDriverError;
+use crate::driver::serial_driver::{
+    FramedDriver, LssCodec, LssCommand, LssResponse, DEFAULT_RECEIVE_TIMEOUT,
+};
+use crate::driver::time::timeout;
+use async_trait::async_trait;
+use futures::{FutureExt, SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_util::codec::{Decoder, Framed};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Opens the byte stream behind a [StreamFramedDriver]
+///
+/// Called once when connecting and again on every reconnect.
+#[async_trait]
+pub trait Connector: Send + Sync {
+    type Stream: AsyncRead + AsyncWrite + Unpin + Send + Sync;
+
+    async fn connect(&self) -> DriverResult<Self::Stream>;
+}
+
+/// Transport for servos behind any byte stream forwarding raw bytes in both directions
+pub struct StreamFramedDriver<C: Connector> {
+    connector: C,
+    receive_timeout: Duration,
+    framed_stream: Framed<C::Stream, LssCodec>,
+}
+
+impl<C: Connector> StreamFramedDriver<C> {
+    pub async fn open(connector: C) -> DriverResult<StreamFramedDriver<C>> {
+        let stream = connector.connect().await?;
+        Ok(StreamFramedDriver {
+            connector,
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            framed_stream: LssCodec::new().framed(stream),
+        })
+    }
+}
+
+#[async_trait]
+impl<C: Connector> FramedDriver for StreamFramedDriver<C> {
+    /// Drops buffered bytes and whatever the socket has already received
+    ///
+    /// Only reads what is available without waiting,
+    /// bytes still in flight from the bridge arrive after this returns.
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        while let Some(Some(Ok(_))) = self.framed_stream.next().now_or_never() {}
+        self.framed_stream.read_buffer_mut().clear();
+        Ok(())
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        self.framed_stream.codec_mut().take_dropped_bytes()
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        self.framed_stream
+            .send(command)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        timeout(self.receive_timeout, self.framed_stream.next())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+            .ok_or(LssDriverError::Disconnected)?
+            .map_err(|_| LssDriverError::Disconnected)
+    }
+
+    async fn reconnect(&mut self) -> DriverResult<()> {
+        let stream = self.connector.connect().await?;
+        self.framed_stream = LssCodec::new().framed(stream);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> DriverResult<()> {
+        SinkExt::<LssCommand>::flush(&mut self.framed_stream)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn close(&mut self) -> DriverResult<()> {
+        SinkExt::<LssCommand>::close(&mut self.framed_stream)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+}
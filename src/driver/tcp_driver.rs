@@ -0,0 +1,43 @@
+use crate::driver::message_types::LssDriverError;
+use crate::driver::stream_driver::{Connector, StreamFramedDriver};
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use tokio::net::{lookup_host, TcpStream, ToSocketAddrs};
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Transport for servos attached to a serial over TCP bridge such as ser2net or ESP-Link
+///
+/// The bridge is expected to forward raw bytes in both directions.
+pub type TcpFramedDriver = StreamFramedDriver<TcpConnector>;
+
+/// Connects to the first reachable address the bridge resolved to
+pub struct TcpConnector {
+    addresses: Vec<SocketAddr>,
+}
+
+impl TcpFramedDriver {
+    pub async fn connect<A: ToSocketAddrs>(address: A) -> DriverResult<TcpFramedDriver> {
+        let addresses = lookup_host(address)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?
+            .collect();
+        StreamFramedDriver::open(TcpConnector { addresses }).await
+    }
+}
+
+#[async_trait]
+impl Connector for TcpConnector {
+    type Stream = TcpStream;
+
+    async fn connect(&self) -> DriverResult<TcpStream> {
+        let stream = TcpStream::connect(&self.addresses[..])
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        // commands are tiny and latency matters more than throughput
+        stream
+            .set_nodelay(true)
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        Ok(stream)
+    }
+}
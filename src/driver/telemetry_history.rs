@@ -0,0 +1,224 @@
+//! Bounded telemetry history with statistics
+//!
+//! Filled by [TelemetryPoller](crate::TelemetryPoller) once history is enabled,
+//! or by hand from values returned by [LSSDriver::last_known](crate::LSSDriver::last_known).
+
+use crate::driver::message_types::{LastKnown, Timestamped};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Statistics of the samples of a value inside a window
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// Population standard deviation
+    pub stddev: f32,
+    /// Number of samples statistics were calculated from
+    pub samples: usize,
+}
+
+impl Stats {
+    fn from_samples<'a>(
+        samples: impl Iterator<Item = &'a Timestamped<f32>> + Clone,
+    ) -> Option<Stats> {
+        let count = samples.clone().count();
+        if count == 0 {
+            return None;
+        }
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+        let mut sum = 0.0;
+        for sample in samples.clone() {
+            min = min.min(sample.value);
+            max = max.max(sample.value);
+            sum += sample.value;
+        }
+        let mean = sum / count as f32;
+        let variance = samples
+            .map(|sample| (sample.value - mean).powi(2))
+            .sum::<f32>()
+            / count as f32;
+        Some(Stats {
+            min,
+            max,
+            mean,
+            stddev: variance.sqrt(),
+            samples: count,
+        })
+    }
+}
+
+/// Statistics of every recorded value. `None` if there were no samples inside the window
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TelemetryStats {
+    /// Position in degrees
+    pub position: Option<Stats>,
+    /// Voltage in volts
+    pub voltage: Option<Stats>,
+    /// Temperature in celsius
+    pub temperature: Option<Stats>,
+    /// Current in amps
+    pub current: Option<Stats>,
+}
+
+/// Last `capacity` samples of position, voltage, temperature and current of a servo
+///
+/// Oldest samples are dropped when full. Pushing the same reading twice only stores it once,
+/// so a servo that stopped replying doesn't fill the history with stale values.
+#[derive(Clone, Debug)]
+pub struct TelemetryHistory {
+    capacity: usize,
+    position: VecDeque<Timestamped<f32>>,
+    voltage: VecDeque<Timestamped<f32>>,
+    temperature: VecDeque<Timestamped<f32>>,
+    current: VecDeque<Timestamped<f32>>,
+}
+
+impl TelemetryHistory {
+    /// Create empty history
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of samples kept per value
+    pub fn new(capacity: usize) -> TelemetryHistory {
+        TelemetryHistory {
+            capacity,
+            position: VecDeque::with_capacity(capacity),
+            voltage: VecDeque::with_capacity(capacity),
+            temperature: VecDeque::with_capacity(capacity),
+            current: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Number of samples kept per value
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Record values that were received since the last push
+    pub fn push(&mut self, telemetry: &LastKnown) {
+        let capacity = self.capacity;
+        record(&mut self.position, telemetry.position, capacity);
+        record(&mut self.voltage, telemetry.voltage, capacity);
+        record(&mut self.temperature, telemetry.temperature, capacity);
+        record(&mut self.current, telemetry.current, capacity);
+    }
+
+    /// Statistics over samples received within `window`
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How far back to look. e.g. 10 seconds for the peak current in the last 10 s
+    pub fn stats(&self, window: Duration) -> TelemetryStats {
+        // window reaching before the clock started covers everything
+        let since = Instant::now().checked_sub(window);
+        let in_window = |samples: &VecDeque<Timestamped<f32>>| {
+            Stats::from_samples(
+                samples
+                    .iter()
+                    .filter(move |sample| since.is_none_or(|since| sample.received_at >= since)),
+            )
+        };
+        TelemetryStats {
+            position: in_window(&self.position),
+            voltage: in_window(&self.voltage),
+            temperature: in_window(&self.temperature),
+            current: in_window(&self.current),
+        }
+    }
+
+    /// Recorded positions, oldest first
+    pub fn positions(&self) -> impl Iterator<Item = &Timestamped<f32>> {
+        self.position.iter()
+    }
+
+    /// Recorded voltages, oldest first
+    pub fn voltages(&self) -> impl Iterator<Item = &Timestamped<f32>> {
+        self.voltage.iter()
+    }
+
+    /// Recorded temperatures, oldest first
+    pub fn temperatures(&self) -> impl Iterator<Item = &Timestamped<f32>> {
+        self.temperature.iter()
+    }
+
+    /// Recorded currents, oldest first
+    pub fn currents(&self) -> impl Iterator<Item = &Timestamped<f32>> {
+        self.current.iter()
+    }
+}
+
+fn record(
+    samples: &mut VecDeque<Timestamped<f32>>,
+    sample: Option<Timestamped<f32>>,
+    capacity: usize,
+) {
+    let sample = match sample {
+        Some(sample) => sample,
+        None => return,
+    };
+    if capacity == 0 || samples.back() == Some(&sample) {
+        return;
+    }
+    if samples.len() == capacity {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current(value: f32, age: Duration) -> LastKnown {
+        LastKnown {
+            current: Some(Timestamped {
+                value,
+                received_at: Instant::now() - age,
+            }),
+            ..LastKnown::default()
+        }
+    }
+
+    #[test]
+    fn drops_oldest_samples() {
+        let mut history = TelemetryHistory::new(2);
+        history.push(&current(1.0, Duration::ZERO));
+        history.push(&current(2.0, Duration::ZERO));
+        history.push(&current(3.0, Duration::ZERO));
+        let values: Vec<f32> = history.currents().map(|sample| sample.value).collect();
+        assert_eq!(values, vec![2.0, 3.0]);
+        assert!(history.positions().next().is_none());
+    }
+
+    #[test]
+    fn ignores_repeated_samples() {
+        let mut history = TelemetryHistory::new(4);
+        let telemetry = current(1.0, Duration::ZERO);
+        history.push(&telemetry);
+        history.push(&telemetry);
+        assert_eq!(history.currents().count(), 1);
+    }
+
+    #[test]
+    fn stats_over_window() {
+        let mut history = TelemetryHistory::new(8);
+        history.push(&current(9.0, Duration::from_secs(30)));
+        history.push(&current(1.0, Duration::from_secs(2)));
+        history.push(&current(3.0, Duration::from_secs(1)));
+        let stats = history.stats(Duration::from_secs(10));
+        let current = stats.current.unwrap();
+        assert_eq!(current.samples, 2);
+        assert_eq!(current.min, 1.0);
+        assert_eq!(current.max, 3.0);
+        assert_eq!(current.mean, 2.0);
+        assert_eq!(current.stddev, 1.0);
+        assert_eq!(stats.voltage, None);
+        assert_eq!(
+            history.stats(Duration::from_secs(60)).current.unwrap().max,
+            9.0
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Telemetry logging to CSV or JSON lines files
+//!
+//! Rows hold the wall clock time the values were received at so logs can be lined up
+//! with other recordings when loaded into pandas or a spreadsheet.
+
+use crate::driver::message_types::{LastKnown, Timestamped};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+const CSV_HEADER: &str = "timestamp,id,position,voltage,temperature,current,status";
+
+/// Receive times of position, voltage, temperature, current and status
+type ReceiveTimes = [Option<Instant>; 5];
+
+/// File format written by [TelemetryLogger]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Comma separated values with a header row. Missing values are empty cells
+    Csv,
+    /// One JSON object per line. Missing values are `null`
+    JsonLines,
+}
+
+/// Writes timestamped telemetry rows
+///
+/// Every row holds unix time in seconds, servo ID, position in degrees, voltage in volts,
+/// temperature in celsius, current in amps and motor status.
+/// Only values received since the previous row of the same servo are written, others are left out.
+/// Time of a row is when its newest value was received.
+/// Requires the `telemetry-log` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use lss_driver::{LSSDriver, LogFormat, TelemetryLogger};
+/// use std::time::Duration;
+///
+/// async fn async_main(){
+///     let handle = LSSDriver::with_baud_rate("COM1", 115200).unwrap().spawn();
+///     let poller = handle.telemetry_poller(&[1, 2, 3], Duration::from_millis(100));
+///     let mut logger = TelemetryLogger::create("telemetry.csv", LogFormat::Csv).unwrap();
+///     loop {
+///         tokio::time::sleep(Duration::from_millis(100)).await;
+///         logger.log_all(&poller.latest_all()).unwrap();
+///     }
+/// }
+/// ```
+pub struct TelemetryLogger<W: Write> {
+    writer: W,
+    format: LogFormat,
+    header_written: bool,
+    /// Receive times of the values already written per servo
+    logged: HashMap<u8, ReceiveTimes>,
+}
+
+impl TelemetryLogger<BufWriter<File>> {
+    /// Create or truncate file at `path`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the log file
+    /// * `format` - Format of the rows
+    pub fn create(
+        path: impl AsRef<Path>,
+        format: LogFormat,
+    ) -> io::Result<TelemetryLogger<BufWriter<File>>> {
+        Ok(TelemetryLogger::new(
+            BufWriter::new(File::create(path)?),
+            format,
+        ))
+    }
+}
+
+impl<W: Write> TelemetryLogger<W> {
+    /// Log into any writer
+    ///
+    /// CSV header is written together with the first row.
+    pub fn new(writer: W, format: LogFormat) -> TelemetryLogger<W> {
+        TelemetryLogger {
+            writer,
+            format,
+            header_written: false,
+            logged: HashMap::new(),
+        }
+    }
+
+    /// Write a row for one servo
+    ///
+    /// Nothing is written if no value was received since the previous row of the servo.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo the telemetry belongs to
+    /// * `telemetry` - Values to write. e.g. from [LSSDriver::last_known](crate::LSSDriver::last_known)
+    pub fn log(&mut self, id: u8, telemetry: &LastKnown) -> io::Result<()> {
+        let received: ReceiveTimes = [
+            telemetry.position.map(|value| value.received_at),
+            telemetry.voltage.map(|value| value.received_at),
+            telemetry.temperature.map(|value| value.received_at),
+            telemetry.current.map(|value| value.received_at),
+            telemetry.status.map(|value| value.received_at),
+        ];
+        let logged = self.logged.insert(id, received).unwrap_or_default();
+        let fresh: Vec<bool> = received
+            .iter()
+            .zip(logged)
+            .map(|(received, logged)| received.is_some() && *received != logged)
+            .collect();
+        let newest = received
+            .iter()
+            .zip(&fresh)
+            .filter(|(_, fresh)| **fresh)
+            .filter_map(|(received, _)| *received)
+            .max();
+        let newest = match newest {
+            Some(newest) => newest,
+            None => return Ok(()),
+        };
+        let timestamp = (SystemTime::now() - newest.elapsed())
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let only_fresh = |value: Option<Timestamped<f32>>, fresh: bool| value.filter(|_| fresh);
+        let values = [
+            only_fresh(telemetry.position, fresh[0]),
+            only_fresh(telemetry.voltage, fresh[1]),
+            only_fresh(telemetry.temperature, fresh[2]),
+            only_fresh(telemetry.current, fresh[3]),
+        ];
+        let status = telemetry
+            .status
+            .filter(|_| fresh[4])
+            .map(|status| format!("{:?}", status.value));
+        match self.format {
+            LogFormat::Csv => {
+                if !self.header_written {
+                    writeln!(self.writer, "{}", CSV_HEADER)?;
+                    self.header_written = true;
+                }
+                write!(self.writer, "{:.3},{}", timestamp, id)?;
+                for value in values {
+                    write!(self.writer, ",{}", csv_value(value))?;
+                }
+                writeln!(self.writer, ",{}", status.unwrap_or_default())
+            }
+            LogFormat::JsonLines => {
+                write!(
+                    self.writer,
+                    "{{\"timestamp\":{:.3},\"id\":{}",
+                    timestamp, id
+                )?;
+                let names = ["position", "voltage", "temperature", "current"];
+                for (name, value) in names.iter().zip(values) {
+                    write!(self.writer, ",\"{}\":{}", name, json_value(value))?;
+                }
+                match status {
+                    Some(status) => writeln!(self.writer, ",\"status\":\"{}\"}}", status),
+                    None => writeln!(self.writer, ",\"status\":null}}"),
+                }
+            }
+        }
+    }
+
+    /// Write a row for every servo, ordered by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `telemetry` - Values by servo ID. e.g. from [TelemetryPoller::latest_all](crate::TelemetryPoller::latest_all)
+    pub fn log_all(&mut self, telemetry: &HashMap<u8, LastKnown>) -> io::Result<()> {
+        let mut ids: Vec<&u8> = telemetry.keys().collect();
+        ids.sort();
+        for id in ids {
+            self.log(*id, &telemetry[id])?;
+        }
+        Ok(())
+    }
+
+    /// Flush buffered rows to the writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Get back the writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn csv_value(value: Option<Timestamped<f32>>) -> String {
+    match value {
+        Some(value) if value.value.is_finite() => value.value.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn json_value(value: Option<Timestamped<f32>>) -> String {
+    // JSON has no representation for NaN or infinity
+    match value {
+        Some(value) if value.value.is_finite() => value.value.to_string(),
+        _ => "null".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telemetry() -> LastKnown {
+        LastKnown {
+            position: Some(Timestamped::now(10.5)),
+            voltage: Some(Timestamped::now(11.2)),
+            ..LastKnown::default()
+        }
+    }
+
+    /// Lines written by the logger
+    fn rows(logger: TelemetryLogger<Vec<u8>>) -> Vec<String> {
+        String::from_utf8(logger.into_inner())
+            .unwrap()
+            .lines()
+            .map(|line| line.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn writes_csv() {
+        let mut logger = TelemetryLogger::new(vec![], LogFormat::Csv);
+        logger.log(3, &telemetry()).unwrap();
+        logger.log(4, &LastKnown::default()).unwrap();
+        let rows = rows(logger);
+        // servo 4 has nothing to log
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], CSV_HEADER);
+        let (_, row) = rows[1].split_once(',').unwrap();
+        assert_eq!(row, "3,10.5,11.2,,,");
+    }
+
+    #[test]
+    fn writes_only_new_values() {
+        let mut logger = TelemetryLogger::new(vec![], LogFormat::Csv);
+        let mut telemetry = telemetry();
+        logger.log(3, &telemetry).unwrap();
+        logger.log(3, &telemetry).unwrap();
+        telemetry.voltage = Some(Timestamped::now(11.5));
+        logger.log(3, &telemetry).unwrap();
+        let rows = rows(logger);
+        assert_eq!(rows.len(), 3);
+        let (_, row) = rows[2].split_once(',').unwrap();
+        assert_eq!(row, "3,,11.5,,,");
+    }
+
+    #[test]
+    fn timestamp_is_receive_time() {
+        let mut logger = TelemetryLogger::new(vec![], LogFormat::Csv);
+        let old = Timestamped {
+            value: 10.5,
+            received_at: Instant::now() - std::time::Duration::from_secs(60),
+        };
+        let telemetry = LastKnown {
+            position: Some(old),
+            ..LastKnown::default()
+        };
+        logger.log(3, &telemetry).unwrap();
+        let rows = rows(logger);
+        let (timestamp, _) = rows[1].split_once(',').unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+        let age = now - timestamp.parse::<f64>().unwrap();
+        assert!((59.0..61.0).contains(&age));
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let mut logger = TelemetryLogger::new(vec![], LogFormat::JsonLines);
+        logger.log(3, &telemetry()).unwrap();
+        let rows = rows(logger);
+        assert_eq!(rows.len(), 1);
+        let (_, row) = rows[0].split_once(",\"id\"").unwrap();
+        assert_eq!(
+            row,
+            ":3,\"position\":10.5,\"voltage\":11.2,\"temperature\":null,\"current\":null,\"status\":null}"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Background telemetry polling
+//!
+//! Polls servos through a [DriverHandle] so user commands issued on other handles
+//! are queued in between individual polls instead of waiting for a whole round.
+
+use crate::driver::driver_handle::DriverHandle;
+use crate::driver::message_types::{Alarm, AlarmKind, AlarmThresholds, LastKnown};
+use crate::driver::telemetry_history::{TelemetryHistory, TelemetryStats};
+use crate::driver::time;
+use futures::Stream;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+type Snapshots = Arc<Mutex<HashMap<u8, LastKnown>>>;
+type Histories = Arc<Mutex<HashMap<u8, TelemetryHistory>>>;
+type AlarmCallback = Arc<dyn Fn(&Alarm) + Send + Sync>;
+
+/// Thresholds and alarm subscribers shared with the polling task
+#[derive(Default)]
+struct AlarmMonitor {
+    thresholds: HashMap<u8, AlarmThresholds>,
+    /// Limits currently exceeded. Alarms only fire when a limit is first exceeded
+    active: HashSet<(u8, AlarmKind)>,
+    callbacks: Vec<AlarmCallback>,
+}
+
+impl AlarmMonitor {
+    fn check(&mut self, latest: &[(u8, LastKnown)]) -> Vec<Alarm> {
+        let mut raised = vec![];
+        for (id, telemetry) in latest {
+            let thresholds = self.thresholds.get(id).copied().unwrap_or_default();
+            self.active.retain(|(active_id, kind)| {
+                active_id != id || !thresholds.cleared(*kind, telemetry)
+            });
+            for (kind, value) in thresholds.exceeded(telemetry) {
+                if self.active.insert((*id, kind)) {
+                    raised.push(Alarm {
+                        id: *id,
+                        kind,
+                        value,
+                    });
+                }
+            }
+        }
+        raised
+    }
+}
+
+/// Check `latest` and call alarm callbacks
+///
+/// Callbacks run after the monitor is unlocked so they can change thresholds or register callbacks.
+fn raise_alarms(monitor: &Mutex<AlarmMonitor>, latest: &[(u8, LastKnown)]) -> Vec<Alarm> {
+    let (raised, callbacks) = {
+        let mut monitor = monitor.lock().unwrap();
+        (monitor.check(latest), monitor.callbacks.clone())
+    };
+    for alarm in &raised {
+        for callback in &callbacks {
+            callback(alarm);
+        }
+    }
+    raised
+}
+
+/// Keeps latest telemetry of a set of servos up to date in the background
+///
+/// Created with [DriverHandle::telemetry_poller](crate::DriverHandle::telemetry_poller).
+/// Every round queries position, voltage, temperature and current of all servos using pipelined queries.
+/// Keeps the driver task running until the poller is dropped.
+pub struct TelemetryPoller {
+    ids: Vec<u8>,
+    snapshots: Snapshots,
+    histories: Histories,
+    monitor: Arc<Mutex<AlarmMonitor>>,
+    alarms: broadcast::Sender<Alarm>,
+    task: JoinHandle<()>,
+}
+
+impl TelemetryPoller {
+    pub(crate) fn spawn(handle: DriverHandle, ids: &[u8], interval: Duration) -> TelemetryPoller {
+        let snapshots = Snapshots::default();
+        let histories = Histories::default();
+        let monitor = Arc::new(Mutex::new(AlarmMonitor::default()));
+        let (alarms, _) = broadcast::channel(64);
+        let task = tokio::spawn(poll(
+            handle,
+            ids.to_vec(),
+            interval,
+            snapshots.clone(),
+            histories.clone(),
+            monitor.clone(),
+            alarms.clone(),
+        ));
+        TelemetryPoller {
+            ids: ids.to_vec(),
+            snapshots,
+            histories,
+            monitor,
+            alarms,
+            task,
+        }
+    }
+
+    /// Latest telemetry of a servo
+    ///
+    /// `None` until the first round finished. Values servo failed to report stay `None`
+    /// or keep their previous value. Check timestamps to detect stale values.
+    pub fn latest(&self, id: u8) -> Option<LastKnown> {
+        self.snapshots.lock().unwrap().get(&id).copied()
+    }
+
+    /// Latest telemetry of all polled servos
+    pub fn latest_all(&self) -> HashMap<u8, LastKnown> {
+        self.snapshots.lock().unwrap().clone()
+    }
+
+    /// Keep the last `capacity` samples of every polled servo
+    ///
+    /// History is off by default. Changing capacity clears recorded samples.
+    /// Setting capacity to 0 turns history off again.
+    pub fn keep_history(&self, capacity: usize) {
+        let mut histories = self.histories.lock().unwrap();
+        histories.clear();
+        if capacity > 0 {
+            for id in &self.ids {
+                histories.insert(*id, TelemetryHistory::new(capacity));
+            }
+        }
+    }
+
+    /// Recorded history of a servo
+    ///
+    /// `None` if history is off or servo isn't polled.
+    pub fn history(&self, id: u8) -> Option<TelemetryHistory> {
+        self.histories.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Statistics over the recorded history of a servo
+    ///
+    /// `None` if history is off or servo isn't polled.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo you want statistics of
+    /// * `window` - How far back to look. e.g. 10 seconds for the peak current in the last 10 s
+    pub fn stats(&self, id: u8, window: Duration) -> Option<TelemetryStats> {
+        self.histories
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|history| history.stats(window))
+    }
+
+    /// Set limits checked after every round
+    ///
+    /// An alarm is raised once when a limit is exceeded and again only after
+    /// the value went back within the limit by more than its hysteresis band.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of servo the limits apply to
+    /// * `thresholds` - Limits. Default value disables alarms of this servo
+    pub fn set_thresholds(&self, id: u8, thresholds: AlarmThresholds) {
+        let mut monitor = self.monitor.lock().unwrap();
+        monitor.thresholds.insert(id, thresholds);
+        monitor.active.retain(|(active_id, _)| *active_id != id);
+    }
+
+    /// Stream of raised alarms
+    ///
+    /// Only alarms raised after subscribing are received.
+    pub fn alarms(&self) -> impl Stream<Item = Alarm> {
+        futures::stream::unfold(self.alarms.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(alarm) => return Some((alarm, receiver)),
+                    // subscriber fell behind and missed the oldest alarms
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Call `callback` for every raised alarm
+    ///
+    /// Runs on the polling task. Keep it short. e.g. limp servos through a [DriverHandle]
+    /// by spawning a task instead of awaiting inside the callback.
+    pub fn on_alarm(&self, callback: impl Fn(&Alarm) + Send + Sync + 'static) {
+        self.monitor
+            .lock()
+            .unwrap()
+            .callbacks
+            .push(Arc::new(callback));
+    }
+}
+
+impl Drop for TelemetryPoller {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn poll(
+    handle: DriverHandle,
+    ids: Vec<u8>,
+    interval: Duration,
+    snapshots: Snapshots,
+    histories: Histories,
+    monitor: Arc<Mutex<AlarmMonitor>>,
+    alarms: broadcast::Sender<Alarm>,
+) {
+    let mut next_round = Instant::now();
+    loop {
+        // each query is a separate job so other handles get a turn in between.
+        // Failures of single servos show up as stale timestamps
+        let _ = handle.query_positions(&ids).await;
+        let _ = handle.query_voltages(&ids).await;
+        let _ = handle.query_temperatures(&ids).await;
+        let _ = handle.query_currents(&ids).await;
+        let owned_ids = ids.clone();
+        let latest = handle
+            .run(move |driver| {
+                Box::pin(async move {
+                    owned_ids
+                        .iter()
+                        .map(|id| (*id, driver.last_known(*id)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .await;
+        match latest {
+            Ok(latest) => {
+                for (id, telemetry) in &latest {
+                    if let Some(history) = histories.lock().unwrap().get_mut(id) {
+                        history.push(telemetry);
+                    }
+                }
+                let raised = raise_alarms(&monitor, &latest);
+                for alarm in raised {
+                    // nobody listening to alarms isn't an error
+                    let _ = alarms.send(alarm);
+                }
+                snapshots.lock().unwrap().extend(latest);
+            }
+            // driver task stopped
+            Err(_) => return,
+        }
+        // skip missed rounds instead of polling back to back to catch up
+        next_round = (next_round + interval).max(Instant::now());
+        time::sleep(next_round.saturating_duration_since(Instant::now())).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::message_types::Timestamped;
+
+    fn temperature(value: f32) -> Vec<(u8, LastKnown)> {
+        vec![(
+            1,
+            LastKnown {
+                temperature: Some(Timestamped::now(value)),
+                ..LastKnown::default()
+            },
+        )]
+    }
+
+    #[test]
+    fn alarm_clears_below_hysteresis_band() {
+        let monitor = Mutex::new(AlarmMonitor::default());
+        monitor.lock().unwrap().thresholds.insert(
+            1,
+            AlarmThresholds {
+                max_temperature: Some(60.0),
+                temperature_hysteresis: 5.0,
+                ..AlarmThresholds::default()
+            },
+        );
+        assert_eq!(raise_alarms(&monitor, &temperature(61.0)).len(), 1);
+        // hovering around the limit keeps the alarm active
+        assert!(raise_alarms(&monitor, &temperature(58.0)).is_empty());
+        assert!(raise_alarms(&monitor, &temperature(61.0)).is_empty());
+        // cooled down past the band
+        assert!(raise_alarms(&monitor, &temperature(54.0)).is_empty());
+        assert_eq!(raise_alarms(&monitor, &temperature(61.0)).len(), 1);
+    }
+
+    #[test]
+    fn callbacks_run_with_monitor_unlocked() {
+        let monitor = Arc::new(Mutex::new(AlarmMonitor::default()));
+        let unlocked = Arc::new(Mutex::new(false));
+        let (inner, seen) = (monitor.clone(), unlocked.clone());
+        let mut guard = monitor.lock().unwrap();
+        guard.thresholds.insert(
+            1,
+            AlarmThresholds {
+                max_temperature: Some(60.0),
+                ..AlarmThresholds::default()
+            },
+        );
+        guard.callbacks.push(Arc::new(move |_| {
+            *seen.lock().unwrap() = inner.try_lock().is_ok();
+        }));
+        drop(guard);
+        raise_alarms(&monitor, &temperature(61.0));
+        assert!(*unlocked.lock().unwrap());
+    }
+}
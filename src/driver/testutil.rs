@@ -0,0 +1,78 @@
+//! Test utilities for running the driver against a fake servo
+//!
+//! The fake servo runs on one end of a pseudo-terminal while the driver talks to the other end.
+//! This exercises the real serial transport including framing and timeouts.
+
+use crate::driver::{serial_driver::FramedSerialDriver, LSSDriver, LssDriverError};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Fake servo responding to LSS commands over a pseudo-terminal
+///
+/// Background task is stopped when this is dropped.
+pub struct FakeServo {
+    task: JoinHandle<()>,
+}
+
+impl FakeServo {
+    /// Spawn fake servo and create a driver connected to it
+    ///
+    /// Needs to be called from within a tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `responder` - Called with every received command including framing (e.g. `#5QV\r`).
+    ///   Returned string is written back verbatim (e.g. `*5QV11200\r`).
+    ///   Return `None` to simulate a servo that doesn't answer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lss_driver::testutil::FakeServo;
+    /// async fn async_main(){
+    ///     let (mut driver, _servo) = FakeServo::spawn(|command| match command {
+    ///         "#5QV\r" => Some("*5QV11200\r".to_owned()),
+    ///         _ => None,
+    ///     })
+    ///     .unwrap();
+    ///     let voltage = driver.query_voltage(5).await.unwrap();
+    /// }
+    /// ```
+    pub fn spawn<F>(mut responder: F) -> DriverResult<(LSSDriver, FakeServo)>
+    where
+        F: FnMut(&str) -> Option<String> + Send + 'static,
+    {
+        let (mut servo_port, driver_port) = tokio_serial::SerialStream::pair()
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        let task = tokio::spawn(async move {
+            let mut buffer = Vec::new();
+            let mut read_buffer = [0_u8; 256];
+            loop {
+                let read = match servo_port.read(&mut read_buffer).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => read,
+                };
+                buffer.extend_from_slice(&read_buffer[..read]);
+                while let Some(command_break) = buffer.iter().position(|b| *b == b'\r') {
+                    let command: Vec<u8> = buffer.drain(..=command_break).collect();
+                    let command = String::from_utf8_lossy(&command);
+                    if let Some(response) = responder(&command) {
+                        if servo_port.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        let driver = FramedSerialDriver::from_serial_stream(driver_port);
+        Ok((LSSDriver::with_driver(Box::new(driver)), FakeServo { task }))
+    }
+}
+
+impl Drop for FakeServo {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,81 @@
+//! Runtime agnostic timers
+//!
+//! Uses tokio timers when called from within a tokio runtime.
+//! Otherwise falls back to `async-io` timers so the driver also works under other executors
+//! such as async-std or smol.
+
+use futures::future::{self, BoxFuture, Either};
+use futures::Stream;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Returned by [timeout] when the deadline passed first
+#[derive(Debug)]
+pub(crate) struct Elapsed;
+
+/// Wait for `duration`
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "runtime-tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::time::sleep(duration).await;
+    }
+    async_io::Timer::after(duration).await;
+}
+
+/// Run `future` failing with [Elapsed] if it doesn't finish within `duration`
+pub(crate) async fn timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, Elapsed> {
+    futures::pin_mut!(future);
+    let deadline = sleep(duration);
+    futures::pin_mut!(deadline);
+    match future::select(future, deadline).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed),
+    }
+}
+
+/// Stream yielding what `poll` returns every `interval` until it returns `None`
+///
+/// `state` is handed to every call of `poll`.
+/// First call happens right away.
+pub(crate) fn every<S, T, F>(interval: Duration, state: S, poll: F) -> impl Stream<Item = T>
+where
+    F: for<'a> FnMut(&'a mut S) -> BoxFuture<'a, Option<T>>,
+{
+    futures::stream::unfold(
+        (state, poll, Instant::now()),
+        move |(mut state, mut poll, next_tick)| async move {
+            sleep(next_tick.saturating_duration_since(Instant::now())).await;
+            // ticks missed while polling are delayed instead of bursting to catch up
+            let next_tick = next_tick.max(Instant::now()) + interval;
+            let item = poll(&mut state).await?;
+            Some((item, (state, poll, next_tick)))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn sleeps_without_runtime() {
+        let start = Instant::now();
+        futures::executor::block_on(sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn times_out_without_runtime() {
+        let result = futures::executor::block_on(timeout(
+            Duration::from_millis(10),
+            future::pending::<()>(),
+        ));
+        assert!(result.is_err());
+        let result = futures::executor::block_on(timeout(Duration::from_millis(10), async { 5 }));
+        assert_eq!(result.unwrap(), 5);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::driver::message_types::{LssDriverError, RetryPolicy};
+use crate::driver::serial_driver::{
+    FramedDriver, LssCodec, LssCommand, LssResponse, DEFAULT_RECEIVE_TIMEOUT,
+};
+use crate::driver::time::timeout;
+use async_trait::async_trait;
+use bytes::BytesMut;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::{lookup_host, ToSocketAddrs, UdpSocket};
+use tokio_util::codec::Decoder;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Largest datagram accepted from the bridge
+const MAX_DATAGRAM_SIZE: usize = 1024;
+
+/// Length of the sequence number header
+const SEQUENCE_NUMBER_LENGTH: usize = 2;
+
+/// Transport for servos attached to a serial over UDP bridge
+///
+/// Every command is sent as a single datagram.
+/// Replies may be split across datagrams or several may share one.
+/// Lost datagrams show up as `TimeoutError`, use a [RetryPolicy] to resend queries.
+///
+/// With [sequence numbers](UdpFramedDriver::with_sequence_numbers) enabled every datagram
+/// starts with a big endian `u16` sequence number.
+/// The bridge acknowledges each command with a datagram carrying the same number, optionally followed by bus data,
+/// and forwards a resent command only once.
+/// Datagrams from the bridge without a new acknowledgement reuse the number of the last command.
+pub struct UdpFramedDriver {
+    socket: UdpSocket,
+    receive_timeout: Duration,
+    read_buffer: BytesMut,
+    codec: LssCodec,
+    sequencing: Option<Sequencing>,
+}
+
+/// Sequence number state of a [UdpFramedDriver]
+struct Sequencing {
+    resend: RetryPolicy,
+    next: u16,
+}
+
+impl UdpFramedDriver {
+    /// Connect to a bridge
+    ///
+    /// Socket is bound to the address family of the bridge so both IPv4 and IPv6 work.
+    pub async fn connect<A: ToSocketAddrs>(address: A) -> DriverResult<UdpFramedDriver> {
+        let address = lookup_host(address)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?
+            .next()
+            .ok_or(LssDriverError::FailedOpeningSerialPort)?;
+        let local: SocketAddr = match address {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        socket
+            .connect(address)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+        Ok(UdpFramedDriver {
+            socket,
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            read_buffer: BytesMut::new(),
+            codec: LssCodec::new(),
+            sequencing: None,
+        })
+    }
+
+    /// Number commands and resend them until the bridge acknowledges them
+    ///
+    /// `resend.backoff` is how long the first attempt waits for the acknowledgement.
+    /// Sending fails with `SendingError` once `resend.max_attempts` went unacknowledged.
+    pub fn with_sequence_numbers(mut self, resend: RetryPolicy) -> UdpFramedDriver {
+        self.sequencing = Some(Sequencing { resend, next: 0 });
+        self
+    }
+
+    /// Read one datagram into the read buffer returning its sequence number if enabled
+    async fn receive_datagram(&mut self) -> DriverResult<Option<u16>> {
+        let mut datagram = [0_u8; MAX_DATAGRAM_SIZE];
+        let read = self
+            .socket
+            .recv(&mut datagram)
+            .await
+            .map_err(|_| LssDriverError::Disconnected)?;
+        if self.sequencing.is_none() {
+            self.read_buffer.extend_from_slice(&datagram[..read]);
+            return Ok(None);
+        }
+        if read < SEQUENCE_NUMBER_LENGTH {
+            // too short to come from the bridge
+            return Ok(None);
+        }
+        self.read_buffer
+            .extend_from_slice(&datagram[SEQUENCE_NUMBER_LENGTH..read]);
+        Ok(Some(u16::from_be_bytes([datagram[0], datagram[1]])))
+    }
+
+    async fn wait_for_acknowledgement(&mut self, number: u16) -> DriverResult<()> {
+        while self.receive_datagram().await? != Some(number) {}
+        Ok(())
+    }
+
+    async fn send_sequenced(
+        &mut self,
+        command: LssCommand,
+        number: u16,
+        resend: RetryPolicy,
+    ) -> DriverResult<()> {
+        let mut datagram = number.to_be_bytes().to_vec();
+        datagram.extend_from_slice(command.as_bytes());
+        for attempt in 1..=resend.max_attempts.max(1) {
+            self.socket
+                .send(&datagram)
+                .await
+                .map_err(|_| LssDriverError::SendingError)?;
+            let acknowledged = timeout(
+                resend.delay_after(attempt),
+                self.wait_for_acknowledgement(number),
+            )
+            .await;
+            if let Ok(result) = acknowledged {
+                return result;
+            }
+        }
+        Err(LssDriverError::SendingError)
+    }
+
+    async fn read_frame(&mut self) -> DriverResult<LssResponse> {
+        loop {
+            if let Some(response) = self
+                .codec
+                .decode(&mut self.read_buffer)
+                .map_err(|_| LssDriverError::Disconnected)?
+            {
+                return Ok(response);
+            }
+            self.receive_datagram().await?;
+        }
+    }
+}
+
+#[async_trait]
+impl FramedDriver for UdpFramedDriver {
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        self.read_buffer.clear();
+        Ok(())
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        self.codec.take_dropped_bytes()
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        if let Some(sequencing) = &mut self.sequencing {
+            let number = sequencing.next;
+            sequencing.next = number.wrapping_add(1);
+            let resend = sequencing.resend;
+            return self.send_sequenced(command, number, resend).await;
+        }
+        self.socket
+            .send(command.as_bytes())
+            .await
+            .map_err(|_| LssDriverError::SendingError)?;
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        timeout(self.receive_timeout, self.read_frame())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+}
@@ -0,0 +1,33 @@
+use crate::driver::message_types::LssDriverError;
+use crate::driver::stream_driver::{Connector, StreamFramedDriver};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use tokio::net::UnixStream;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Transport for a local daemon that owns the serial port and forwards raw bytes over a unix domain socket
+pub type UdsFramedDriver = StreamFramedDriver<UdsConnector>;
+
+/// Connects to the socket of the daemon
+pub struct UdsConnector {
+    path: PathBuf,
+}
+
+impl UdsFramedDriver {
+    pub async fn connect<P: AsRef<Path>>(path: P) -> DriverResult<UdsFramedDriver> {
+        let path = path.as_ref().to_owned();
+        StreamFramedDriver::open(UdsConnector { path }).await
+    }
+}
+
+#[async_trait]
+impl Connector for UdsConnector {
+    type Stream = UnixStream;
+
+    async fn connect(&self) -> DriverResult<UnixStream> {
+        UnixStream::connect(&self.path)
+            .await
+            .map_err(|_| LssDriverError::FailedOpeningSerialPort)
+    }
+}
@@ -0,0 +1,124 @@
+//! Conversions between SI-ish units used by this driver and native units of the servo
+//!
+//! Useful when sending custom commands. Native units of commands are:
+//!
+//! * Position (`D`, `MD`, `CFD`, `QFD`, `QD`, `QDT`, `O`, `CO`, `QO`, `AR`, `CAR`, `QAR`) - tenths of degrees
+//! * Speed (`SD`, `QSD`, `SD` modifier) - tenths of degrees per second
+//! * Wheel speed (`WD`, `QWD`) - degrees per second
+//! * Wheel speed (`WR`, `QWR`) and maximum speed (`SR`, `QSR`) - rotations per minute
+//! * Voltage (`QV`) - millivolts
+//! * Current (`QC`, `CH` and `CL` modifiers) - milliamps
+//! * Temperature (`QT`) - tenths of degrees celsius
+//!
+//! Conversions to native units round to the nearest value and saturate at `i32` bounds.
+
+/// Convert degrees to tenths of degrees
+pub fn degrees_to_native(degrees: f32) -> i32 {
+    (degrees * 10.0).round() as i32
+}
+
+/// Convert tenths of degrees to degrees
+pub fn native_to_degrees(native: i32) -> f32 {
+    native as f32 / 10.0
+}
+
+/// Convert degrees per second to tenths of degrees per second
+pub fn deg_per_s_to_native(deg_per_s: f32) -> i32 {
+    (deg_per_s * 10.0).round() as i32
+}
+
+/// Convert tenths of degrees per second to degrees per second
+pub fn native_to_deg_per_s(native: i32) -> f32 {
+    native as f32 / 10.0
+}
+
+/// Convert wheel speed in degrees per second to whole degrees per second
+pub fn wheel_deg_per_s_to_native(deg_per_s: f32) -> i32 {
+    deg_per_s.round() as i32
+}
+
+/// Convert wheel speed in whole degrees per second to degrees per second
+pub fn native_to_wheel_deg_per_s(native: i32) -> f32 {
+    native as f32
+}
+
+/// Convert wheel speed in RPM to whole RPM
+pub fn wheel_rpm_to_native(rpm: f32) -> i32 {
+    rpm.round() as i32
+}
+
+/// Convert wheel speed in whole RPM to RPM
+pub fn native_to_wheel_rpm(native: i32) -> f32 {
+    native as f32
+}
+
+/// Convert millivolts to volts
+pub fn native_to_volts(native: i32) -> f32 {
+    native as f32 / 1000.0
+}
+
+/// Convert milliamps to Amps
+pub fn native_to_amps(native: i32) -> f32 {
+    native as f32 / 1000.0
+}
+
+/// Convert Amps to milliamps
+pub fn amps_to_native(amps: f32) -> i32 {
+    (amps * 1000.0).round() as i32
+}
+
+/// Convert tenths of degrees celsius to degrees celsius
+pub fn native_to_celsius(native: i32) -> f32 {
+    native as f32 / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn degrees_round_trip() {
+        assert_eq!(degrees_to_native(180.0), 1800);
+        assert_eq!(degrees_to_native(-1.3), -13);
+        assert_eq!(degrees_to_native(0.0), 0);
+        assert_relative_eq!(native_to_degrees(1800), 180.0);
+        assert_relative_eq!(native_to_degrees(-13), -1.3);
+    }
+
+    #[test]
+    fn degrees_round_to_nearest() {
+        assert_eq!(degrees_to_native(0.04), 0);
+        assert_eq!(degrees_to_native(0.06), 1);
+        assert_eq!(degrees_to_native(-0.06), -1);
+    }
+
+    #[test]
+    fn degrees_saturate() {
+        assert_eq!(degrees_to_native(f32::MAX), i32::MAX);
+        assert_eq!(degrees_to_native(f32::MIN), i32::MIN);
+        assert_eq!(degrees_to_native(f32::NAN), 0);
+    }
+
+    #[test]
+    fn speed_conversions() {
+        assert_eq!(deg_per_s_to_native(180.0), 1800);
+        assert_eq!(deg_per_s_to_native(0.1), 1);
+        assert_relative_eq!(native_to_deg_per_s(1800), 180.0);
+        assert_relative_eq!(native_to_deg_per_s(i32::MAX), i32::MAX as f32 / 10.0);
+        assert_eq!(wheel_deg_per_s_to_native(-90.4), -90);
+        assert_relative_eq!(native_to_wheel_deg_per_s(-90), -90.0);
+        assert_eq!(wheel_rpm_to_native(12.6), 13);
+        assert_relative_eq!(native_to_wheel_rpm(-12), -12.0);
+    }
+
+    #[test]
+    fn electrical_conversions() {
+        assert_relative_eq!(native_to_volts(11200), 11.2);
+        assert_relative_eq!(native_to_amps(200), 0.2);
+        assert_eq!(amps_to_native(0.4), 400);
+        assert_eq!(amps_to_native(0.0), 0);
+        assert_relative_eq!(native_to_celsius(441), 44.1);
+        assert_relative_eq!(native_to_celsius(-50), -5.0);
+    }
+}
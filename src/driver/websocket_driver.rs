@@ -0,0 +1,173 @@
+//! WebSocket client transport
+//!
+//! Only plain `ws://` URLs are supported.
+//! Commands are sent as text messages and payload of received text or binary messages is treated as serial data.
+//! Handshake, framing and answering pings are left to [tokio-tungstenite](https://docs.rs/tokio-tungstenite).
+
+use crate::driver::message_types::LssDriverError;
+use crate::driver::serial_driver::{
+    FramedDriver, LssCodec, LssCommand, LssResponse, DEFAULT_RECEIVE_TIMEOUT,
+};
+use crate::driver::time::timeout;
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::codec::Decoder;
+
+type DriverResult<T> = Result<T, LssDriverError>;
+
+/// Servo replies are a few bytes. Anything larger means the stream is corrupt
+const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+
+/// Transport talking to a WebSocket server that forwards messages to and from the servo bus
+pub struct WebSocketDriver {
+    url: String,
+    receive_timeout: Duration,
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    read_buffer: BytesMut,
+    codec: LssCodec,
+}
+
+impl WebSocketDriver {
+    pub async fn connect(url: &str) -> DriverResult<WebSocketDriver> {
+        Ok(WebSocketDriver {
+            url: url.to_owned(),
+            receive_timeout: DEFAULT_RECEIVE_TIMEOUT,
+            stream: open(url).await?,
+            read_buffer: BytesMut::new(),
+            codec: LssCodec::new(),
+        })
+    }
+
+    async fn read_frame(&mut self) -> DriverResult<LssResponse> {
+        loop {
+            if let Some(response) = self
+                .codec
+                .decode(&mut self.read_buffer)
+                .map_err(|_| LssDriverError::Disconnected)?
+            {
+                return Ok(response);
+            }
+            let message = self
+                .stream
+                .next()
+                .await
+                .ok_or(LssDriverError::Disconnected)?
+                .map_err(|_| LssDriverError::Disconnected)?;
+            match message {
+                Message::Text(text) => self.read_buffer.extend_from_slice(text.as_bytes()),
+                Message::Binary(data) => self.read_buffer.extend_from_slice(&data),
+                Message::Close(_) => return Err(LssDriverError::Disconnected),
+                // pongs are queued by tungstenite and go out with the next write
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => (),
+            }
+        }
+    }
+}
+
+/// Connect and perform opening handshake
+async fn open(url: &str) -> DriverResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    if !url.starts_with("ws://") {
+        return Err(LssDriverError::FailedOpeningSerialPort);
+    }
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_LENGTH))
+        .max_frame_size(Some(MAX_MESSAGE_LENGTH));
+    let (stream, _) = tokio_tungstenite::connect_async_with_config(url, Some(config), true)
+        .await
+        .map_err(|_| LssDriverError::FailedOpeningSerialPort)?;
+    Ok(stream)
+}
+
+#[async_trait]
+impl FramedDriver for WebSocketDriver {
+    async fn discard_input(&mut self) -> DriverResult<()> {
+        self.read_buffer.clear();
+        Ok(())
+    }
+
+    async fn take_dropped_bytes(&mut self) -> usize {
+        self.codec.take_dropped_bytes()
+    }
+
+    async fn send(&mut self, command: LssCommand) -> DriverResult<()> {
+        self.stream
+            .send(Message::text(command.as_str()))
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    async fn receive(&mut self) -> DriverResult<LssResponse> {
+        timeout(self.receive_timeout, self.read_frame())
+            .await
+            .map_err(|_| LssDriverError::TimeoutError)?
+    }
+
+    async fn reconnect(&mut self) -> DriverResult<()> {
+        self.stream = open(&self.url).await?;
+        self.read_buffer.clear();
+        Ok(())
+    }
+
+    async fn close(&mut self) -> DriverResult<()> {
+        self.stream
+            .close(None)
+            .await
+            .map_err(|_| LssDriverError::SendingError)
+    }
+
+    fn set_receive_timeout(&mut self, timeout: Duration) {
+        self.receive_timeout = timeout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn rejects_unsupported_url() {
+        assert!(matches!(
+            WebSocketDriver::connect("wss://robot/lss").await,
+            Err(LssDriverError::FailedOpeningSerialPort)
+        ));
+    }
+
+    #[tokio::test]
+    async fn handshake_and_query() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(socket).await.unwrap();
+            assert_eq!(
+                socket.next().await.unwrap().unwrap(),
+                Message::text("#5QV\r")
+            );
+            // reply split across two messages
+            socket.send(Message::text("*5QV1")).await.unwrap();
+            socket
+                .send(Message::binary(b"1200\r".to_vec()))
+                .await
+                .unwrap();
+            assert_eq!(
+                socket.next().await.unwrap().unwrap(),
+                Message::text("#5L\r")
+            );
+        });
+        let url = format!("ws://{}/lss", address);
+        let mut driver = WebSocketDriver::connect(&url).await.unwrap();
+        driver.set_receive_timeout(Duration::from_millis(100));
+        driver.send(LssCommand::simple(5, "QV")).await.unwrap();
+        let response = driver.receive().await.unwrap();
+        assert_eq!(response.as_str(), "*5QV11200\r");
+        driver.send(LssCommand::simple(5, "L")).await.unwrap();
+        server.await.unwrap();
+    }
+}
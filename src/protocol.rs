@@ -0,0 +1,237 @@
+//! Encoding and parsing of LSS protocol frames
+//!
+//! Only depends on `core` so the same code can drive servos directly from microcontrollers.
+//! Build with `default-features = false` to get just this module without the async driver.
+//!
+//! Commands are written into any [`core::fmt::Write`] such as a fixed buffer with [`encode_command`]
+//! and replies are parsed from borrowed strings, so no allocator is needed.
+//!
+//! [Protocol](https://www.robotshop.com/info/wiki/lynxmotion/view/lynxmotion-smart-servo/lss-communication-protocol/)
+
+use core::fmt::{self, Write};
+
+/// Start of a command frame
+pub const COMMAND_START: char = '#';
+/// Start of a reply frame
+pub const REPLY_START: char = '*';
+/// End of both command and reply frames
+pub const FRAME_END: char = '\r';
+
+/// Errors from encoding commands and parsing replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// Command didn't fit into the buffer
+    BufferTooSmall,
+    /// Reply doesn't start with `*`
+    MissingPrefix,
+    /// Reply doesn't start with a valid servo ID
+    InvalidId,
+    /// Reply is for a different command than expected
+    UnexpectedCommand,
+    /// Reply has no value
+    MissingValue,
+    /// Value of reply isn't a number
+    InvalidValue,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ProtocolError::BufferTooSmall => "Buffer too small",
+            ProtocolError::MissingPrefix => "Missing response prefix",
+            ProtocolError::InvalidId => "Failed parsing id",
+            ProtocolError::UnexpectedCommand => "Unexpected command",
+            ProtocolError::MissingValue => "Failed to extract value",
+            ProtocolError::InvalidValue => "Failed parsing value",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Write command frame `#<id><cmd><value><modifiers>\r`
+///
+/// # Arguments
+///
+/// * `out` - Where to write the frame
+/// * `id` - ID of servo the command is for
+/// * `cmd` - Command such as `D` or `QV`
+/// * `value` - Parameter of the command if it takes one
+/// * `modifiers` - Name and value of each modifier such as `("T", 1000)`
+pub fn write_command<W: Write>(
+    out: &mut W,
+    id: u8,
+    cmd: &str,
+    value: Option<i32>,
+    modifiers: &[(&str, i64)],
+) -> fmt::Result {
+    write!(out, "{}{}{}", COMMAND_START, id, cmd)?;
+    if let Some(value) = value {
+        write!(out, "{}", value)?;
+    }
+    for (name, value) in modifiers {
+        write!(out, "{}{}", name, value)?;
+    }
+    out.write_char(FRAME_END)
+}
+
+/// Encode command frame into `buffer`
+///
+/// Returns number of bytes written
+///
+/// # Example
+///
+/// ```
+/// use lss_driver::protocol::encode_command;
+/// let mut buffer = [0_u8; 32];
+/// let len = encode_command(&mut buffer, 5, "D", Some(900), &[("T", 1000)]).unwrap();
+/// assert_eq!(&buffer[..len], b"#5D900T1000\r");
+/// ```
+pub fn encode_command(
+    buffer: &mut [u8],
+    id: u8,
+    cmd: &str,
+    value: Option<i32>,
+    modifiers: &[(&str, i64)],
+) -> Result<usize, ProtocolError> {
+    let mut writer = SliceWriter {
+        buffer,
+        position: 0,
+    };
+    write_command(&mut writer, id, cmd, value, modifiers)
+        .map_err(|_| ProtocolError::BufferTooSmall)?;
+    Ok(writer.position)
+}
+
+struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.position + s.len();
+        self.buffer
+            .get_mut(self.position..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.position = end;
+        Ok(())
+    }
+}
+
+/// Strip `*` and trailing `\r` from a reply
+fn reply_body(frame: &str) -> Result<&str, ProtocolError> {
+    frame
+        .strip_prefix(REPLY_START)
+        .map(|body| body.trim_end_matches(FRAME_END))
+        .ok_or(ProtocolError::MissingPrefix)
+}
+
+/// ID of servo that sent the reply
+///
+/// `None` for replies without an ID such as QID
+pub fn reply_id(frame: &str) -> Option<u8> {
+    let body = frame.strip_prefix(REPLY_START)?;
+    let id_len = body.find(|c: char| !c.is_ascii_digit())?;
+    body[..id_len].parse().ok()
+}
+
+/// Split reply `*<id><cmd><value>\r` into id and the raw value
+///
+/// `cmd` has to directly follow the id.
+/// This prevents stale or unexpected replies from being parsed as the expected one
+pub fn split_reply<'a>(frame: &'a str, cmd: &str) -> Result<(u8, &'a str), ProtocolError> {
+    let body = reply_body(frame)?;
+    let id_len = body
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(body.len());
+    let (id, rest) = body.split_at(id_len);
+    let id: u8 = id.parse().map_err(|_| ProtocolError::InvalidId)?;
+    let value = rest
+        .strip_prefix(cmd)
+        .ok_or(ProtocolError::UnexpectedCommand)?;
+    Ok((id, value))
+}
+
+/// Parse reply `*<id><cmd><value>\r` into id and numeric value
+///
+/// # Example
+///
+/// ```
+/// use lss_driver::protocol::parse_reply;
+/// assert_eq!(parse_reply("*5QV11200\r", "QV"), Ok((5, 11200)));
+/// ```
+pub fn parse_reply(frame: &str, cmd: &str) -> Result<(u8, i32), ProtocolError> {
+    let (id, value) = split_reply(frame, cmd)?;
+    if value.is_empty() {
+        return Err(ProtocolError::MissingValue);
+    }
+    let value = value.parse().map_err(|_| ProtocolError::InvalidValue)?;
+    Ok((id, value))
+}
+
+/// Parse numeric value following the last `cmd` in the reply without parsing the id
+///
+/// Useful for replies that don't contain an ID such as QID
+pub fn parse_reply_value(frame: &str, cmd: &str) -> Result<i32, ProtocolError> {
+    reply_body(frame)?
+        .rsplit(cmd)
+        .next()
+        .ok_or(ProtocolError::MissingValue)?
+        .parse()
+        .map_err(|_| ProtocolError::InvalidValue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_command() {
+        let mut buffer = [0_u8; 32];
+        let len = encode_command(&mut buffer, 1, "QV", None, &[]).unwrap();
+        assert_eq!(&buffer[..len], b"#1QV\r");
+        let len =
+            encode_command(&mut buffer, 3, "D", Some(-50), &[("SD", 200), ("CH", 400)]).unwrap();
+        assert_eq!(&buffer[..len], b"#3D-50SD200CH400\r");
+    }
+
+    #[test]
+    fn encode_fails_on_small_buffer() {
+        let mut buffer = [0_u8; 4];
+        assert_eq!(
+            encode_command(&mut buffer, 1, "QV", None, &[]),
+            Err(ProtocolError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn parses_reply() {
+        assert_eq!(parse_reply("*12QD-900\r", "QD"), Ok((12, -900)));
+        assert_eq!(split_reply("*5QF\r", "QF"), Ok((5, "")));
+        assert_eq!(
+            parse_reply("*5QF\r", "QF"),
+            Err(ProtocolError::MissingValue)
+        );
+        assert_eq!(
+            parse_reply("5QF1\r", "QF"),
+            Err(ProtocolError::MissingPrefix)
+        );
+        assert_eq!(parse_reply("*QF1\r", "QF"), Err(ProtocolError::InvalidId));
+        assert_eq!(
+            parse_reply("*5QDT1\r", "QD"),
+            Err(ProtocolError::InvalidValue)
+        );
+        assert_eq!(
+            parse_reply("*5QT1\r", "QD"),
+            Err(ProtocolError::UnexpectedCommand)
+        );
+    }
+
+    #[test]
+    fn parses_reply_without_id() {
+        assert_eq!(reply_id("*QID5\r"), None);
+        assert_eq!(reply_id("*7QID5\r"), Some(7));
+        assert_eq!(parse_reply_value("*QID5\r", "QID"), Ok(5));
+    }
+}
@@ -0,0 +1,27 @@
+use approx::assert_relative_eq;
+use async_std::io::{ReadExt, WriteExt};
+use async_std::net::{TcpListener, TcpStream};
+use lss_driver::{LSSDriver, LssDriverError};
+
+#[test]
+fn query_without_tokio_runtime() {
+    async_std::task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let bridge = async_std::task::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0_u8; 64];
+            let read = socket.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..read], b"#5QV\r");
+            socket.write_all(b"*5QV11200\r").await.unwrap();
+            socket
+        });
+        let stream = TcpStream::connect(address).await.unwrap();
+        let mut driver = LSSDriver::with_io(stream);
+        let voltage = driver.query_voltage(5).await.unwrap();
+        assert_relative_eq!(voltage, 11.2);
+        let _socket = bridge.await;
+        let result = driver.query_voltage(5).await;
+        assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+    });
+}
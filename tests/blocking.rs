@@ -0,0 +1,23 @@
+use approx::assert_relative_eq;
+use lss_driver::blocking::LSSDriver;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+#[test]
+fn query_without_async_runtime() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let bridge = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+        let mut buffer = [0_u8; 64];
+        let read = socket.read(&mut buffer).unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        socket.write_all(b"*5QV11200\r").unwrap();
+        socket
+    });
+    let mut driver = LSSDriver::connect_tcp(address).unwrap();
+    let voltage = driver.query_voltage(5).unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    bridge.join().unwrap();
+}
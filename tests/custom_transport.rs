@@ -0,0 +1,39 @@
+use lss_driver::{async_trait, FramedDriver, LSSDriver, LssCommand, LssDriverError, LssResponse};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Transport answering from a script and recording every frame written
+struct ScriptedTransport {
+    sent: Arc<Mutex<Vec<String>>>,
+    replies: VecDeque<String>,
+}
+
+#[async_trait]
+impl FramedDriver for ScriptedTransport {
+    async fn send(&mut self, command: LssCommand) -> Result<(), LssDriverError> {
+        self.sent.lock().unwrap().push(command.as_str().to_owned());
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<LssResponse, LssDriverError> {
+        self.replies
+            .pop_front()
+            .map(LssResponse::new)
+            .ok_or(LssDriverError::TimeoutError)
+    }
+}
+
+#[tokio::test]
+async fn driver_runs_over_custom_transport() {
+    let sent = Arc::new(Mutex::new(vec![]));
+    let mut driver = LSSDriver::with_driver(Box::new(ScriptedTransport {
+        sent: sent.clone(),
+        replies: VecDeque::from(vec!["*3QD450\r".to_owned()]),
+    }));
+    driver.move_to_position(3, 45.0).await.unwrap();
+    let position = driver.query_position(3).await.unwrap();
+    assert_eq!(position, 45.0);
+    let missing = driver.query_position(3).await;
+    assert!(matches!(missing, Err(LssDriverError::TimeoutError)));
+    assert_eq!(*sent.lock().unwrap(), vec!["#3D450\r", "#3QD\r", "#3QD\r"]);
+}
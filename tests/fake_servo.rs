@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+use approx::assert_relative_eq;
+use lss_driver::testutil::FakeServo;
+use lss_driver::{LedColor, LssCommand, LssDriverError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn query_round_trip() {
+    let (mut driver, _servo) = FakeServo::spawn(|command| match command {
+        "#5QV\r" => Some("*5QV11200\r".to_owned()),
+        "#5QT\r" => Some("*5QT441\r".to_owned()),
+        _ => None,
+    })
+    .unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    let temperature = driver.query_temperature(5).await.unwrap();
+    assert_relative_eq!(temperature, 44.1);
+}
+
+#[tokio::test]
+async fn commands_are_received() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let servo_received = received.clone();
+    let (mut driver, _servo) = FakeServo::spawn(move |command| {
+        servo_received.lock().unwrap().push(command.to_owned());
+        match command {
+            "#2QLED\r" => Some("*2QLED3\r".to_owned()),
+            _ => None,
+        }
+    })
+    .unwrap();
+    driver.limp(1).await.unwrap();
+    driver.set_color(2, LedColor::Blue).await.unwrap();
+    let color = driver.query_color(2).await.unwrap();
+    assert_eq!(color, LedColor::Blue);
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec!["#1L\r", "#2LED3\r", "#2QLED\r"]
+    );
+}
+
+#[tokio::test]
+async fn missing_response_times_out() {
+    let (mut driver, _servo) = FakeServo::spawn(|_| None).unwrap();
+    let result = driver.query_voltage(5).await;
+    assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+}
+
+#[tokio::test]
+async fn extra_broadcast_replies_are_discarded() {
+    let (mut driver, _servo) = FakeServo::spawn(|command| match command {
+        "#254QID\r" => Some("*QID1\r*QID2\r".to_owned()),
+        "#2QV\r" => Some("*2QV11200\r".to_owned()),
+        _ => None,
+    })
+    .unwrap();
+    driver.limp(lss_driver::BROADCAST_ID).await.unwrap();
+    let id = driver.query_id(lss_driver::BROADCAST_ID).await.unwrap();
+    assert_eq!(id, 1);
+    let voltage = driver.query_voltage(2).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+}
+
+#[tokio::test]
+async fn receive_timeout_is_configurable() {
+    let (mut driver, _servo) = FakeServo::spawn(|_| None).unwrap();
+    let previous = driver.set_receive_timeout(Duration::from_millis(100));
+    assert_eq!(previous, Duration::from_millis(10));
+    let start = Instant::now();
+    let result = driver.query_voltage(5).await;
+    assert!(matches!(result, Err(LssDriverError::TimeoutError)));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn close_flushes_commands() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let servo_received = received.clone();
+    let (mut driver, _servo) = FakeServo::spawn(move |command| {
+        servo_received.lock().unwrap().push(command.to_owned());
+        None
+    })
+    .unwrap();
+    driver.limp(1).await.unwrap();
+    driver.flush().await.unwrap();
+    driver.close().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(*received.lock().unwrap(), vec!["#1L\r"]);
+}
+
+#[tokio::test]
+async fn split_driver_pipelines_queries() {
+    let (driver, _servo) = FakeServo::spawn(|command| match command {
+        "#1QV\r" => Some("*1QV11200\r".to_owned()),
+        "#2QV\r" => Some("*2QV7400\r".to_owned()),
+        _ => None,
+    })
+    .unwrap();
+    let (sender, mut receiver) = driver.split();
+    sender.send(LssCommand::simple(1, "QV")).await.unwrap();
+    sender.send(LssCommand::simple(2, "QV")).await.unwrap();
+    let first = receiver.recv().await.unwrap().unwrap();
+    let second = receiver.recv().await.unwrap().unwrap();
+    assert_eq!(first.separate("QV").unwrap(), (1, 11200));
+    assert_eq!(second.separate("QV").unwrap(), (2, 7400));
+}
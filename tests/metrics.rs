@@ -0,0 +1,59 @@
+use lss_driver::{
+    async_trait, encode_metrics, FramedDriver, LSSDriver, LastKnown, LssCommand, LssDriverError,
+    LssResponse, MetricsServer,
+};
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Servo always reporting the same voltage
+struct VoltageTransport {
+    pending: Option<u8>,
+}
+
+#[async_trait]
+impl FramedDriver for VoltageTransport {
+    async fn send(&mut self, command: LssCommand) -> Result<(), LssDriverError> {
+        self.pending = Some(command.id());
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<LssResponse, LssDriverError> {
+        match self.pending.take() {
+            Some(id) => Ok(LssResponse::new(format!("*{}QV11200\r", id))),
+            None => Err(LssDriverError::TimeoutError),
+        }
+    }
+}
+
+async fn scrape(server: &MetricsServer, path: &str) -> String {
+    let mut stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn serves_telemetry_and_bus_metrics() {
+    let handle = LSSDriver::with_driver(Box::new(VoltageTransport { pending: None })).spawn();
+    let bus = handle.bus_metrics().await.unwrap();
+    handle.query_voltage(3).await.unwrap();
+    let telemetry: HashMap<u8, LastKnown> = handle
+        .run(|driver| Box::pin(async move { [(3, driver.last_known(3))].into_iter().collect() }))
+        .await
+        .unwrap();
+    let server = MetricsServer::bind("127.0.0.1:0", move || {
+        encode_metrics(&telemetry, Some(&bus))
+    })
+    .await
+    .unwrap();
+    let response = scrape(&server, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("lss_servo_voltage_volts{id=\"3\"} 11.2\n"));
+    assert!(response.contains("lss_bus_commands_sent_total 1\n"));
+    assert!(response.contains("lss_bus_responses_received_total 1\n"));
+    let response = scrape(&server, "/").await;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
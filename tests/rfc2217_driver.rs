@@ -0,0 +1,102 @@
+use approx::assert_relative_eq;
+use lss_driver::{LSSDriver, LssDriverError};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const IAC: u8 = 255;
+const WILL: u8 = 251;
+const WONT: u8 = 252;
+const DO: u8 = 253;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const BINARY: u8 = 0;
+const TERMINAL_TYPE: u8 = 24;
+const COM_PORT_OPTION: u8 = 44;
+
+/// Read from the client until `received` ends with `suffix`
+async fn read_until(socket: &mut TcpStream, received: &mut Vec<u8>, suffix: &[u8]) {
+    let mut buffer = [0_u8; 128];
+    while !received.ends_with(suffix) {
+        let read = socket.read(&mut buffer).await.unwrap();
+        assert_ne!(read, 0);
+        received.extend_from_slice(&buffer[..read]);
+    }
+}
+
+fn count(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .filter(|window| *window == needle)
+        .count()
+}
+
+#[tokio::test]
+async fn negotiates_and_queries() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut received = vec![];
+        let baud_rate = [IAC, SB, COM_PORT_OPTION, 1, 0, 1, 194, 0, IAC, SE];
+        read_until(&mut socket, &mut received, &baud_rate).await;
+        let mut reply = vec![IAC, DO, COM_PORT_OPTION, IAC, WILL, BINARY];
+        reply.extend_from_slice(&[IAC, DO, TERMINAL_TYPE]);
+        reply.extend_from_slice(&[IAC, SB, COM_PORT_OPTION, 101, 0, 1, 194, 0, IAC, SE]);
+        socket.write_all(&reply).await.unwrap();
+        read_until(&mut socket, &mut received, b"#5QV\r").await;
+        socket.write_all(b"*5QV11200\r").await.unwrap();
+        (received, socket)
+    });
+    let mut driver = LSSDriver::connect_rfc2217(address, 115200).await.unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    let (received, _socket) = server.await.unwrap();
+    assert!(received.starts_with(&[
+        IAC,
+        WILL,
+        BINARY,
+        IAC,
+        DO,
+        BINARY,
+        IAC,
+        WILL,
+        COM_PORT_OPTION
+    ]));
+    // refuses unknown options exactly once and doesn't repeat agreed ones
+    assert_eq!(count(&received, &[IAC, WONT, TERMINAL_TYPE]), 1);
+    assert_eq!(count(&received, &[IAC, DO, BINARY]), 1);
+    assert_eq!(count(&received, &[IAC, WILL, COM_PORT_OPTION]), 1);
+}
+
+#[tokio::test]
+async fn fails_when_baud_rate_is_not_acknowledged() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut received = vec![];
+        let baud_rate = [IAC, SB, COM_PORT_OPTION, 1, 0, 1, 194, 0, IAC, SE];
+        read_until(&mut socket, &mut received, &baud_rate).await;
+        // server only supports 9600
+        let reply = [IAC, SB, COM_PORT_OPTION, 101, 0, 0, 37, 128, IAC, SE];
+        socket.write_all(&reply).await.unwrap();
+        socket
+    });
+    let result = LSSDriver::connect_rfc2217(address, 115200).await;
+    assert!(matches!(
+        result,
+        Err(LssDriverError::UnsupportedBaudRate(115200))
+    ));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn rejects_baud_rate_servos_do_not_support() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let result = LSSDriver::connect_rfc2217(address, 115201).await;
+    assert!(matches!(
+        result,
+        Err(LssDriverError::UnsupportedBaudRate(115201))
+    ));
+}
@@ -0,0 +1,43 @@
+use approx::assert_relative_eq;
+use lss_driver::LSSDriver;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn query_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let bridge = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer = [0_u8; 64];
+        let read = socket.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        socket.write_all(b"*5QV11200\r").await.unwrap();
+        socket
+    });
+    let mut driver = LSSDriver::connect_tcp(address).await.unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    bridge.await.unwrap();
+}
+
+#[tokio::test]
+async fn stale_reply_is_discarded_before_query() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let bridge = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        socket.write_all(b"*5QV9000\r").await.unwrap();
+        let mut buffer = [0_u8; 64];
+        let read = socket.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        socket.write_all(b"*5QV11200\r").await.unwrap();
+        socket
+    });
+    let mut driver = LSSDriver::connect_tcp(address).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    bridge.await.unwrap();
+}
@@ -0,0 +1,67 @@
+use approx::assert_relative_eq;
+use lss_driver::{LSSDriver, LssDriverError, RetryPolicy};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+#[tokio::test]
+async fn query_over_udp_with_split_reply() {
+    let bridge = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = bridge.local_addr().unwrap();
+    let bridge = tokio::spawn(async move {
+        let mut buffer = [0_u8; 64];
+        let (read, peer) = bridge.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        bridge.send_to(b"*5QV11", peer).await.unwrap();
+        bridge.send_to(b"200\r", peer).await.unwrap();
+    });
+    let mut driver = LSSDriver::connect_udp(address).await.unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    bridge.await.unwrap();
+}
+
+#[tokio::test]
+async fn query_over_ipv6() {
+    let bridge = UdpSocket::bind("[::1]:0").await.unwrap();
+    let address = bridge.local_addr().unwrap();
+    let bridge = tokio::spawn(async move {
+        let mut buffer = [0_u8; 64];
+        let (read, peer) = bridge.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        bridge.send_to(b"*5QV11200\r", peer).await.unwrap();
+    });
+    let mut driver = LSSDriver::connect_udp(address).await.unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    bridge.await.unwrap();
+}
+
+#[tokio::test]
+async fn resends_unacknowledged_command() {
+    let bridge = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = bridge.local_addr().unwrap();
+    let bridge = tokio::spawn(async move {
+        let mut buffer = [0_u8; 64];
+        // first attempt is lost
+        let (read, _) = bridge.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"\0\0#5QV\r");
+        let (read, peer) = bridge.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"\0\0#5QV\r");
+        bridge.send_to(b"\0\0*5QV11", peer).await.unwrap();
+        bridge.send_to(b"\x00\x00200\r", peer).await.unwrap();
+        let (read, peer) = bridge.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"\0\x01#5L\r");
+        bridge.send_to(b"\0\x01", peer).await.unwrap();
+        bridge
+    });
+    let resend = RetryPolicy::new(2, Duration::from_millis(50));
+    let mut driver = LSSDriver::connect_udp_sequenced(address, resend)
+        .await
+        .unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    driver.limp(5).await.unwrap();
+    let _bridge = bridge.await.unwrap();
+    let result = driver.limp(5).await;
+    assert!(matches!(result, Err(LssDriverError::SendingError)));
+}
@@ -0,0 +1,26 @@
+#![cfg(unix)]
+
+use approx::assert_relative_eq;
+use lss_driver::LSSDriver;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+
+#[tokio::test]
+async fn query_over_unix_socket() {
+    let path = std::env::temp_dir().join(format!("lss_driver_test_{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let daemon = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer = [0_u8; 64];
+        let read = socket.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..read], b"#5QV\r");
+        socket.write_all(b"*5QV11200\r").await.unwrap();
+        socket
+    });
+    let mut driver = LSSDriver::connect_unix(&path).await.unwrap();
+    let voltage = driver.query_voltage(5).await.unwrap();
+    assert_relative_eq!(voltage, 11.2);
+    daemon.await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+}